@@ -1,6 +1,12 @@
-use crate::scanner::{ScanConfig, ScanResults};
-use std::path::PathBuf;
+use crate::baseline::Baseline;
+use crate::patterns::Severity;
+use crate::review::ReviewStore;
+use crate::scanner::{ScanConfig, ScanPhase, ScanResults, Summary};
+use crate::theme::Theme;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum AppState {
@@ -18,18 +24,52 @@ pub struct App {
     pub entries: Vec<DirEntry>,
     pub selected_index: usize,
     pub scroll_offset: usize,
+    // List dotfiles and dot-directories too; scans always walk them
+    pub include_hidden: bool,
 
     // Scan config
-    pub include_node_modules: bool,
+    pub config: ScanConfig,
+    // `--baseline` and `--blame`, applied to the results of every scan
+    pub baseline: Option<Baseline>,
+    pub allow_suppress_critical: bool,
+    pub blame: bool,
 
     // Scanning state
     pub scan_progress: Arc<Mutex<ScanProgress>>,
     pub scan_results: Option<ScanResults>,
     pub scan_path: Option<PathBuf>,
+    // The running scan is a rescan of `scan_path` forced to include node_modules
+    pub deep_scan: bool,
+    // When the latest scan started; a refresh rescans files modified after it
+    pub last_scan: Option<SystemTime>,
+    // Results a running refresh merges its findings into
+    refreshing: Option<ScanResults>,
 
     // Results navigation
     pub results_scroll: usize,
     pub selected_finding: usize,
+    // Rows available to the findings list, updated on every draw
+    pub results_height: usize,
+    // One header line per finding instead of the full details
+    pub compact_results: bool,
+    // Findings expanded (in compact mode) or collapsed (otherwise) one by one
+    pub toggled_findings: HashSet<usize>,
+    // Severities left out of the findings list; the results themselves stay complete
+    pub hidden_severities: HashSet<Severity>,
+
+    // Palette, switchable at runtime
+    pub theme: Theme,
+
+    // Findings marked as reviewed, persisted across rescans
+    pub reviews: ReviewStore,
+    // `ReviewStore::root_key` of the scan the results come from
+    pub review_root: String,
+
+    // Brief confirmation shown in the footer until the next key press
+    pub status_message: Option<String>,
+    // Kept open for the app's lifetime: on X11/Wayland the copied text is only
+    // served while the clipboard handle is alive
+    clipboard: Option<arboard::Clipboard>,
 }
 
 #[derive(Clone)]
@@ -41,6 +81,7 @@ pub struct DirEntry {
 
 #[derive(Default, Clone)]
 pub struct ScanProgress {
+    pub phase: ScanPhase,
     pub current: usize,
     pub total: usize,
     pub current_file: String,
@@ -48,11 +89,17 @@ pub struct ScanProgress {
 }
 
 impl App {
-    pub fn new(initial_path: Option<PathBuf>, include_node_modules: bool) -> anyhow::Result<Self> {
+    pub fn new(initial_path: Option<PathBuf>, config: ScanConfig) -> anyhow::Result<Self> {
         let current_path = initial_path.unwrap_or_else(|| {
             std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"))
         });
 
+        let (reviews, status_message) = match ReviewStore::load(ReviewStore::default_path()) {
+            Ok(reviews) => (reviews, None),
+            // Leave the broken file alone: marks made now are kept in memory only
+            Err(e) => (ReviewStore::default(), Some(format!("{:#}; review marks won't be saved", e))),
+        };
+
         let mut app = Self {
             state: AppState::SelectFolder,
             should_quit: false,
@@ -60,12 +107,28 @@ impl App {
             entries: Vec::new(),
             selected_index: 0,
             scroll_offset: 0,
-            include_node_modules,
+            include_hidden: false,
+            config,
+            baseline: None,
+            allow_suppress_critical: false,
+            blame: false,
             scan_progress: Arc::new(Mutex::new(ScanProgress::default())),
             scan_results: None,
             scan_path: None,
+            deep_scan: false,
+            last_scan: None,
+            refreshing: None,
             results_scroll: 0,
             selected_finding: 0,
+            results_height: 24,
+            compact_results: false,
+            toggled_findings: HashSet::new(),
+            hidden_severities: HashSet::new(),
+            theme: Theme::default(),
+            reviews,
+            review_root: String::new(),
+            status_message,
+            clipboard: None,
         };
 
         app.refresh_entries()?;
@@ -94,8 +157,8 @@ impl App {
                 let name = entry.file_name().to_string_lossy().to_string();
                 let is_dir = path.is_dir();
 
-                // Skip hidden files/dirs except ..
-                if name.starts_with('.') && name != ".." {
+                // Skip hidden files/dirs except .. unless asked to list them
+                if name.starts_with('.') && name != ".." && !self.include_hidden {
                     continue;
                 }
 
@@ -109,8 +172,8 @@ impl App {
             }
 
             // Sort alphabetically
-            dirs.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-            files.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+            dirs.sort_by_key(|a| a.name.to_lowercase());
+            files.sort_by_key(|a| a.name.to_lowercase());
 
             self.entries.extend(dirs);
             self.entries.extend(files);
@@ -181,27 +244,63 @@ impl App {
     }
 
     pub fn start_scan(&mut self) {
+        // Use the selected/highlighted folder, not the current view folder
+        let path = self.get_selected_path();
+        self.deep_scan = false;
+        self.config.modified_since = None;
+        self.scan(path);
+    }
+
+    /// Rescan only the files modified since the last scan started and merge their
+    /// findings into the current results
+    pub fn refresh_changed(&mut self) {
+        let (Some(path), Some(since)) = (self.scan_path.clone(), self.last_scan) else {
+            return;
+        };
+        if self.scan_results.is_none() {
+            return;
+        }
+        self.refreshing = self.scan_results.take();
+        self.config.modified_since = Some(since);
+        self.scan(path);
+    }
+
+    /// Rescan the same folder with node_modules included, to follow up on results
+    pub fn deep_rescan(&mut self) {
+        let Some(path) = self.scan_path.clone() else {
+            return;
+        };
+        self.config.include_node_modules = true;
+        self.deep_scan = true;
+        self.config.modified_since = None;
+        self.scan(path);
+    }
+
+    fn scan(&mut self, path: PathBuf) {
         self.state = AppState::Scanning;
         self.scan_results = None;
+        self.toggled_findings.clear();
+        if self.config.modified_since.is_none() {
+            self.refreshing = None;
+        }
+        // Taken before the walk, so files changed while scanning count as changed
+        self.last_scan = Some(SystemTime::now());
 
         // Reset progress
         if let Ok(mut progress) = self.scan_progress.lock() {
             *progress = ScanProgress::default();
         }
 
-        // Use the selected/highlighted folder, not the current view folder
-        let path = self.get_selected_path();
         self.scan_path = Some(path.clone());
-        let config = ScanConfig {
-            include_node_modules: self.include_node_modules,
-        };
+        let config = self.config.clone();
         let progress = self.scan_progress.clone();
 
         // Spawn scanning thread
         std::thread::spawn(move || {
             let callback_progress = progress.clone();
-            let callback = Box::new(move |current: usize, total: usize, file: &str| {
+            let callback = Box::new(move |phase: ScanPhase, current: usize, total: usize, file: &str| {
                 if let Ok(mut p) = callback_progress.lock() {
+                    p.phase = phase;
                     p.current = current;
                     p.total = total;
                     p.current_file = file.to_string();
@@ -227,15 +326,34 @@ impl App {
 
         if finished && self.scan_results.is_none() {
             // Perform scan again to get results (since thread result isn't easily accessible)
-            let config = ScanConfig {
-                include_node_modules: self.include_node_modules,
-            };
             let scan_path = self.scan_path.clone().unwrap_or_else(|| self.current_path.clone());
-            if let Ok(results) =
-                crate::scanner::scan_directory_sync(&scan_path, &config)
+            if let Ok(mut results) =
+                crate::scanner::scan_directory_sync(&scan_path, &self.config)
             {
+                if let Some(baseline) = &self.baseline {
+                    let report = baseline.apply(&mut results, self.allow_suppress_critical);
+                    let mut message = format!("Baseline suppressed {} findings", report.suppressed);
+                    if report.critical_suppressed > 0 {
+                        message += &format!(", {} of them Critical", report.critical_suppressed);
+                    }
+                    if report.critical_kept > 0 {
+                        message += &format!("; {} baselined Critical findings are still shown", report.critical_kept);
+                    }
+                    self.status_message = Some(message);
+                    results.baseline = Some(report);
+                }
+                if self.blame {
+                    crate::blame::annotate(&mut results, &scan_path);
+                }
+                if let (Some(previous), Some(since)) = (self.refreshing.take(), self.config.modified_since.take()) {
+                    results = merge_refresh(previous, results, since, &scan_path);
+                }
+                self.selected_finding = self.selected_finding.min(results.findings.len().saturating_sub(1));
+                self.adjust_results_scroll();
+                self.review_root = ReviewStore::root_key(&scan_path);
                 self.scan_results = Some(results.clone());
                 self.state = AppState::Results;
+                self.keep_selection_visible();
                 return Some(results);
             }
         }
@@ -243,32 +361,181 @@ impl App {
     }
 
     pub fn toggle_node_modules(&mut self) {
-        self.include_node_modules = !self.include_node_modules;
+        self.config.include_node_modules = !self.config.include_node_modules;
     }
 
     pub fn results_up(&mut self) {
-        if self.selected_finding > 0 {
-            self.selected_finding -= 1;
+        if let Some(&index) = self.visible_findings().iter().rev().find(|&&i| i < self.selected_finding) {
+            self.selected_finding = index;
             self.adjust_results_scroll();
         }
     }
 
     pub fn results_down(&mut self) {
+        if let Some(&index) = self.visible_findings().iter().find(|&&i| i > self.selected_finding) {
+            self.selected_finding = index;
+            self.adjust_results_scroll();
+        }
+    }
+
+    pub fn select_finding(&mut self, index: usize) {
         if let Some(results) = &self.scan_results {
-            if self.selected_finding < results.findings.len().saturating_sub(1) {
-                self.selected_finding += 1;
+            if results.findings.get(index).is_some_and(|f| !self.hidden_severities.contains(&f.severity)) {
+                self.selected_finding = index;
                 self.adjust_results_scroll();
             }
         }
     }
 
+    /// Indices of the findings shown under the severity filter, in results order
+    pub fn visible_findings(&self) -> Vec<usize> {
+        let Some(results) = &self.scan_results else {
+            return Vec::new();
+        };
+        (0..results.findings.len())
+            .filter(|&i| !self.hidden_severities.contains(&results.findings[i].severity))
+            .collect()
+    }
+
+    /// Show or hide the findings of one severity
+    pub fn toggle_severity(&mut self, severity: Severity) {
+        let hidden = self.hidden_severities.insert(severity);
+        if !hidden {
+            self.hidden_severities.remove(&severity);
+        }
+        self.keep_selection_visible();
+        self.status_message = Some(format!(
+            "{} findings {}",
+            severity.as_str(),
+            if hidden { "hidden" } else { "shown" }
+        ));
+    }
+
+    /// Move the selection off a hidden finding: to the next shown one, else the last
+    fn keep_selection_visible(&mut self) {
+        let visible = self.visible_findings();
+        if let Some(&index) = visible.iter().find(|&&i| i >= self.selected_finding).or(visible.last()) {
+            self.selected_finding = index;
+        }
+        self.results_scroll = self.results_scroll.min(self.selected_finding);
+        self.adjust_results_scroll();
+    }
+
+    /// Keep the selected finding on screen, whatever the heights of the ones above it
     fn adjust_results_scroll(&mut self) {
-        let visible_height = 8; // Approximate visible findings (each takes ~3 lines)
         if self.selected_finding < self.results_scroll {
             self.results_scroll = self.selected_finding;
-        } else if self.selected_finding >= self.results_scroll + visible_height {
-            self.results_scroll = self.selected_finding - visible_height + 1;
         }
+        while self.results_scroll < self.selected_finding
+            && (self.results_scroll..=self.selected_finding)
+                .map(|i| self.finding_height(i))
+                .sum::<usize>()
+                > self.results_height
+        {
+            self.results_scroll += 1;
+        }
+    }
+
+    pub fn is_expanded(&self, index: usize) -> bool {
+        self.compact_results == self.toggled_findings.contains(&index)
+    }
+
+    /// Rows a finding takes in the results list; keep in sync with `draw_results`
+    pub fn finding_height(&self, index: usize) -> usize {
+        let Some(finding) = self.scan_results.as_ref().and_then(|r| r.findings.get(index)) else {
+            return 0;
+        };
+        if self.hidden_severities.contains(&finding.severity) {
+            return 0;
+        }
+        if !self.is_expanded(index) {
+            return 1;
+        }
+        let details = if index == self.selected_finding {
+            finding.locations.len() + finding.blame.is_some() as usize + finding.remediation.is_some() as usize
+        } else {
+            0
+        };
+        2 + finding.context.is_some() as usize + details
+    }
+
+    /// Switch every finding between the header line and the full details
+    pub fn toggle_compact(&mut self) {
+        self.compact_results = !self.compact_results;
+        self.toggled_findings.clear();
+        self.adjust_results_scroll();
+        self.status_message = Some(
+            if self.compact_results { "Compact view" } else { "Expanded view" }.to_string(),
+        );
+    }
+
+    /// Expand or collapse just the selected finding
+    pub fn toggle_expanded(&mut self) {
+        if !self.toggled_findings.remove(&self.selected_finding) {
+            self.toggled_findings.insert(self.selected_finding);
+        }
+        self.adjust_results_scroll();
+    }
+
+    pub fn cycle_theme(&mut self) {
+        self.theme = Theme::new(self.theme.name.next());
+        self.status_message = Some(format!("Theme: {}", self.theme.name.as_str()));
+    }
+
+    /// Toggle the reviewed mark on the selected finding and persist it
+    pub fn toggle_reviewed(&mut self) {
+        let Some(results) = &self.scan_results else {
+            return;
+        };
+        let Some(finding) = results.findings.get(self.selected_finding) else {
+            return;
+        };
+
+        let key = finding.review_key(Path::new(&results.scan_path));
+        self.reviews.toggle(&self.review_root, &key);
+        if let Err(e) = self.reviews.save() {
+            self.status_message = Some(format!("{:#}", e));
+        }
+    }
+
+    pub fn is_reviewed(&self, finding: &crate::scanner::Finding) -> bool {
+        self.scan_results
+            .as_ref()
+            .is_some_and(|r| {
+                self.reviews
+                    .is_reviewed(&self.review_root, &finding.review_key(Path::new(&r.scan_path)))
+            })
+    }
+
+    /// Copy the selected finding's path (with `:line` if `with_line`) to the clipboard
+    pub fn copy_selected_path(&mut self, with_line: bool) {
+        let Some(finding) = self
+            .scan_results
+            .as_ref()
+            .and_then(|r| r.findings.get(self.selected_finding))
+        else {
+            return;
+        };
+        let text = match (with_line, finding.line) {
+            (true, Some(line)) => format!("{}:{}", finding.path, line),
+            _ => finding.path.clone(),
+        };
+
+        if self.clipboard.is_none() {
+            self.clipboard = arboard::Clipboard::new().ok();
+        }
+        self.status_message = Some(match &mut self.clipboard {
+            Some(clipboard) => match clipboard.set_text(text.clone()) {
+                Ok(()) => format!("Copied {}", text),
+                Err(e) => format!("Clipboard error: {}", e),
+            },
+            None => "No clipboard available".to_string(),
+        });
+    }
+
+    /// A refresh is running (or its results are about to be merged)
+    pub fn is_refreshing(&self) -> bool {
+        self.refreshing.is_some()
     }
 
     pub fn back_to_folder_select(&mut self) {
@@ -277,5 +544,34 @@ impl App {
         self.scan_path = None;
         self.selected_finding = 0;
         self.results_scroll = 0;
+        self.toggled_findings.clear();
+    }
+}
+
+/// Fold a changed-files refresh into the previous results: findings and errors of
+/// files modified (or deleted) since `since` are replaced by the refresh's
+fn merge_refresh(previous: ScanResults, refreshed: ScanResults, since: SystemTime, root: &Path) -> ScanResults {
+    let changed = |path: &str| {
+        let path = Path::new(path);
+        let file = if path.is_relative() && previous.config.relative_paths {
+            root.join(path)
+        } else {
+            path.to_path_buf()
+        };
+        crate::scanner::modified_after(&file, since)
+    };
+
+    let mut findings: Vec<_> = previous.findings.iter().filter(|f| !changed(&f.path)).cloned().collect();
+    findings.extend(refreshed.findings);
+    let mut errors: Vec<_> = previous.errors.iter().filter(|e| !changed(&e.path)).cloned().collect();
+    errors.extend(refreshed.errors);
+
+    let mut summary = Summary::from_findings(&findings);
+    summary.errors = errors.len();
+    ScanResults {
+        findings,
+        summary,
+        errors,
+        ..previous
     }
 }
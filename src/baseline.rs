@@ -0,0 +1,88 @@
+use crate::patterns::Severity;
+use crate::scanner::{Finding, ScanResults, Summary};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Findings accepted earlier (`--baseline`): a previous JSON report whose findings
+/// are left out of later ones. Matched by `fingerprint`; reports written before
+/// fingerprints existed match by rule, path and package or context
+pub struct Baseline {
+    path: String,
+    sha256: String,
+    fingerprints: HashSet<String>,
+    legacy_keys: HashSet<String>,
+}
+
+/// What a baseline did to a scan, echoed into the report as `baseline`. The hash
+/// lets a reviewer notice a baseline that changed between runs
+#[derive(Debug, Clone, Serialize)]
+pub struct BaselineReport {
+    pub path: String,
+    pub sha256: String,
+    pub suppressed: usize,
+    /// Critical findings the baseline lists but that were reported anyway
+    pub critical_kept: usize,
+    /// Critical findings suppressed with `--allow-suppress-critical`
+    pub critical_suppressed: usize,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read(path).with_context(|| format!("Failed to read baseline {}", path.display()))?;
+        let report: serde_json::Value = serde_json::from_slice(&content)
+            .with_context(|| format!("Invalid baseline {}", path.display()))?;
+        let findings: Vec<Finding> = serde_json::from_value(report.get("findings").cloned().unwrap_or_default())
+            .with_context(|| format!("Invalid baseline {}: no findings array", path.display()))?;
+
+        let (fingerprinted, legacy): (Vec<_>, Vec<_>) = findings.iter().partition(|f| !f.fingerprint.is_empty());
+        Ok(Self {
+            path: path.display().to_string(),
+            sha256: hex::encode(Sha256::digest(&content)),
+            fingerprints: fingerprinted.into_iter().map(|f| f.fingerprint.clone()).collect(),
+            legacy_keys: legacy.into_iter().map(legacy_key).collect(),
+        })
+    }
+
+    /// Drop baselined findings from `results`. Critical ones stay unless
+    /// `allow_critical`: whoever can write the baseline shouldn't be able to hide a
+    /// payload with it
+    pub fn apply(&self, results: &mut ScanResults, allow_critical: bool) -> BaselineReport {
+        let before = results.findings.len();
+        let mut critical = 0;
+        results.findings.retain(|finding| {
+            if !self.contains(finding) {
+                return true;
+            }
+            if finding.severity == Severity::Critical {
+                critical += 1;
+                return !allow_critical;
+            }
+            false
+        });
+
+        let errors = results.summary.errors;
+        results.summary = Summary::from_findings(&results.findings);
+        results.summary.errors = errors;
+        BaselineReport {
+            path: self.path.clone(),
+            sha256: self.sha256.clone(),
+            suppressed: before - results.findings.len(),
+            critical_kept: if allow_critical { 0 } else { critical },
+            critical_suppressed: if allow_critical { critical } else { 0 },
+        }
+    }
+
+    fn contains(&self, finding: &Finding) -> bool {
+        self.fingerprints.contains(&finding.fingerprint)
+            || (!self.legacy_keys.is_empty() && self.legacy_keys.contains(&legacy_key(finding)))
+    }
+}
+
+fn legacy_key(finding: &Finding) -> String {
+    let detail = finding.package.as_deref().or(finding.context.as_deref()).unwrap_or_default();
+    format!("{}\u{0}{}\u{0}{}", finding.rule_id, finding.path, detail)
+}
@@ -0,0 +1,127 @@
+use crate::scanner::ScanResults;
+use chrono::{DateTime, FixedOffset};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Who last changed a finding's line (`--blame`), to point responders at the commit
+/// that introduced it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Blame {
+    pub author: String,
+    pub commit: String,
+    /// Author date, RFC 3339 in the author's time zone
+    pub date: String,
+}
+
+/// Attach `blame` to every finding with a line, running `git blame` once per file.
+/// Files outside a repository, untracked files and uncommitted lines are left
+/// without it
+pub fn annotate(results: &mut ScanResults, root: &Path) {
+    let relative = results.config.relative_paths;
+    let resolve = |path: &str| if relative { root.join(path) } else { PathBuf::from(path) };
+
+    let mut lines: BTreeMap<PathBuf, Vec<usize>> = BTreeMap::new();
+    for finding in &results.findings {
+        if let Some(line) = finding.line {
+            lines.entry(resolve(&finding.path)).or_default().push(line);
+        }
+    }
+
+    let blamed: HashMap<PathBuf, HashMap<usize, Blame>> = lines
+        .into_par_iter()
+        .filter_map(|(file, lines)| Some((file.clone(), blame_lines(&file, &lines)?)))
+        .collect();
+
+    for finding in &mut results.findings {
+        let file = resolve(&finding.path);
+        finding.blame = finding
+            .line
+            .and_then(|line| blamed.get(&file)?.get(&line).cloned());
+    }
+}
+
+/// `git blame --porcelain` of the given lines of `file`, keyed by line number
+fn blame_lines(file: &Path, lines: &[usize]) -> Option<HashMap<usize, Blame>> {
+    let dir = file.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let mut command = Command::new("git");
+    command.arg("-C").arg(dir).args(["blame", "--porcelain"]);
+    for line in lines {
+        command.arg("-L").arg(format!("{line},{line}"));
+    }
+    let output = command.arg("--").arg(file.file_name()?).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_porcelain(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Porcelain output starts each line with `<sha> <orig> <final> [<count>]`; the
+/// commit's `author*` headers only follow the first time a commit appears
+fn parse_porcelain(output: &str) -> HashMap<usize, Blame> {
+    #[derive(Default)]
+    struct Commit {
+        author: String,
+        time: i64,
+        tz: String,
+    }
+
+    let mut commits: HashMap<String, Commit> = HashMap::new();
+    let mut line_commits: Vec<(usize, String)> = Vec::new();
+    let mut current: Option<String> = None;
+
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            continue;
+        }
+        let mut fields = line.split(' ');
+        let first = fields.next().unwrap_or_default();
+        if matches!(first.len(), 40 | 64) && first.chars().all(|c| c.is_ascii_hexdigit()) {
+            if let Some(final_line) = fields.nth(1).and_then(|n| n.parse().ok()) {
+                line_commits.push((final_line, first.to_string()));
+                commits.entry(first.to_string()).or_default();
+                current = Some(first.to_string());
+            }
+            continue;
+        }
+        let Some(commit) = current.as_ref().and_then(|sha| commits.get_mut(sha)) else {
+            continue;
+        };
+        let value = line.split_once(' ').map(|(_, v)| v).unwrap_or_default();
+        match first {
+            "author" => commit.author = value.to_string(),
+            "author-time" => commit.time = value.parse().unwrap_or_default(),
+            "author-tz" => commit.tz = value.to_string(),
+            _ => {}
+        }
+    }
+
+    line_commits
+        .into_iter()
+        // Uncommitted lines are attributed to the all-zero commit
+        .filter(|(_, sha)| sha.chars().any(|c| c != '0'))
+        .filter_map(|(line, sha)| {
+            let commit = commits.get(&sha)?;
+            Some((
+                line,
+                Blame {
+                    author: commit.author.clone(),
+                    date: format_date(commit.time, &commit.tz)?,
+                    commit: sha,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Unix time plus git's `+hhmm` offset as RFC 3339
+fn format_date(time: i64, tz: &str) -> Option<String> {
+    let sign = if tz.starts_with('-') { -1 } else { 1 };
+    let digits = tz.trim_start_matches(['+', '-']);
+    let hours: i32 = digits.get(..2)?.parse().ok()?;
+    let minutes: i32 = digits.get(2..4)?.parse().ok()?;
+    let offset = FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))?;
+    Some(DateTime::from_timestamp(time, 0)?.with_timezone(&offset).to_rfc3339())
+}
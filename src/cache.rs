@@ -0,0 +1,97 @@
+use crate::patterns::{
+    COMPROMISED_PACKAGES, HOOK_PATTERNS, IOC_VERSION, MALICIOUS_HASHES, SEQUENCE_RULES,
+    SUSPICIOUS_PATTERNS,
+};
+use crate::scanner::{Finding, ScanConfig};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Findings of earlier scans (`--cache`), keyed by path below the scan root and
+/// reused while the file's SHA256 is unchanged. The header invalidates the whole
+/// cache when the tool, the rules or the settings that shape per-file findings change.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ScanCache {
+    tool_version: String,
+    fingerprint: String,
+    entries: HashMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub sha256: String,
+    pub findings: Vec<Finding>,
+}
+
+impl ScanCache {
+    /// Load the cache at `path`; a missing, unreadable or stale cache starts empty
+    pub fn load(path: &Path, config: &ScanConfig) -> Self {
+        let fingerprint = fingerprint(config);
+        let cache = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<ScanCache>(&content).ok())
+            .filter(|cache| cache.tool_version == env!("CARGO_PKG_VERSION") && cache.fingerprint == fingerprint);
+        cache.unwrap_or_else(|| Self {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            fingerprint,
+            entries: HashMap::new(),
+        })
+    }
+
+    /// Cached findings for `key`, if its content hasn't changed
+    pub fn get(&self, key: &str, sha256: &str) -> Option<&[Finding]> {
+        self.entries
+            .get(key)
+            .filter(|entry| entry.sha256 == sha256)
+            .map(|entry| entry.findings.as_slice())
+    }
+
+    /// Replace the entries with the files of the latest scan, dropping deleted ones
+    pub fn replace_entries(&mut self, entries: HashMap<String, CacheEntry>) {
+        self.entries = entries;
+    }
+
+    /// Write atomically (temp file + rename) so an interrupted scan can't leave a
+    /// truncated cache behind
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write scan cache {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to write scan cache {}", path.display()))
+    }
+}
+
+/// Hash of everything besides a file's content that decides its findings: the
+/// embedded rules and IOCs, the loaded bundle and the per-file scan settings
+fn fingerprint(config: &ScanConfig) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(IOC_VERSION);
+    for rule in SUSPICIOUS_PATTERNS.iter() {
+        hasher.update(format!("{}|{}|{}\n", rule.id, rule.regex.as_str(), rule.severity.as_str()));
+    }
+    for rule in SEQUENCE_RULES.iter() {
+        hasher.update(format!("{}|{}|{}|{:?}\n", rule.setup.as_str(), rule.action.as_str(), rule.severity.as_str(), rule.window));
+    }
+    for rule in HOOK_PATTERNS.iter() {
+        hasher.update(format!("{}|{}|{}\n", rule.id, rule.regex.as_str(), rule.severity.as_str()));
+    }
+    for hash in MALICIOUS_HASHES {
+        hasher.update(hash);
+    }
+    for (name, versions) in COMPROMISED_PACKAGES {
+        hasher.update(format!("{name}@{}\n", versions.join(",")));
+    }
+    hasher.update(format!("{:?}", config.iocs));
+    hasher.update(format!(
+        "{}|{}|{}|{:?}|{:?}",
+        config.deps_only,
+        config.content_only,
+        config.no_hash,
+        config.max_findings_per_file,
+        config.trusted_registries(),
+    ));
+    hex::encode(hasher.finalize())
+}
@@ -0,0 +1,118 @@
+use crate::patterns::{
+    self, Severity, BUILTIN_CHECKS, COMPROMISED_PACKAGES, HOOK_PATTERNS, SEQUENCE_RULES,
+    SUSPICIOUS_PATTERNS,
+};
+use anyhow::{bail, Result};
+
+/// One row of `list` / the body of `explain`
+pub struct RuleInfo {
+    pub id: String,
+    pub severity: Option<Severity>,
+    pub description: String,
+    pub tags: &'static [&'static str],
+    pub remediation: Option<&'static str>,
+}
+
+/// Every rule id a scan can report, table-driven rules first
+pub fn rules() -> Vec<RuleInfo> {
+    let patterns = SUSPICIOUS_PATTERNS.iter().map(|rule| RuleInfo {
+        id: rule.id.to_string(),
+        severity: Some(rule.severity),
+        description: rule.description.to_string(),
+        tags: rule.tags,
+        remediation: rule.remediation,
+    });
+    let sequences = SEQUENCE_RULES.iter().map(|rule| RuleInfo {
+        id: rule.id.to_string(),
+        severity: Some(rule.severity),
+        description: rule.description.to_string(),
+        tags: rule.tags,
+        remediation: rule.remediation,
+    });
+    let hooks = HOOK_PATTERNS.iter().map(|rule| RuleInfo {
+        id: rule.id.to_string(),
+        severity: Some(rule.severity),
+        description: rule.description.to_string(),
+        tags: rule.tags,
+        remediation: Some(patterns::REMEDIATE_HOOK),
+    });
+    let builtin = BUILTIN_CHECKS.iter().map(|(id, description)| RuleInfo {
+        id: id.to_string(),
+        severity: None,
+        description: description.to_string(),
+        tags: &[],
+        remediation: None,
+    });
+    patterns.chain(sequences).chain(hooks).chain(builtin).collect()
+}
+
+/// Look up a rule id; any `custom/…` id maps to the IOC bundle entry
+pub fn find_rule(rule_id: &str) -> Option<RuleInfo> {
+    let rule_id = if rule_id.starts_with("custom/") { "custom/<slug>" } else { rule_id };
+    rules().into_iter().find(|rule| rule.id == rule_id)
+}
+
+pub fn explain(rule_id: &str) -> Result<()> {
+    let Some(rule) = find_rule(rule_id) else {
+        bail!("Unknown rule id '{rule_id}'; run `list` to see all rule ids");
+    };
+    println!("{}", rule.id);
+    println!("  {}", rule.description);
+    if let Some(severity) = rule.severity {
+        println!("  Severity: {}", severity.as_str());
+    }
+    if !rule.tags.is_empty() {
+        println!("  Tags: {}", rule.tags.join(", "));
+    }
+    if let Some(advice) = rule.remediation {
+        println!("  Remediation: {advice}");
+    }
+    Ok(())
+}
+
+pub fn list(packages: bool) {
+    if packages {
+        for (name, versions) in COMPROMISED_PACKAGES {
+            println!("{name} {}", versions.join(", "));
+        }
+        return;
+    }
+    for rule in rules() {
+        let severity = rule.severity.map_or("-", |s| s.as_str());
+        println!("{:<55} {:<8} {}", rule.id, severity, rule.description);
+    }
+}
+
+/// `@scope/name@1.2.3` -> (`@scope/name`, Some(`1.2.3`))
+pub fn split_spec(spec: &str) -> (&str, Option<&str>) {
+    match spec.rfind('@') {
+        Some(at) if at > 0 => (&spec[..at], Some(&spec[at + 1..])),
+        _ => (spec, None),
+    }
+}
+
+/// Print a verdict per package; false if any given version is infected
+pub fn check(specs: &[String]) -> bool {
+    let mut clean = true;
+    for spec in specs {
+        let (name, version) = split_spec(spec);
+        match (patterns::is_package_compromised(name), version) {
+            (None, _) => println!("✓ {spec}: not in the compromised list"),
+            (Some(versions), None) => {
+                println!("! {name}: compromised package; infected versions: {}", versions.join(", "))
+            }
+            (Some(versions), Some(version)) => {
+                if patterns::is_version_compromised(name, version).is_some() {
+                    clean = false;
+                    println!("✗ {spec}: INFECTED");
+                } else {
+                    println!(
+                        "✓ {spec}: not an infected version (infected: {})",
+                        versions.join(", ")
+                    );
+                }
+            }
+        }
+    }
+    clean
+}
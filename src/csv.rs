@@ -0,0 +1,59 @@
+use crate::scanner::ScanResults;
+
+const HEADER: &[&str] = &[
+    "severity",
+    "rule_id",
+    "finding_type",
+    "path",
+    "line",
+    "package",
+    "manifest_scope",
+    "workspace",
+    "description",
+    "context",
+    "matched_line",
+    "fingerprint",
+];
+
+/// Render the findings as CSV (RFC 4180), one row per finding, for spreadsheets
+/// and ad-hoc filtering
+pub fn render(results: &ScanResults) -> String {
+    let mut out = String::new();
+    push_row(&mut out, HEADER.iter().map(|h| h.to_string()));
+
+    for finding in &results.findings {
+        push_row(
+            &mut out,
+            [
+                finding.severity.as_str().to_string(),
+                finding.rule_id.clone(),
+                format!("{:?}", finding.finding_type),
+                finding.path.clone(),
+                finding.line.map(|l| l.to_string()).unwrap_or_default(),
+                finding.package.clone().unwrap_or_default(),
+                finding.manifest_scope.map(|s| s.as_str().to_string()).unwrap_or_default(),
+                finding.workspace.clone().unwrap_or_default(),
+                finding.description.clone(),
+                finding.context.clone().unwrap_or_default(),
+                finding.matched_line.clone().unwrap_or_default(),
+                finding.fingerprint.clone(),
+            ],
+        );
+    }
+    out
+}
+
+fn push_row(out: &mut String, fields: impl IntoIterator<Item = String>) {
+    let fields: Vec<String> = fields.into_iter().map(|f| escape(&f)).collect();
+    out.push_str(&fields.join(","));
+    out.push_str("\r\n");
+}
+
+/// Quote fields containing separators, quotes or line breaks; quotes are doubled
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
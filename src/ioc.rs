@@ -0,0 +1,282 @@
+use crate::patterns::{
+    Severity, COMPROMISED_ACTIONS, COMPROMISED_PACKAGES, IOC_VERSION, MALICIOUS_HASHES, PAYLOAD_SIGNATURES,
+};
+use anyhow::{bail, Context, Result};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Longest pattern accepted from a bundle
+const MAX_PATTERN_LEN: usize = 1_000;
+/// Compiled program and lazy DFA cache limits for bundle patterns. The regex crate
+/// matches in linear time, so the remaining risk from an untrusted pattern is a
+/// blow-up in compiled size (e.g. nested counted repetition), which these bound.
+const PATTERN_SIZE_LIMIT: usize = 1 << 20;
+const PATTERN_DFA_SIZE_LIMIT: usize = 2 << 20;
+const PATTERN_NEST_LIMIT: u32 = 32;
+
+/// Extra indicators loaded from a JSON bundle (`--iocs <file>`) and merged with the
+/// embedded IOC dataset in `patterns.rs`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IocBundle {
+    /// Dataset version, reported alongside the embedded `IOC_VERSION`
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Additional payload content signatures
+    #[serde(default)]
+    pub signatures: Vec<String>,
+    /// Override for how many distinct signatures must match
+    #[serde(default)]
+    pub signature_quorum: Option<usize>,
+    /// Extra line-based content rules
+    #[serde(default)]
+    pub patterns: Vec<CustomRule>,
+    /// Extra compromised GitHub Actions, checked with the embedded ones
+    #[serde(default)]
+    pub compromised_actions: Vec<CompromisedAction>,
+    /// SHA256 of the bundle file, recorded in the report and in `--ioc-lock` files
+    #[serde(skip)]
+    pub sha256: Option<String>,
+    /// Why the installed or pinned bundle couldn't be loaded; the scan goes on
+    /// without it
+    #[serde(skip)]
+    pub load_error: Option<String>,
+}
+
+/// `--ioc-lock` file: the IOC dataset a scan resolved, so later scans of the same
+/// tree use exactly that one or refuse to run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IocLock {
+    /// Embedded dataset version of the build that wrote the lock
+    pub ioc_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_sha256: Option<String>,
+}
+
+/// How complete the IOC dataset behind a scan is, reported as `ioc_status` so a
+/// "clean" result from a degraded dataset doesn't go unnoticed
+#[derive(Debug, Clone, Serialize)]
+pub struct IocStatus {
+    pub malicious_hashes: usize,
+    pub compromised_packages: usize,
+    pub compromised_actions: usize,
+    pub payload_signatures: usize,
+    pub custom_rules: usize,
+    /// What is missing; empty when the dataset is complete
+    pub problems: Vec<String>,
+}
+
+impl IocStatus {
+    pub fn degraded(&self) -> bool {
+        !self.problems.is_empty()
+    }
+}
+
+/// A GitHub Action supplied by a bundle, with the refs (tags, branches or commit
+/// SHAs) that ran malicious code
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompromisedAction {
+    /// `owner/repo`, or `owner/repo/path` for an action in a subdirectory
+    pub name: String,
+    pub refs: Vec<String>,
+}
+
+/// A content rule supplied by a bundle. The regex is compiled (with complexity
+/// limits) when the bundle is loaded.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomRule {
+    pub pattern: String,
+    pub description: String,
+    pub severity: Severity,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(skip)]
+    pub regex: Option<Regex>,
+}
+
+impl CustomRule {
+    /// Compile the pattern, rejecting ones that exceed the complexity limits
+    pub fn compile(&mut self) -> Result<()> {
+        if self.pattern.len() > MAX_PATTERN_LEN {
+            bail!("pattern is longer than {} characters", MAX_PATTERN_LEN);
+        }
+        let regex = RegexBuilder::new(&self.pattern)
+            .size_limit(PATTERN_SIZE_LIMIT)
+            .dfa_size_limit(PATTERN_DFA_SIZE_LIMIT)
+            .nest_limit(PATTERN_NEST_LIMIT)
+            .build()
+            .map_err(|e| match e {
+                regex::Error::CompiledTooBig(_) => anyhow::anyhow!("pattern exceeds the complexity limit"),
+                e => anyhow::anyhow!(e),
+            })?;
+        self.regex = Some(regex);
+        Ok(())
+    }
+}
+
+impl IocBundle {
+    /// The installed bundle, if any. Unlike an explicit `--iocs`, a broken one
+    /// doesn't stop the scan: it is skipped and reported through `status`
+    pub fn load_installed() -> Self {
+        match installed_path().filter(|p| p.exists()) {
+            Some(path) => Self::load(&path).unwrap_or_else(|e| Self {
+                load_error: Some(format!("installed IOC bundle couldn't be loaded: {e:#}")),
+                ..Self::default()
+            }),
+            None => Self::default(),
+        }
+    }
+
+    /// The bundle `update` installed under version `tag`. One that isn't installed
+    /// leaves the embedded IOCs, reported through `status`; one that is installed but
+    /// broken is an error, as with `--iocs`
+    pub fn load_version(tag: &str) -> Result<Self> {
+        let path = versioned_path(tag)?;
+        if !path.exists() {
+            return Ok(Self {
+                load_error: Some(format!(
+                    "pinned IOC bundle version {tag} isn't installed (install it with `shk update`); using the embedded IOCs only"
+                )),
+                ..Self::default()
+            });
+        }
+        Self::load(&path)
+    }
+
+    /// Status of the embedded dataset plus this bundle
+    pub fn status(&self) -> IocStatus {
+        dataset_status(MALICIOUS_HASHES.len(), COMPROMISED_PACKAGES.len(), self)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read(path)
+            .with_context(|| format!("Failed to read IOC bundle {}", path.display()))?;
+        let mut bundle: Self = serde_json::from_slice(&content)
+            .with_context(|| format!("Invalid IOC bundle {}", path.display()))?;
+        bundle.sha256 = Some(hex::encode(Sha256::digest(&content)));
+
+        for rule in &mut bundle.patterns {
+            rule.compile().with_context(|| {
+                format!("Rejected pattern '{}' in IOC bundle {}", rule.pattern, path.display())
+            })?;
+        }
+        Ok(bundle)
+    }
+}
+
+/// `IocStatus` for hash and package lists of the given sizes
+pub fn dataset_status(hashes: usize, packages: usize, bundle: &IocBundle) -> IocStatus {
+    let mut problems = Vec::new();
+    if hashes == 0 {
+        problems.push("malicious hash list is empty: renamed payload files won't be detected".to_string());
+    }
+    if packages == 0 {
+        problems.push("compromised package list is empty: infected dependencies won't be detected".to_string());
+    }
+    if let Some(error) = &bundle.load_error {
+        problems.push(error.clone());
+    }
+    IocStatus {
+        malicious_hashes: hashes,
+        compromised_packages: packages,
+        compromised_actions: COMPROMISED_ACTIONS.len() + bundle.compromised_actions.len(),
+        payload_signatures: PAYLOAD_SIGNATURES.len() + bundle.signatures.len(),
+        custom_rules: bundle.patterns.len(),
+        problems,
+    }
+}
+
+impl IocLock {
+    /// The dataset `bundle` resolved to
+    pub fn of(bundle: &IocBundle) -> Self {
+        Self {
+            ioc_version: IOC_VERSION.to_string(),
+            bundle_version: bundle.version.clone(),
+            bundle_sha256: bundle.sha256.clone(),
+        }
+    }
+
+    /// The lock at `path`, or `None` when there is none yet
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read IOC lock {}", path.display()))?;
+        let lock = serde_json::from_str(&content).with_context(|| format!("Invalid IOC lock {}", path.display()))?;
+        Ok(Some(lock))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json + "\n").with_context(|| format!("Failed to write IOC lock {}", path.display()))
+    }
+
+    /// Fail unless `bundle` is the locked one. A bundle that couldn't be loaded is
+    /// left to `--require-iocs`, like any other degraded dataset
+    pub fn verify(&self, bundle: &IocBundle, path: &Path) -> Result<()> {
+        if bundle.load_error.is_some() {
+            return Ok(());
+        }
+        if bundle.sha256 != self.bundle_sha256 {
+            bail!(
+                "IOC bundle doesn't match {}: locked {}, resolved {} (delete the lock to pin the new one)",
+                path.display(),
+                self.bundle_sha256.as_deref().unwrap_or("no bundle"),
+                bundle.sha256.as_deref().unwrap_or("no bundle"),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// `$XDG_DATA_HOME/shai-hulud-killer`, falling back to `~/.local/share`
+fn data_dir() -> Option<PathBuf> {
+    let data = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))?;
+    Some(data.join("shai-hulud-killer"))
+}
+
+/// Where `update` installs a bundle: `iocs.json` in the data directory. Scans load
+/// it when neither `--iocs` nor `--ioc-version` is given.
+pub fn installed_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("iocs.json"))
+}
+
+/// Where `update` keeps the bundle with version `tag`, for `--ioc-version`
+pub fn versioned_path(tag: &str) -> Result<PathBuf> {
+    if tag.is_empty() || tag.starts_with('.') || !tag.chars().all(|c| c.is_ascii_alphanumeric() || "._-".contains(c)) {
+        bail!("Invalid IOC bundle version '{tag}': use letters, digits, '.', '_' and '-'");
+    }
+    let Some(dir) = data_dir() else {
+        bail!("Neither XDG_DATA_HOME nor HOME is set; can't locate installed IOC bundles");
+    };
+    Ok(dir.join("versions").join(format!("{tag}.json")))
+}
+
+/// Validate `source` like `--iocs` would, then copy it to `installed_path()` and,
+/// when it has a version, keep a copy under that version for `--ioc-version`
+pub fn install(source: &Path) -> Result<PathBuf> {
+    let bundle = IocBundle::load(source)?;
+    let Some(target) = installed_path() else {
+        bail!("Neither XDG_DATA_HOME nor HOME is set; can't pick an install location");
+    };
+    let mut targets = vec![target.clone()];
+    if let Some(version) = &bundle.version {
+        targets.push(versioned_path(version)?);
+    }
+    for target in targets {
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        fs::copy(source, &target)
+            .with_context(|| format!("Failed to install IOC bundle to {}", target.display()))?;
+    }
+    Ok(target)
+}
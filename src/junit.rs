@@ -0,0 +1,100 @@
+use crate::scanner::ScanResults;
+use std::fmt::Write as _;
+
+/// Render scan results as a JUnit XML report: every finding is a failed test case
+/// (classname = finding type) and every unreadable path an errored one, so the scan
+/// shows up in CI test-result views
+pub fn render(results: &ScanResults) -> String {
+    let tests = results.findings.len() + results.errors.len();
+    let failures = results.findings.len();
+    let errors = results.errors.len();
+    let time = results.stats.elapsed.as_secs_f64();
+    let suite = escape(&results.scan_path);
+    let mut out = String::new();
+
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"shai-hulud-killer\" tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\">",
+        tests.max(1)
+    );
+    let _ = writeln!(
+        out,
+        "  <testsuite name=\"{suite}\" tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\" timestamp=\"{}\">",
+        tests.max(1),
+        chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S"),
+    );
+
+    for finding in &results.findings {
+        let location = match (finding.line, finding.offset) {
+            (Some(line), _) => format!("{}:{line}", finding.path),
+            (None, Some(offset)) => format!("{}@{offset}", finding.path),
+            (None, None) => finding.path.clone(),
+        };
+        let _ = writeln!(
+            out,
+            "    <testcase name=\"{} {}\" classname=\"{:?}\" file=\"{}\">",
+            escape(&finding.rule_id),
+            escape(&location),
+            finding.finding_type,
+            escape(&finding.path),
+        );
+        let _ = writeln!(
+            out,
+            "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+            escape(&finding.description),
+            finding.severity.as_str(),
+            escape(&failure_body(finding)),
+        );
+        out.push_str("    </testcase>\n");
+    }
+
+    for error in &results.errors {
+        let _ = writeln!(
+            out,
+            "    <testcase name=\"{}\" classname=\"ScanError\" file=\"{}\">",
+            escape(&error.path),
+            escape(&error.path),
+        );
+        let _ = writeln!(out, "      <error message=\"{}\"/>", escape(&error.message));
+        out.push_str("    </testcase>\n");
+    }
+
+    // Dashboards tend to hide empty suites; a clean scan is one passing test
+    if tests == 0 {
+        out.push_str("    <testcase name=\"scan\" classname=\"shai-hulud-killer\"/>\n");
+    }
+
+    out.push_str("  </testsuite>\n");
+    out.push_str("</testsuites>\n");
+    out
+}
+
+/// Severity, rule, matched context and advice, one per line
+fn failure_body(finding: &crate::scanner::Finding) -> String {
+    let mut body = format!("{} {}", finding.severity.as_str(), finding.rule_id);
+    if let Some(context) = &finding.context {
+        let _ = write!(body, "\n{context}");
+    }
+    if let Some(advice) = &finding.remediation {
+        let _ = write!(body, "\n{advice}");
+    }
+    body
+}
+
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Not allowed in XML 1.0 at all
+            c if c.is_control() && !matches!(c, '\n' | '\t' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -1,53 +1,780 @@
 mod app;
+mod baseline;
+mod blame;
+mod cache;
+mod commands;
+mod csv;
+mod ioc;
+mod junit;
+mod markdown;
+mod metrics;
 mod patterns;
+mod quarantine;
+mod remediation;
+mod review;
+mod sarif;
 mod scanner;
+mod server;
+mod theme;
 mod ui;
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use app::App;
-use clap::Parser;
-use std::path::PathBuf;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(name = "Shai-Hulud 2.0 Killer")]
 #[command(version = "0.1.0")]
 #[command(about = "Detect Shai-Hulud 2.0 npm supply chain attack", long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Without a subcommand, the arguments of `scan`
+    #[command(flatten)]
+    scan: ScanArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Scan a directory (the default when no subcommand is given)
+    Scan(Box<ScanArgs>),
+
+    /// Check packages against the compromised list, e.g. `check @ctrl/tinycolor@4.1.1`;
+    /// exits with 1 if any given version is infected
+    Check {
+        #[arg(required = true, value_name = "PACKAGE[@VERSION]")]
+        packages: Vec<String>,
+    },
+
+    /// List the detection rules and their ids
+    List {
+        /// List the compromised packages and their infected versions instead
+        #[arg(long)]
+        packages: bool,
+    },
+
+    /// Explain a rule id from a finding's `rule_id`
+    Explain {
+        #[arg(value_name = "RULE_ID")]
+        rule_id: String,
+    },
+
+    /// Validate an IOC bundle and install it as the default for later scans
+    Update {
+        #[arg(value_name = "FILE")]
+        bundle: PathBuf,
+    },
+
+    /// Serve scans over a Unix socket: one JSON request per line, e.g.
+    /// {"path": "/repo", "include_node_modules": true}, answered with one line of ScanResults JSON
+    Serve {
+        /// Socket path to listen on
+        #[arg(long, value_name = "PATH")]
+        socket: PathBuf,
+
+        #[command(flatten)]
+        options: Box<ScanOptions>,
+    },
+}
+
+#[derive(clap::Args)]
+struct ScanArgs {
     /// Directory to scan (interactive mode if not provided)
+    #[arg(conflicts_with_all = ["stdin", "tree"])]
     path: Option<PathBuf>,
 
+    /// Scan content piped on stdin instead of a directory; runs non-interactively and
+    /// prints JSON unless --assert-clean is given
+    #[arg(long, requires = "name", conflicts_with = "quarantine")]
+    stdin: bool,
+
+    /// File name the --stdin content is checked and reported as (e.g. package.json)
+    #[arg(long, value_name = "PATH", requires = "stdin")]
+    name: Option<PathBuf>,
+
+    /// Check a dependency tree saved from `npm ls --all --json` ("-" for stdin) instead of
+    /// a directory; runs non-interactively and prints JSON unless --assert-clean is given
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["stdin", "quarantine"])]
+    tree: Option<PathBuf>,
+
+    /// Output results as JSON (non-interactive); same as --format json
+    #[arg(short, long)]
+    json: bool,
+
+    /// Output format for the results (non-interactive)
+    #[arg(long, value_enum, value_name = "FORMAT", conflicts_with = "json")]
+    format: Option<OutputFormat>,
+
+    /// Directory --format all writes report.json, report.sarif, report.csv and
+    /// report.md into (created if missing)
+    #[arg(long, value_name = "DIR", required_if_eq("format", "all"), requires = "format")]
+    output_dir: Option<PathBuf>,
+
+    /// Write the results to this file instead of stdout ("-" for stdout); applies to
+    /// every format but `all`, and with --count
+    #[arg(short, long, value_name = "PATH")]
+    output: Option<PathBuf>,
+
+    /// Only output the summary counts, scanned file count and risk score
+    #[arg(long, requires = "json")]
+    summary_only: bool,
+
+    /// Print only the totals on one line, e.g. `critical=1 high=0 ... total=1 files=120`,
+    /// for shell-based monitoring; runs non-interactively
+    #[arg(long, conflicts_with_all = ["json", "format", "summary_only"])]
+    count: bool,
+
+    /// Print JSON on a single line instead of pretty-printing it (JSON and SARIF
+    /// output, including the JSON that --stdin and --tree print by default)
+    #[arg(long)]
+    compact: bool,
+
+    /// Write Prometheus text-format metrics to this file (e.g. for node_exporter's
+    /// textfile collector); runs non-interactively
+    #[arg(long, value_name = "FILE")]
+    metrics_file: Option<PathBuf>,
+
+    /// Write a commented cleanup shell script based on the findings; runs non-interactively
+    #[arg(long, value_name = "FILE")]
+    emit_remediation: Option<PathBuf>,
+
+    /// Move files flagged as known-malicious into this directory, keeping their
+    /// relative paths and recording a manifest.json; runs non-interactively
+    #[arg(long, value_name = "DIR")]
+    quarantine: Option<PathBuf>,
+
+    /// Don't ask for confirmation before quarantining
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Print throttled progress lines to stderr in non-interactive mode (not with --quiet)
+    #[arg(long)]
+    progress: bool,
+
+    /// Print only the results: no progress, warnings, verdict or TUI. Errors still go
+    /// to stderr, and with --assert-clean the exit code is the only output
+    #[arg(short, long, conflicts_with = "stats")]
+    quiet: bool,
+
+    /// Leave out findings already listed in this earlier JSON report (matched by
+    /// fingerprint); Critical ones are still reported
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+
+    /// Let --baseline suppress Critical findings too
+    #[arg(long, requires = "baseline")]
+    allow_suppress_critical: bool,
+
+    /// Attach the author, commit and date of each finding's line from `git blame`
+    /// (slow on large repos; files not tracked by git are left without it)
+    #[arg(long, requires = "path")]
+    blame: bool,
+
+    /// Fail (exit 2) instead of warning when the IOC dataset is degraded: an empty
+    /// hash or package list, or an installed bundle that couldn't be loaded
+    #[arg(long)]
+    require_iocs: bool,
+
+    /// Print scan timings (total and time spent hashing) to stderr
+    #[arg(long)]
+    stats: bool,
+
+    /// Exit with 1 if any finding is at or above this severity (after --min-severity
+    /// and --tag filtering); runs non-interactively
+    #[arg(long, value_enum, value_name = "SEVERITY")]
+    fail_on: Option<patterns::Severity>,
+
+    /// CI gate: print a one-line verdict to stderr and exit 0 only if no findings remain
+    /// (or none at or above --fail-on); runs non-interactively
+    #[arg(long)]
+    assert_clean: bool,
+
+    /// List dotfiles and dot-directories (.github, .npmrc, ...) in the TUI folder browser;
+    /// scans always walk them
+    #[arg(long)]
+    include_hidden: bool,
+
+    /// Color theme for the interactive TUI (press 't' to switch while running)
+    #[arg(long, value_enum, default_value_t = theme::ThemeName::Dark)]
+    theme: theme::ThemeName,
+
+    #[command(flatten)]
+    options: ScanOptions,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Json,
+    /// JUnit XML: one failed test case per finding, for CI test-result views
+    Junit,
+    /// SARIF 2.1.0, for code-scanning dashboards
+    Sarif,
+    /// One CSV row per finding
+    Csv,
+    /// Markdown summary and findings table
+    #[value(alias = "md")]
+    Markdown,
+    /// JSON, SARIF, CSV and Markdown from a single scan, written to --output-dir
+    All,
+}
+
+/// Options that shape what a scan looks at and reports, shared by `scan` and `serve`
+#[derive(clap::Args)]
+struct ScanOptions {
     /// Include node_modules directories
     #[arg(short = 'n', long)]
     include_node_modules: bool,
 
-    /// Output results as JSON (non-interactive)
-    #[arg(short, long)]
-    json: bool,
+    /// Report paths relative to the scan root (default in JSON mode)
+    #[arg(long, conflicts_with = "absolute_paths")]
+    relative_paths: bool,
+
+    /// Report absolute paths (default in interactive mode)
+    #[arg(long)]
+    absolute_paths: bool,
+
+    /// Include the SHA256 of every scanned file in JSON output
+    #[arg(long)]
+    emit_hashes: bool,
+
+    /// Load extra indicators from a JSON IOC bundle (default: the bundle installed
+    /// with `update`, if any)
+    #[arg(long, value_name = "FILE")]
+    iocs: Option<PathBuf>,
+
+    /// Use the IOC bundle installed by `update` under this version instead of the
+    /// latest one; falls back to the embedded IOCs if it isn't installed
+    #[arg(long, value_name = "TAG", conflicts_with = "iocs")]
+    ioc_version: Option<String>,
+
+    /// Pin the resolved IOC dataset: written on first use, later scans fail unless
+    /// they resolve the same bundle (and pick its version when --ioc-version isn't given)
+    #[arg(long, value_name = "FILE")]
+    ioc_lock: Option<PathBuf>,
+
+    /// The legitimate registry host, replacing the public npm registry (repeatable);
+    /// checks treat any other registry as anomalous
+    #[arg(long = "registry", value_name = "HOST")]
+    registries: Vec<String>,
+
+    /// Private registry host that may be configured without being flagged (repeatable)
+    #[arg(long = "allow-registry", value_name = "HOST")]
+    allowed_registries: Vec<String>,
+
+    /// Only check package.json files and lockfiles: much faster, but dropped payload
+    /// files and malicious code are not detected
+    #[arg(long, conflicts_with = "content_only")]
+    deps_only: bool,
+
+    /// Only check file names, hashes and contents; skip package.json and lockfile checks
+    #[arg(long)]
+    content_only: bool,
+
+    /// Skip hashing files against the known-malicious hash list; saves time on large
+    /// trees at the cost of missing renamed payload files
+    #[arg(long, conflicts_with = "emit_hashes")]
+    no_hash: bool,
+
+    /// Reuse findings from this cache file for files whose SHA256 is unchanged, and
+    /// update it; rule, IOC or version changes invalidate it
+    #[arg(long, value_name = "FILE")]
+    cache: Option<PathBuf>,
+
+    /// Also scan the directories skipped by default (.git, vendor, dist, build, ...);
+    /// payloads are sometimes dropped into build outputs
+    #[arg(long)]
+    no_skip_dirs: bool,
+
+    /// Skip directories with this name, in addition to the defaults (repeatable)
+    #[arg(long = "skip-dir", value_name = "NAME")]
+    skip_dirs: Vec<String>,
+
+    /// Don't descend more than N directories below the scan root; directories cut off
+    /// by the limit are listed under `depth_limited`
+    #[arg(long, value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// Content findings reported per file before the rest are summarized as one
+    #[arg(long, value_name = "N", default_value_t = scanner::DEFAULT_MAX_FINDINGS_PER_FILE)]
+    max_findings_per_file: usize,
+
+    /// Only report findings at or above this severity
+    #[arg(long, value_enum, value_name = "SEVERITY")]
+    min_severity: Option<patterns::Severity>,
+
+    /// Report a rule at a different severity, e.g.
+    /// `--severity content/npm-config-file-access=high` (repeatable)
+    #[arg(long = "severity", value_name = "RULE_ID=SEVERITY", value_parser = parse_severity_override)]
+    severity_overrides: Vec<(String, patterns::Severity)>,
+
+    /// JSON file of severity overrides, e.g. {"content/npm-config-file-access": "high"};
+    /// --severity flags take precedence
+    #[arg(long, value_name = "FILE")]
+    severity_file: Option<PathBuf>,
+
+    /// Only report findings with this tag (repeatable, e.g. credential-theft, rce, ci)
+    #[arg(short, long = "tag", value_name = "NAME")]
+    tags: Vec<String>,
+}
+
+impl ScanOptions {
+    /// Build the scanner config; `json` switches the default to relative paths and
+    /// `quiet` silences warnings about the options
+    fn config(self, json: bool, quiet: bool) -> Result<scanner::ScanConfig> {
+        let lock = self.ioc_lock.as_deref().map(ioc::IocLock::read).transpose()?.flatten();
+        let locked_version = lock.as_ref().and_then(|l| l.bundle_version.clone());
+        if let (Some(tag), Some(locked)) = (&self.ioc_version, &locked_version) {
+            if tag != locked {
+                anyhow::bail!("--ioc-version {tag} doesn't match version {locked} pinned by the IOC lock");
+            }
+        }
+        let iocs = match (&self.iocs, self.ioc_version.as_ref().or(locked_version.as_ref())) {
+            (Some(path), _) => ioc::IocBundle::load(path)?,
+            (None, Some(tag)) => ioc::IocBundle::load_version(tag)?,
+            (None, None) => ioc::IocBundle::load_installed(),
+        };
+        if let Some(path) = &self.ioc_lock {
+            match &lock {
+                Some(lock) => {
+                    lock.verify(&iocs, path)?;
+                    if !quiet && lock.ioc_version != patterns::IOC_VERSION {
+                        eprintln!(
+                            "Warning: {} was written with embedded IOCs {}, this build has {}",
+                            path.display(),
+                            lock.ioc_version,
+                            patterns::IOC_VERSION
+                        );
+                    }
+                }
+                // Don't pin a fallback: the next run should still look for the bundle
+                None if iocs.load_error.is_none() => ioc::IocLock::of(&iocs).write(path)?,
+                None => {}
+            }
+        }
+        let mut severity_overrides = match &self.severity_file {
+            Some(path) => load_severity_overrides(path)?,
+            None => BTreeMap::new(),
+        };
+        severity_overrides.extend(self.severity_overrides);
+        for rule_id in severity_overrides.keys() {
+            if !quiet && commands::find_rule(rule_id).is_none() {
+                eprintln!("Warning: severity override for unknown rule id '{rule_id}' has no effect");
+            }
+        }
+
+        Ok(scanner::ScanConfig {
+            include_node_modules: self.include_node_modules,
+            tags: self.tags,
+            relative_paths: self.relative_paths || (json && !self.absolute_paths),
+            emit_hashes: self.emit_hashes,
+            iocs,
+            registries: self.registries,
+            allowed_registries: self.allowed_registries,
+            deps_only: self.deps_only,
+            content_only: self.content_only,
+            max_findings_per_file: Some(self.max_findings_per_file),
+            no_hash: self.no_hash,
+            no_skip_dirs: self.no_skip_dirs,
+            extra_skip_dirs: self.skip_dirs,
+            min_severity: self.min_severity,
+            severity_overrides,
+            max_depth: self.max_depth,
+            cache: self.cache,
+            modified_since: None,
+        })
+    }
+}
+
+/// `content/npm-config-file-access=high` -> (rule id, severity)
+fn parse_severity_override(value: &str) -> Result<(String, patterns::Severity), String> {
+    let (rule_id, severity) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected RULE_ID=SEVERITY, got '{value}'"))?;
+    let severity = patterns::Severity::from_str(severity.trim(), true)?;
+    Ok((rule_id.trim().to_string(), severity))
+}
+
+fn load_severity_overrides(path: &Path) -> Result<BTreeMap<String, patterns::Severity>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read severity overrides {}", path.display()))?;
+    let raw: BTreeMap<String, String> = serde_json::from_str(&content)
+        .with_context(|| format!("Invalid severity overrides {}", path.display()))?;
+    raw.into_iter()
+        .map(|(rule_id, severity)| {
+            let severity = patterns::Severity::from_str(&severity, true)
+                .map_err(|e| anyhow::anyhow!("Invalid severity for {rule_id} in {}: {e}", path.display()))?;
+            Ok((rule_id, severity))
+        })
+        .collect()
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    if args.json {
-        // Non-interactive JSON mode
-        if let Some(path) = args.path {
-            let config = scanner::ScanConfig {
-                include_node_modules: args.include_node_modules,
+    match args.command {
+        None => scan(args.scan),
+        Some(Command::Scan(scan_args)) => scan(*scan_args),
+        Some(Command::Check { packages }) => {
+            if !commands::check(&packages) {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Some(Command::List { packages }) => {
+            commands::list(packages);
+            Ok(())
+        }
+        Some(Command::Explain { rule_id }) => commands::explain(&rule_id),
+        Some(Command::Update { bundle }) => {
+            let installed = ioc::install(&bundle)?;
+            eprintln!("Installed {} as the default IOC bundle", installed.display());
+            Ok(())
+        }
+        Some(Command::Serve { socket, options }) => {
+            let config = options.config(true, false)?;
+            let listener = server::bind(&socket)?;
+            eprintln!("Listening on {}", socket.display());
+            server::serve(listener, config)
+        }
+    }
+}
+
+fn scan(args: ScanArgs) -> Result<()> {
+    let format = args
+        .format
+        .or(args.json.then_some(OutputFormat::Json))
+        .or(((args.stdin || args.tree.is_some()) && !args.assert_clean).then_some(OutputFormat::Json));
+    if args.output_dir.is_some() && format != Some(OutputFormat::All) {
+        anyhow::bail!("--output-dir only applies to --format all");
+    }
+    if args.compact && !matches!(format, Some(OutputFormat::Json | OutputFormat::Sarif)) {
+        anyhow::bail!("--compact only applies to JSON output: --json, --format json/sarif, --stdin or --tree");
+    }
+    if args.output.is_some() && (format == Some(OutputFormat::All) || (format.is_none() && !args.count)) {
+        anyhow::bail!("--output needs a single-report output: --json, --format (but not all) or --count");
+    }
+    let config = args.options.config(format.is_some(), args.quiet)?;
+    let ioc_status = config.iocs.status();
+    for problem in &ioc_status.problems {
+        if args.require_iocs {
+            eprintln!("Error: IOC dataset degraded: {problem}");
+        } else if !args.quiet {
+            eprintln!("Warning: IOC dataset degraded: {problem}");
+        }
+    }
+    if args.require_iocs && ioc_status.degraded() {
+        std::process::exit(2);
+    }
+    let baseline = args.baseline.as_deref().map(baseline::Baseline::load).transpose()?;
+
+    let headless = format.is_some()
+        || args.count
+        || args.quiet
+        || args.metrics_file.is_some()
+        || args.emit_remediation.is_some()
+        || args.quarantine.is_some()
+        || args.assert_clean
+        || args.fail_on.is_some()
+        || args.stdin
+        || args.tree.is_some();
+    // Exit codes are part of the CI contract: 0 clean, 1 findings at or above the
+    // threshold, 2 the scan failed or was incomplete
+    let threshold = args.fail_on.or(args.assert_clean.then_some(patterns::Severity::Info));
+
+    if headless {
+        // Non-interactive mode
+        let scanned = if args.stdin {
+            let name = args.name.as_deref().unwrap_or(Path::new("stdin"));
+            read_stdin().map(|bytes| scanner::scan_content(name, &bytes, &config))
+        } else if let Some(tree) = &args.tree {
+            let content = if tree == Path::new("-") {
+                read_stdin().map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            } else {
+                std::fs::read_to_string(tree).with_context(|| format!("Failed to read {}", tree.display()))
             };
-            let results = scanner::scan_directory_sync(&path, &config)?;
-            println!("{}", serde_json::to_string_pretty(&results)?);
+            content.and_then(|content| scanner::scan_tree(tree, &content, &config))
+        } else if let Some(path) = &args.path {
+            if args.progress && !args.quiet {
+                scanner::scan_directory_with_progress(path, &config, stderr_progress())
+            } else {
+                scanner::scan_directory_sync(path, &config)
+            }
         } else {
-            eprintln!("Error: Path required for JSON output mode");
+            eprintln!("Error: Path required for non-interactive output");
             std::process::exit(1);
+        };
+        let mut results = match scanned {
+            Ok(results) => results,
+            Err(e) if threshold.is_some() => {
+                eprintln!("Error: {e:#}");
+                std::process::exit(2);
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(baseline) = &baseline {
+            let report = baseline.apply(&mut results, args.allow_suppress_critical);
+            if report.critical_kept > 0 && !args.quiet {
+                eprintln!(
+                    "Warning: baseline {} lists {} Critical findings; they are still reported \
+                     (--allow-suppress-critical suppresses them)",
+                    report.path, report.critical_kept
+                );
+            }
+            if report.critical_suppressed > 0 && !args.quiet {
+                eprintln!(
+                    "Warning: baseline {} suppressed {} Critical findings (--allow-suppress-critical)",
+                    report.path, report.critical_suppressed
+                );
+            }
+            results.baseline = Some(report);
+        }
+        if let (true, Some(path)) = (args.blame, &args.path) {
+            blame::annotate(&mut results, path);
+        }
+        if args.stats {
+            print_stats(&results);
+        }
+        for error in results.errors.iter().filter(|_| !args.quiet) {
+            eprintln!("Warning: couldn't read {}: {}", error.path, error.message);
+        }
+        if !results.depth_limited.is_empty() && !args.quiet {
+            eprintln!(
+                "Warning: --max-depth left {} directories unscanned (listed under depth_limited)",
+                results.depth_limited.len()
+            );
+        }
+        if let (Some(dir), Some(path)) = (&args.quarantine, &args.path) {
+            let files = quarantine::candidates(&results, path);
+            if !files.is_empty() && confirm_quarantine(&files, dir, args.yes)? {
+                let moved = quarantine::quarantine(&files, path, dir)?;
+                for entry in moved.iter().filter(|_| !args.quiet) {
+                    eprintln!("Quarantined {} -> {}", entry.original, entry.quarantined);
+                }
+                results.quarantined = Some(moved);
+            }
+        }
+        if let Some(metrics_file) = &args.metrics_file {
+            metrics::write(metrics_file, &results)?;
+        }
+        if let Some(script) = &args.emit_remediation {
+            remediation::write(script, &results)?;
+        }
+        let report = if args.count {
+            Some(format!("{}\n", count_line(&results)))
+        } else if args.summary_only {
+            Some(format!("{}\n", to_json(&results.summary_report(), args.compact)?))
+        } else {
+            match format {
+                Some(OutputFormat::Json) => Some(format!("{}\n", to_json(&results, args.compact)?)),
+                Some(OutputFormat::Junit) => Some(junit::render(&results)),
+                Some(OutputFormat::Sarif) => Some(format!("{}\n", to_json(&sarif::render(&results), args.compact)?)),
+                Some(OutputFormat::Csv) => Some(csv::render(&results)),
+                Some(OutputFormat::Markdown) => Some(markdown::render(&results)),
+                Some(OutputFormat::All) => {
+                    let dir = args.output_dir.as_deref().expect("clap requires --output-dir");
+                    write_reports(dir, &results)?;
+                    if !args.quiet {
+                        eprintln!("Wrote report.json, report.sarif, report.csv and report.md to {}", dir.display());
+                    }
+                    None
+                }
+                None => None,
+            }
+        };
+        if let Some(report) = report {
+            // A report that can't be written is a failed run, like a failed scan
+            if let Err(e) = write_output(args.output.as_deref(), &report, args.quiet) {
+                eprintln!("Error: {e:#}");
+                std::process::exit(2);
+            }
+        }
+        if let Some(threshold) = threshold {
+            let failing = results.findings.iter().filter(|f| f.severity.at_least(threshold)).count();
+            if args.assert_clean && !args.quiet {
+                print_verdict(&results, failing, threshold);
+            }
+            if failing > 0 {
+                std::process::exit(1);
+            }
+            if !results.errors.is_empty() {
+                std::process::exit(2);
+            }
         }
     } else {
         // Interactive TUI mode
-        let mut app = App::new(args.path, args.include_node_modules)?;
+        let mut app = App::new(args.path, config)?;
+        app.theme = theme::Theme::new(args.theme);
+        app.baseline = baseline;
+        app.allow_suppress_critical = args.allow_suppress_critical;
+        app.blame = args.blame;
+        if args.include_hidden {
+            app.include_hidden = true;
+            app.refresh_entries()?;
+        }
         ui::run(&mut app)?;
+        // The TUI owns the screen while it runs; report on the last scan afterwards
+        if let (true, Some(results)) = (args.stats, &app.scan_results) {
+            print_stats(results);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the report, or write it to `--output` unless that is "-"
+fn write_output(output: Option<&Path>, report: &str, quiet: bool) -> Result<()> {
+    match output.filter(|path| *path != Path::new("-")) {
+        Some(path) => {
+            std::fs::write(path, report).with_context(|| format!("Failed to write {}", path.display()))?;
+            if !quiet {
+                eprintln!("Wrote results to {}", path.display());
+            }
+        }
+        None => print!("{report}"),
     }
+    Ok(())
+}
 
+/// Every report format from the same results (`--format all`), so archiving them all
+/// costs one scan
+fn write_reports(dir: &Path, results: &scanner::ScanResults) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let reports = [
+        ("report.json", to_json(results, false)?),
+        ("report.sarif", to_json(&sarif::render(results), false)?),
+        ("report.csv", csv::render(results)),
+        ("report.md", markdown::render(results)),
+    ];
+    for (name, content) in reports {
+        let path = dir.join(name);
+        std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
     Ok(())
 }
+
+/// `--count` output: severity totals, findings and scanned files as `key=value` pairs
+fn count_line(results: &scanner::ScanResults) -> String {
+    let summary = &results.summary;
+    format!(
+        "critical={} high={} medium={} low={} info={} total={} files={}",
+        summary.critical, summary.high, summary.medium, summary.low, summary.info, summary.total, results.scanned_files
+    )
+}
+
+fn to_json<T: serde::Serialize>(value: &T, compact: bool) -> serde_json::Result<String> {
+    if compact {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
+}
+
+fn print_stats(results: &scanner::ScanResults) {
+    let stats = &results.stats;
+    eprintln!(
+        "Scanned {} files in {:.2}s; hashing took {:.2}s of CPU time over {} files{}",
+        results.scanned_files,
+        stats.elapsed.as_secs_f64(),
+        stats.hashing.as_secs_f64(),
+        stats.hashed_files,
+        if results.config.no_hash { " (--no-hash)" } else { "" },
+    );
+    if stats.cache_hits + stats.cache_misses > 0 {
+        eprintln!("Cache: {} hits, {} misses", stats.cache_hits, stats.cache_misses);
+    }
+}
+
+/// One line for CI logs, e.g. `NOT CLEAN: 3 findings at or above HIGH in 1200 files`
+fn print_verdict(results: &scanner::ScanResults, failing: usize, threshold: patterns::Severity) {
+    let verdict = if failing > 0 {
+        "NOT CLEAN"
+    } else if !results.errors.is_empty() {
+        "INCOMPLETE"
+    } else {
+        "CLEAN"
+    };
+    let unreadable = match results.errors.len() {
+        0 => String::new(),
+        n => format!(", {n} unreadable"),
+    };
+    eprintln!(
+        "{verdict}: {failing} findings at or above {} in {} files{unreadable}",
+        threshold.as_str(),
+        results.scanned_files,
+    );
+}
+
+fn read_stdin() -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut bytes = Vec::new();
+    std::io::stdin().read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Ask before moving files; without a terminal, `--yes` is required
+fn confirm_quarantine(files: &[PathBuf], dir: &std::path::Path, yes: bool) -> Result<bool> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    if yes {
+        return Ok(true);
+    }
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("Refusing to quarantine without confirmation; pass --yes");
+    }
+
+    for file in files {
+        eprintln!("  {}", file.display());
+    }
+    eprint!("Move {} file(s) to {}? [y/N] ", files.len(), dir.display());
+    std::io::stderr().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Progress lines for CI logs
+fn stderr_progress() -> scanner::ProgressCallback {
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    let last = Mutex::new(None::<Instant>);
+    Box::new(move |phase, current, total, _file| {
+        let Ok(mut last) = last.lock() else {
+            return;
+        };
+        if let Some(line) = progress_line(phase, current, total, &mut last, Instant::now()) {
+            eprintln!("{line}");
+        }
+    })
+}
+
+/// The progress line to print at `now`, if any: at most one per second since the
+/// `last` one, plus the final count
+fn progress_line(
+    phase: scanner::ScanPhase,
+    current: usize,
+    total: usize,
+    last: &mut Option<std::time::Instant>,
+    now: std::time::Instant,
+) -> Option<String> {
+    let due = last.is_none_or(|t| now.duration_since(t) >= std::time::Duration::from_secs(1));
+    let done = phase == scanner::ScanPhase::Scanning && current == total;
+    if !due && !done {
+        return None;
+    }
+    *last = Some(now);
+    Some(match phase {
+        scanner::ScanPhase::Collecting => format!("Collecting files… {current} found"),
+        scanner::ScanPhase::Scanning => format!("Scanned {current}/{total} files"),
+    })
+}
@@ -0,0 +1,74 @@
+use crate::scanner::ScanResults;
+use std::fmt::Write as _;
+
+/// Render scan results as a Markdown report: summary counts, then one table row per
+/// finding with its evidence, ordered as scanned. Fits a PR comment or an archived ticket
+pub fn render(results: &ScanResults) -> String {
+    let summary = &results.summary;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# Shai-Hulud scan: {}\n", escape(&results.scan_path));
+    let _ = writeln!(
+        out,
+        "{} files scanned, tool {}, IOCs {}, risk score {}/100\n",
+        results.scanned_files,
+        results.tool_version,
+        results.ioc_version,
+        summary.risk_score(),
+    );
+    out.push_str("| Critical | High | Medium | Low | Info | Unreadable |\n");
+    out.push_str("|---------:|-----:|-------:|----:|-----:|-----------:|\n");
+    let _ = writeln!(
+        out,
+        "| {} | {} | {} | {} | {} | {} |\n",
+        summary.critical, summary.high, summary.medium, summary.low, summary.info, summary.errors
+    );
+
+    if results.findings.is_empty() {
+        out.push_str("No findings.\n");
+    } else {
+        out.push_str("## Findings\n\n");
+        out.push_str("| Severity | Rule | Location | Description | Evidence |\n");
+        out.push_str("|----------|------|----------|-------------|----------|\n");
+        for finding in &results.findings {
+            let location = match finding.line {
+                Some(line) => format!("{}:{line}", finding.path),
+                None => finding.path.clone(),
+            };
+            let evidence = finding.matched_line.as_ref().or(finding.context.as_ref());
+            let _ = writeln!(
+                out,
+                "| {} | `{}` | {} | {} | {} |",
+                finding.severity.as_str(),
+                finding.rule_id,
+                escape(&location),
+                escape(&finding.description),
+                escape(evidence.map(String::as_str).unwrap_or_default()),
+            );
+        }
+    }
+
+    if !results.errors.is_empty() {
+        out.push_str("\n## Unreadable paths\n\n");
+        for error in &results.errors {
+            let _ = writeln!(out, "- {}: {}", escape(&error.path), escape(&error.message));
+        }
+    }
+    out
+}
+
+/// Keep table cells on one line and stop text from being read as markup
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '|' | '\\' | '`' | '*' | '_' | '<' | '>' | '[' | ']' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' | '\r' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,71 @@
+use crate::scanner::ScanResults;
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Render scan results as Prometheus text-format gauges, labelled with the scanned
+/// path so one textfile collector can track many repositories
+pub fn render(results: &ScanResults) -> String {
+    let path = escape_label(&results.scan_path);
+    let summary = &results.summary;
+    let mut out = String::new();
+
+    out.push_str("# HELP shai_hulud_findings Findings from the last scan by severity\n");
+    out.push_str("# TYPE shai_hulud_findings gauge\n");
+    for (severity, count) in [
+        ("critical", summary.critical),
+        ("high", summary.high),
+        ("medium", summary.medium),
+        ("low", summary.low),
+        ("info", summary.info),
+    ] {
+        let _ = writeln!(
+            out,
+            "shai_hulud_findings{{path=\"{path}\",severity=\"{severity}\"}} {count}"
+        );
+    }
+
+    out.push_str("# HELP shai_hulud_scanned_files Files visited by the last scan\n");
+    out.push_str("# TYPE shai_hulud_scanned_files gauge\n");
+    let _ = writeln!(
+        out,
+        "shai_hulud_scanned_files{{path=\"{path}\"}} {}",
+        results.scanned_files
+    );
+
+    out.push_str("# HELP shai_hulud_scan_errors Paths the last scan couldn't read\n");
+    out.push_str("# TYPE shai_hulud_scan_errors gauge\n");
+    let _ = writeln!(
+        out,
+        "shai_hulud_scan_errors{{path=\"{path}\"}} {}",
+        results.summary.errors
+    );
+
+    out.push_str("# HELP shai_hulud_last_scan_timestamp_seconds Completion time of the last scan\n");
+    out.push_str("# TYPE shai_hulud_last_scan_timestamp_seconds gauge\n");
+    let _ = writeln!(
+        out,
+        "shai_hulud_last_scan_timestamp_seconds{{path=\"{path}\"}} {}",
+        chrono::Utc::now().timestamp()
+    );
+
+    out
+}
+
+/// Write metrics atomically (temp file + rename) so the textfile collector never
+/// reads a half-written file
+pub fn write(path: &Path, results: &ScanResults) -> Result<()> {
+    let tmp = path.with_extension("prom.tmp");
+    fs::write(&tmp, render(results))
+        .with_context(|| format!("Failed to write metrics to {}", tmp.display()))?;
+    fs::rename(&tmp, path)
+        .with_context(|| format!("Failed to write metrics to {}", path.display()))
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
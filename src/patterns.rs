@@ -1,7 +1,10 @@
 use regex::Regex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::sync::LazyLock;
 
+/// Version of the embedded IOC dataset (files, hashes, compromised packages)
+pub const IOC_VERSION: &str = "2025-11-26";
+
 /// Known malicious filenames
 pub const MALICIOUS_FILES: &[&str] = &["setup_bun.js", "bun_environment.js"];
 
@@ -13,6 +16,26 @@ pub const MALICIOUS_HASHES: &[&str] = &[
     "a3894003ad1d293ba96d77881ccd2071446dc3f65f434669b49b3da92421901a",
 ];
 
+/// Byte sequences characteristic of the bun_environment.js payload. A file containing
+/// at least `SIGNATURE_QUORUM` distinct signatures is flagged regardless of its name or hash.
+pub const PAYLOAD_SIGNATURES: &[&str] = &[
+    "Sha1-Hulud: The Second Coming",
+    "SHA1HULUD",
+    "list_AWS_secrets",
+    "list_GCP_secrets",
+    "list_Azure_secrets",
+    "githubGetPackagesByMaintainer",
+    "githubUpdatePackage",
+    "truffleSecrets.json",
+    "actionsSecrets.json",
+    "cloud.json",
+    "environment.json",
+    "contents.json",
+];
+
+/// Distinct payload signatures required for a match
+pub const SIGNATURE_QUORUM: usize = 4;
+
 /// Directories to skip during scanning
 pub const SKIP_DIRS: &[&str] = &[".git", ".svn", ".hg", "vendor", "dist", "build", "__pycache__"];
 
@@ -20,138 +43,828 @@ pub const SKIP_DIRS: &[&str] = &[".git", ".svn", ".hg", "vendor", "dist", "build
 pub const DANGEROUS_HOOKS: &[&str] = &["preinstall", "postinstall", "preuninstall", "install"];
 
 /// File extensions to scan for patterns
-pub const SCANNABLE_EXTENSIONS: &[&str] = &["js", "ts", "mjs", "cjs", "json", "yaml", "yml", "sh"];
+pub const SCANNABLE_EXTENSIONS: &[&str] = &["js", "ts", "mjs", "cjs", "json", "yaml", "yml", "sh", "ps1", "py"];
+
+/// Remediation advice attached to findings
+pub const REMEDIATE_PAYLOAD: &str = "Delete the payload files, reinstall dependencies from a clean lockfile, and rotate npm, GitHub and cloud credentials used on this machine";
+pub const REMEDIATE_CREDENTIALS: &str = "If this code isn't yours, remove it and rotate the credentials it reads (npm, GitHub, cloud)";
+pub const REMEDIATE_CI: &str = "Remove the workflow or step, deregister unknown self-hosted runners, and rotate repository secrets";
+pub const REMEDIATE_COMPROMISED_ACTION: &str = "Remove the action or pin it to a known-good commit SHA, then rotate every secret the workflow could read and check the logs of runs that used it";
+pub const REMEDIATE_UNPINNED_ACTION: &str = "Pin the action to a full commit SHA (keep the tag in a comment) so a hijacked branch can't change what runs in your workflow";
+pub const REMEDIATE_RCE: &str = "Replace piped remote scripts with a pinned, checksum-verified download";
+pub const REMEDIATE_PACKAGE: &str = "Pin to a version outside the infected list (or remove the dependency), reinstall from a clean lockfile, and rotate npm/GitHub tokens";
+pub const REMEDIATE_TARGETED_PACKAGE: &str = "Check that the version resolved in your lockfile isn't infected and pin it";
+pub const REMEDIATE_DYNAMIC_LOAD: &str = "Check which module is loaded at runtime; if it isn't part of your codebase, remove the loader and the dropped file";
+pub const REMEDIATE_REGISTRY: &str = "Restore the default registry, reinstall from a clean lockfile, and if this is your private registry allow it with --allow-registry";
+pub const REMEDIATE_HOOK: &str = "Remove the install hook, don't run npm install until the package is verified, and rotate tokens if it already ran";
+pub const REMEDIATE_SSH: &str = "If this code isn't yours, remove it; replace the affected SSH keys and remove the old public keys from servers and Git hosts";
+pub const REMEDIATE_YARN_PLUGIN: &str = "Don't run yarn in this project until the plugin is verified; remove the entry and re-import trusted plugins with `yarn plugin import`";
+pub const REMEDIATE_REMOTE_DEPENDENCY: &str = "Depend on a registry version pinned by the lockfile instead; if the source is really needed, pin it to a commit or checksum and review what it installs";
+pub const REMEDIATE_NPM_API: &str = "If this isn't your own release tooling, remove it, reinstall from a clean lockfile, check recently published versions of your packages and rotate npm tokens";
+pub const REMEDIATE_SECURITY_TOOLING: &str = "Restore the setting or package, find out what changed it (this usually comes right before a payload runs) and re-run the audit";
+pub const REMEDIATE_FORCED_VERSION: &str = "Remove the override entry and find out who added it, reinstall from a clean lockfile, and rotate npm/GitHub tokens if it was installed";
+pub const REMEDIATE_PERSISTENCE: &str = "Remove the added lines from the shell startup files and crontab (review with `crontab -l`), then rotate the credentials used on this machine";
+pub const REMEDIATE_TRUNCATED: &str = "Review the whole file by hand: it matched more rules than are reported, which is itself unusual for legitimate code";
 
 /// Suspicious code patterns with descriptions and severity
 pub static SUSPICIOUS_PATTERNS: LazyLock<Vec<PatternRule>> = LazyLock::new(|| {
     vec![
         PatternRule::new(
+            "content/shai-hulud-runner-identifier",
             r"(?i)SHA1HULUD",
             "Shai-Hulud runner identifier",
             Severity::Critical,
-        ),
+            &["marker", "ci"],
+        )
+        .remediation(REMEDIATE_PAYLOAD),
         PatternRule::new(
+            "content/shai-hulud-2-0-marker-string",
             r"(?i)Sha1-Hulud:\s*The\s*Second\s*Coming",
             "Shai-Hulud 2.0 marker string",
             Severity::Critical,
-        ),
+            &["marker"],
+        )
+        .remediation(REMEDIATE_PAYLOAD),
         PatternRule::new(
+            "content/malicious-setup-file-reference",
             r"setup_bun\.js",
             "Malicious setup file reference",
             Severity::Critical,
-        ),
+            &["ioc"],
+        )
+        .remediation(REMEDIATE_PAYLOAD),
         PatternRule::new(
+            "content/malicious-environment-file-reference",
             r"bun_environment\.js",
             "Malicious environment file reference",
             Severity::Critical,
-        ),
+            &["ioc"],
+        )
+        .remediation(REMEDIATE_PAYLOAD),
         PatternRule::new(
+            "content/cloud-secrets-enumeration-function",
             r"list_AWS_secrets|list_GCP_secrets|list_Azure_secrets",
             "Cloud secrets enumeration function",
             Severity::Critical,
-        ),
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
         PatternRule::new(
+            "content/malicious-github-package-functions",
             r"githubGetPackagesByMaintainer|githubUpdatePackage",
             "Malicious GitHub package functions",
             Severity::Critical,
-        ),
+            &["propagation"],
+        )
+        .remediation(REMEDIATE_PAYLOAD),
         PatternRule::new(
+            "content/suspicious-github-automation",
             r"github_save_file|githubListRepos",
             "Suspicious GitHub automation",
             Severity::High,
-        ),
+            &["exfiltration"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
         PatternRule::new(
+            "content/github-cli-token-extraction",
             r"gh\s+auth\s+token",
             "GitHub CLI token extraction",
             Severity::High,
-        ),
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        PatternRule::new(
+            "content/github-cli-token-display",
+            r"gh\s+auth\s+status\b[^\n|;&]*\s(--show-token|-t)\b",
+            "GitHub CLI token display",
+            Severity::High,
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        // Where the CLI stores its OAuth token: ~/.config/gh/hosts.yml (or $GH_CONFIG_DIR),
+        // either as a path literal or built with path.join(..., "gh", "hosts.yml")
         PatternRule::new(
-            r"\.npmrc",
+            "content/github-cli-credential-file-access",
+            r#"(?i)\bgh[\\/]+hosts\.ya?ml\b|['"`]gh['"`]\s*,\s*['"`]hosts\.ya?ml['"`]|GH_CONFIG_DIR[^\n]*hosts\.ya?ml"#,
+            "GitHub CLI credential file access",
+            Severity::High,
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        PatternRule::new(
+            "content/npm-config-file-access",
+            // Only file access: a quoted/path-prefixed name or a shell command operand,
+            // not prose mentioning npm config
+            r#"(['"`/\\]\.npmrc\b|\b(cat|cp|mv|scp|tar|base64)\s+(-\S+\s+)*\.npmrc\b)"#,
             "NPM config file access",
             Severity::Medium,
-        ),
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        PatternRule::new(
+            "content/ssh-private-key-file-access",
+            r#"\.ssh[\\/]+id_(rsa|dsa|ecdsa|ed25519)(_sk)?([^.\w]|$)|['"`]\.ssh['"`]\s*,\s*['"`]id_(rsa|dsa|ecdsa|ed25519)(_sk)?['"`]"#,
+            "SSH private key file access",
+            Severity::High,
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_SSH),
+        PatternRule::new(
+            "content/ssh-config-or-known-hosts-access",
+            r#"\.ssh[\\/]+(config|known_hosts)\b|['"`]\.ssh['"`]\s*,\s*['"`](config|known_hosts)['"`]"#,
+            "SSH config or known_hosts access",
+            Severity::High,
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_SSH),
+        // The header is required: the word "ssh" alone is everywhere in deploy scripts
+        PatternRule::new(
+            "content/embedded-ssh-private-key",
+            r"-----BEGIN (OPENSSH |RSA |EC |DSA )?PRIVATE KEY-----",
+            "Embedded SSH private key",
+            Severity::Critical,
+            &["credential-theft"],
+        )
+        .redact_with(&PRIVATE_KEY_BODY)
+        .remediation(REMEDIATE_SSH),
+        PatternRule::new(
+            "content/macos-keychain-access",
+            r"\bsecurity\s+(-\S+\s+)*(find-generic-password|find-internet-password|dump-keychain)\b",
+            "macOS Keychain access",
+            Severity::High,
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        PatternRule::new(
+            "content/windows-credential-manager-access",
+            r"(?i)\bcmdkey(\.exe)?\s+/list\b|\bvaultcmd(\.exe)?\s+/list(creds|properties)?\b|\bCredEnumerate[AW]?\b|\bGet-StoredCredential\b|Windows\.Security\.Credentials\.PasswordVault",
+            "Windows Credential Manager access",
+            Severity::High,
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        // Full browser profile paths only: a bare `logins.json` is a common app file name
+        PatternRule::new(
+            "content/browser-saved-password-database-access",
+            r#"(Chrome|Chromium|Edge|Brave-Browser|Opera|Vivaldi)[^\n]*\bLogin Data\b|(?i:firefox|\.mozilla)[^\n]*\blogins\.json\b|\bkey4\.db\b"#,
+            "Browser saved-password database access",
+            Severity::High,
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
         PatternRule::new(
+            "content/npm-token-reference",
             r"NPM_TOKEN|npm_token",
             "NPM token reference",
             Severity::High,
-        ),
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
         PatternRule::new(
+            "content/github-token-environment-variable",
             r"GITHUB_TOKEN|GH_TOKEN",
             "GitHub token environment variable",
             Severity::Medium,
-        ),
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS)
+        // Workflows reference the token constantly; only exfiltration is a signal there
+        .in_workflows(Severity::Info),
         PatternRule::new(
+            "content/secret-scanning-tool-reference",
             r"(?i)trufflehog",
             "Secret scanning tool reference",
             Severity::High,
-        ),
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        PatternRule::new(
+            "content/trufflehog-download-piped-to-shell",
+            r"(?i)(curl|wget)[^|]*trufflehog[^|]*\|\s*(sh|bash|node)",
+            "Trufflehog download piped to shell",
+            Severity::Critical,
+            &["credential-theft", "rce"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
         PatternRule::new(
+            "content/github-actions-runner-config-access",
             r"actions/runner/config",
             "GitHub Actions runner config access",
             Severity::High,
-        ),
+            &["ci"],
+        )
+        .remediation(REMEDIATE_CI),
+        PatternRule::new(
+            "content/self-hosted-runner-registration",
+            r"config\.(sh|cmd)\s.*--url\s+\S+.*--token\b",
+            "Self-hosted runner registration",
+            Severity::High,
+            &["ci"],
+        )
+        .remediation(REMEDIATE_CI),
         PatternRule::new(
+            "content/suspicious-workflow-filename",
             r"discussion\.ya?ml",
             "Suspicious workflow filename",
             Severity::High,
-        ),
+            &["ci"],
+        )
+        .remediation(REMEDIATE_CI),
         PatternRule::new(
+            "content/self-hosted-runner-configuration",
             r"runs-on:\s*\[?\s*self-hosted",
             "Self-hosted runner configuration",
             Severity::Medium,
-        ),
+            &["ci"],
+        )
+        .remediation(REMEDIATE_CI),
         PatternRule::new(
+            "content/remote-code-execution-via-curl-pipe",
             r"curl.*\|\s*(sh|bash|node)",
             "Remote code execution via curl pipe",
             Severity::High,
-        ),
+            &["rce"],
+        )
+        .remediation(REMEDIATE_RCE),
         PatternRule::new(
+            "content/remote-code-execution-via-wget-pipe",
             r"wget.*\|\s*(sh|bash|node)",
             "Remote code execution via wget pipe",
             Severity::High,
-        ),
+            &["rce"],
+        )
+        .remediation(REMEDIATE_RCE),
+        // PowerShell's `curl`/`wget` are aliases for Invoke-WebRequest, so they count as
+        // downloads here
+        PatternRule::new(
+            "content/remote-code-execution-via-powershell-download",
+            concat!(
+                r"(?i)\b(iex|Invoke-Expression)\b[^\n]*\b(DownloadString|DownloadData|Invoke-WebRequest|iwr|Invoke-RestMethod|irm|curl|wget)\b",
+                r"|\b(DownloadString|Invoke-WebRequest|iwr|Invoke-RestMethod|irm|curl|wget)\b[^\n]*\|\s*(iex|Invoke-Expression)\b",
+                r"|\[scriptblock\]::Create\([^\n]*\b(DownloadString|Invoke-WebRequest|iwr|Invoke-RestMethod|irm)\b",
+            ),
+            "Remote code execution via PowerShell download",
+            Severity::High,
+            &["rce"],
+        )
+        .remediation(REMEDIATE_RCE),
+        // Reverse shells have no business in package code, so these are Critical
+        PatternRule::new(
+            "content/reverse-shell-via-dev-tcp",
+            r"/dev/(tcp|udp)/[^\s/]+/[^\s/]+",
+            "Reverse shell via /dev/tcp",
+            Severity::Critical,
+            &["rce"],
+        )
+        .remediation(REMEDIATE_PAYLOAD),
         PatternRule::new(
-            r"~/\.aws/credentials",
+            "content/reverse-shell-via-netcat-exec",
+            r"\b(nc|netcat|ncat)\b[^\n|;&]*\s-[a-zA-Z]*[ec]\s+\S|\bncat\b[^\n|;&]*\s--(sh-)?exec\b",
+            "Reverse shell via netcat exec",
+            Severity::Critical,
+            &["rce"],
+        )
+        .remediation(REMEDIATE_PAYLOAD),
+        PatternRule::new(
+            "content/reverse-shell-via-socat-exec",
+            r"(?i)\bsocat\b[^\n]*\b(exec|system):",
+            "Reverse shell via socat EXEC",
+            Severity::Critical,
+            &["rce"],
+        )
+        .remediation(REMEDIATE_PAYLOAD),
+        // Python helpers build the path from pieces: `Path.home() / ".aws" / "credentials"`
+        PatternRule::new(
+            "content/aws-credentials-file-access",
+            r#"~/\.aws/credentials|['"]\.aws['"]\s*[,/]\s*['"]credentials['"]"#,
             "AWS credentials file access",
             Severity::High,
-        ),
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
         PatternRule::new(
+            "content/gcp-credentials-file-access",
             r"application_default_credentials\.json",
             "GCP credentials file access",
             Severity::High,
-        ),
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        PatternRule::new(
+            "content/gcp-gcloud-config-directory-access",
+            r#"\.config/gcloud\b|['"]\.config['"]\s*[,/]\s*['"]gcloud['"]"#,
+            "GCP gcloud config directory access",
+            Severity::High,
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        // Payloads build credential paths from the home directory instead of
+        // spelling them out: `path.join(os.homedir(), '.aws')`, `${process.env.HOME}/.ssh`.
+        // Only credential locations count; `path.join(os.homedir(), '.config', 'app')` is fine
+        PatternRule::new(
+            "content/credential-path-built-from-the-home-directory",
+            &format!(
+                r#"{HOME_DIR}\s*,\s*['"`]{CREDENTIAL_SUBPATH}|{HOME_DIR}\s*(?:\}}|\+\s*['"`])[\/]{CREDENTIAL_SUBPATH}"#
+            ),
+            "Credential path built from the home directory",
+            Severity::High,
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        // boto3 resolves keys from every source it knows (env, files, instance
+        // metadata); application code hands the session to a client instead
+        PatternRule::new(
+            "content/python-boto3-credential-dump",
+            r"\.get_(?:frozen_)?credentials\s*\(\s*\)",
+            "Python boto3 credential dump",
+            Severity::High,
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
         PatternRule::new(
+            "content/azure-profile-access",
             r"azureProfile\.json",
             "Azure profile access",
             Severity::High,
+            &["credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        // npm's own install engine: droppers use it to add dependencies to a tree
+        // behind the lockfile's back, while project code practically never loads it
+        PatternRule::new(
+            "content/programmatic-install-via-npm-arborist",
+            r#"(?:require\s*\(\s*|\bfrom\s+|import\s*\(\s*)['"]@npmcli/arborist['"]"#,
+            "Programmatic install via npm Arborist",
+            Severity::High,
+            &["supply-chain"],
+        )
+        .remediation(REMEDIATE_NPM_API),
+        // Persistence outside the package: shell startup files run the payload in
+        // every new terminal. Reading or sourcing them is ordinary
+        PatternRule::new(
+            "content/shell-startup-file-modified",
+            &format!(r#"(?:>>?|\btee\s+(?:-a\s+)?)\s*["']?(?:~|\$HOME|\$\{{HOME\}}|/root|/home/[^/\s]+)/{SHELL_RC_FILES}|\b(?:appendFileSync|appendFile|writeFileSync|writeFile|createWriteStream)\s*\([^\n]*{SHELL_RC_FILES}|\bopen\s*\([^\n]*{SHELL_RC_FILES}["']\s*\)?\s*,\s*(?:mode\s*=\s*)?["'][aw]"#),
+            "Shell startup file modified",
+            Severity::High,
+            &["persistence"],
+        )
+        .remediation(REMEDIATE_PERSISTENCE),
+        PatternRule::new(
+            "content/crontab-entry-installed",
+            r#"\|\s*crontab\s+(?:-u\s+\S+\s+)?-(?:\s|$|["'`)])|\bcrontab\s+(?:-u\s+\S+\s+)?[^-\s'"`;|&)]|(?:>>?|\btee\s+(?:-a\s+)?|\b(?:appendFileSync|appendFile|writeFileSync|writeFile)\s*\()\s*["'`]?/(?:etc/cron\.(?:d|daily|hourly|weekly|monthly)|etc/crontab|var/spool/cron)\b"#,
+            "Crontab entry installed",
+            Severity::High,
+            &["persistence"],
+        )
+        .remediation(REMEDIATE_PERSISTENCE),
+        // Anti-analysis: payloads switch off the defenses that would catch the next
+        // step. One-off `npm ci --no-audit` is routine, persisting it is not
+        PatternRule::new(
+            "content/npm-audit-or-ignore-scripts-disabled",
+            r#"\bnpm\s+(?:config\s+)?set\s+(?:audit|ignore-scripts)(?:\s+|\s*=\s*)false\b|\b(?:audit|ignore-scripts)\s*=\s*false\b[^\n]*\.npmrc\b|\.npmrc\b[^\n]*\b(?:audit|ignore-scripts)\s*=\s*false\b"#,
+            "npm audit or ignore-scripts disabled",
+            Severity::Medium,
+            &["defense-evasion"],
+        )
+        .remediation(REMEDIATE_SECURITY_TOOLING),
+        PatternRule::new(
+            "content/security-package-uninstalled",
+            &format!(r#"\b(?:npm|pnpm|yarn|bun)\s+(?:uninstall|remove|rm|un|r)\s+(?:-\S+\s+)*(?:[^\s;&|]+\s+)*?(?:{SECURITY_PACKAGES})(?:@\S*)?(?:\s|$|[;&|'"])"#),
+            "Security package uninstalled",
+            Severity::High,
+            &["defense-evasion"],
+        )
+        .remediation(REMEDIATE_SECURITY_TOOLING),
+        PatternRule::new(
+            "content/security-tooling-config-deleted",
+            &format!(r#"(?:\brm\s+(?:-\S+\s+)*|\b(?:unlinkSync|unlink|rmSync|rimraf(?:\.sync)?)\s*\(\s*|\bRemove-Item\s+(?:-\S+\s+)*)['"`]?[^\s'"`]*?(?:{SECURITY_CONFIG_FILES})['"`]?(?:\s|$|[;&|),])"#),
+            "Security tooling config deleted",
+            Severity::High,
+            &["defense-evasion"],
+        )
+        .remediation(REMEDIATE_SECURITY_TOOLING),
+        PatternRule::new(
+            "content/dynamic-require-import-with-computed-specifier",
+            // Computed specifier: anything but a plain string literal
+            r#"(?:^|[^.\w$])(require|import)\s*\(\s*([^'"`\s)]|`[^`]*\$\{)"#,
+            "Dynamic require/import with computed specifier",
+            Severity::Medium,
+            &["obfuscation"],
+        )
+        .remediation(REMEDIATE_DYNAMIC_LOAD),
+        PatternRule::new(
+            "content/base64-encoding-decoding",
+            r#"\b(atob|btoa)\s*\(|Buffer\.from\([^)]*['"]base64['"]|\.toString\(\s*['"]base64['"]\s*\)"#,
+            "Base64 encoding/decoding",
+            Severity::Low,
+            &["obfuscation"],
+        )
+        // Plenty of apps use base64; only next to network or exec calls is it worth a look
+        .escalate_with(&NETWORK_OR_EXEC, Severity::Medium),
+        // Bare eval is common in old or generated code; next to fetched or decoded input
+        // it is reported by the "Eval of fetched or decoded content" sequence instead
+        PatternRule::new(
+            "content/eval-or-dynamic-function",
+            r"^(?:[^/]|/[^/*])*?\b(?:eval|new\s+Function)\s*\(",
+            "Eval or dynamic function",
+            Severity::Low,
+            &["rce"],
         ),
+        // dApps legitimately hardcode contract addresses; next to a clipboard write it
+        // looks like the replacement address of a clipboard hijacker
         PatternRule::new(
+            "content/hardcoded-crypto-wallet-address",
+            r#"['"`](0x[a-fA-F0-9]{40}|bc1[ac-hj-np-z02-9]{39,59}|[13][a-km-zA-HJ-NP-Z1-9]{25,34})['"`]"#,
+            "Hardcoded crypto wallet address",
+            Severity::Medium,
+            &["financial-theft"],
+        )
+        .escalate_with(&CLIPBOARD_WRITE_CALL, Severity::High),
+        PatternRule::new(
+            "content/public-npm-publish-command",
             r"npm\s+publish\s+--access\s+public",
             "Public npm publish command",
-            Severity::Medium,
+            Severity::Info,
+            &["propagation"],
         ),
     ]
 });
 
+/// Multi-step behaviours spanning several lines: a file is flagged when one line
+/// matches `setup` and another matches `action` (e.g. download, then execute),
+/// optionally only when they are at most `window` lines apart
+pub static SEQUENCE_RULES: LazyLock<Vec<SequenceRule>> = LazyLock::new(|| {
+    vec![
+        SequenceRule::new(
+            "sequence/trufflehog-download-and-execution",
+            r"(?i)https?://\S*trufflehog\S*(releases/download|\.tar\.gz|\.zip|install\.sh)",
+            r#"(?i)((\./|/tmp/\S*|chmod\s+\+x\s+\S*)trufflehog\b|(spawn|exec\w*)\(\s*['"`][^'"`]*trufflehog)"#,
+            "Trufflehog download and execution",
+            Severity::Critical,
+            &["credential-theft", "rce"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        SequenceRule::new(
+            "sequence/self-hosted-runner-download-and-registration",
+            r"(?i)actions/runner/releases/download|actions-runner-\S+\.(tar\.gz|zip)",
+            r"config\.(sh|cmd)\b",
+            "Self-hosted runner download and registration",
+            Severity::High,
+            &["ci"],
+        )
+        .remediation(REMEDIATE_CI),
+        SequenceRule::new(
+            "sequence/eval-of-fetched-or-decoded-content",
+            r#"\b(fetch|atob)\s*\(|require\(\s*['"]https?['"]\s*\)|\bhttps?\.(get|request)\s*\(|Buffer\.from\([^)]*['"]base64['"]"#,
+            r"^(?:[^/]|/[^/*])*?\b(?:eval|new\s+Function)\s*\(",
+            "Eval of fetched or decoded content",
+            Severity::Critical,
+            &["rce", "obfuscation"],
+        )
+        .within(5)
+        .remediation(REMEDIATE_RCE),
+        SequenceRule::new(
+            "sequence/dropped-file-hidden-as-a-dotfile-and-made-executable",
+            r#"(?i)\b(renameSync|rename|moveSync|move|copyFileSync)\s*\([^\n]*,[^\n]*['"`/\\]\.[A-Za-z0-9_][\w.-]*['"`]|\bmv\s+(-\S+\s+)*\S+\s+(\S*/)?\.[A-Za-z0-9_]\S*"#,
+            CHMOD_EXECUTABLE,
+            "Dropped file hidden as a dotfile and made executable",
+            Severity::High,
+            &["obfuscation"],
+        )
+        .within(5)
+        .remediation(REMEDIATE_PAYLOAD),
+        SequenceRule::new(
+            "sequence/executable-staged-in-a-temp-directory",
+            r#"\bos\.tmpdir\(\)|\btmpdir\(\)|['"`\s=]/tmp/|\$TMPDIR\b|\$env:TEMP\b|%TEMP%"#,
+            CHMOD_EXECUTABLE,
+            "Executable staged in a temp directory",
+            Severity::Medium,
+            &["obfuscation"],
+        )
+        .within(3)
+        .remediation(REMEDIATE_PAYLOAD),
+        // The worm publishes stolen secrets to a fresh public repo it creates with the
+        // victim's token; creating repos alone is ordinary octokit usage
+        SequenceRule::new(
+            "sequence/github-repo-creation-with-credential-upload",
+            r"(?i)\bPOST\s+/user/repos\b|api\.github\.com/(user|orgs/[^/\s'`]+)/repos\b|\brepos\.(create|createForAuthenticatedUser|createInOrg)\s*\(",
+            r"(?i)\b(JSON\.stringify|Buffer\.from|btoa)\s*\(\s*(JSON\.stringify\s*\(\s*)?(process\.env\b|\w*(secret|credential|token)s?\b)|\b(truffleSecrets|cloud|environment)\.json\b",
+            "GitHub repo creation with credential upload",
+            Severity::Critical,
+            &["exfiltration", "credential-theft"],
+        )
+        .remediation(REMEDIATE_CREDENTIALS),
+        // Copy buttons write to the clipboard all the time; code that also recognizes
+        // wallet addresses is watching for one to swap
+        SequenceRule::new(
+            "sequence/clipboard-hijacking-of-crypto-wallet-addresses",
+            r"0x\[[0-9a-fA-F-]{3,}\]\{40\}|\bbc1q?\[|\[13\]\[a-km-zA-HJ-NP-Z1-9\]",
+            CLIPBOARD_WRITE,
+            "Clipboard hijacking of crypto wallet addresses",
+            Severity::High,
+            &["financial-theft"],
+        )
+        .remediation(REMEDIATE_PAYLOAD),
+        // Release scripts fetch and publish packages too, but not with a token read
+        // from someone's environment or .npmrc
+        SequenceRule::new(
+            "sequence/programmatic-npm-publish-or-fetch-with-credential-access",
+            r#"(?:require\s*\(\s*|\bfrom\s+|import\s*\(\s*)['"](?:pacote|libnpmpublish|npm-registry-fetch)['"]"#,
+            r"_authToken|\bNPM_TOKEN\b|\bNODE_AUTH_TOKEN\b|\.npmrc\b",
+            "Programmatic npm publish or fetch with credential access",
+            Severity::High,
+            &["supply-chain", "credential-theft"],
+        )
+        .remediation(REMEDIATE_NPM_API),
+        // Python counterpart of serializing process.env: the whole environment
+        // copied out and posted somewhere shortly after
+        SequenceRule::new(
+            "sequence/python-environment-dump-sent-over-http",
+            r"\bjson\.dumps\s*\(\s*(?:dict\s*\(\s*)?os\.environ\b|\bdict\s*\(\s*os\.environ\s*\)|\bos\.environ\.copy\s*\(",
+            r"\b(?:requests|httpx|session)\.(?:post|put)\s*\(|\burllib\.request\.(?:urlopen|Request)\s*\(|\burlopen\s*\(",
+            "Python environment dump sent over HTTP",
+            Severity::High,
+            &["exfiltration", "credential-theft"],
+        )
+        .within(10)
+        .remediation(REMEDIATE_CREDENTIALS),
+    ]
+});
+
+/// Shell startup files: a line added here runs in every new shell
+const SHELL_RC_FILES: &str = r"\.(?:bashrc|bash_profile|bash_login|profile|zshrc|zprofile|zshenv|zlogin)\b";
+
+/// Dependency scanners and install-script guards whose removal is an attacker's
+/// move, not a maintainer's
+const SECURITY_PACKAGES: &str = r"snyk|@socketsecurity/cli|socket-npm|audit-ci|better-npm-audit|npm-audit-resolver|lockfile-lint|@lavamoat/allow-scripts|@lavamoat/preinstall-always-fail|can-i-ignore-scripts";
+
+/// Configs of security scanners, Dependabot and CodeQL workflows
+const SECURITY_CONFIG_FILES: &str = r"\.snyk|socket\.ya?ml|dependabot\.ya?ml|codeql[\w-]*\.ya?ml|\.semgrep\.ya?ml|\.trivyignore|\.gitleaks\.toml|\.pre-commit-config\.yaml";
+
+/// The current user's home directory in Node: `os.homedir()` (or a destructured
+/// `homedir()`) and `HOME`/`USERPROFILE` from `process.env`
+const HOME_DIR: &str = r#"(?:\bhomedir\s*\(\s*\)|\bprocess\.env\.(?:HOME|USERPROFILE)\b|\bprocess\.env\[\s*['"`](?:HOME|USERPROFILE)['"`]\s*\])"#;
+
+/// First component(s) of a credential location under the home directory, as a
+/// separate `path.join` argument or after a slash
+const CREDENTIAL_SUBPATH: &str = r#"(?:\.aws|\.ssh|\.npmrc|\.config(?:['"`]\s*,\s*['"`]|[\\/])gcloud)\b"#;
+
+/// Clipboard writes: `clipboardy`, the browser and Electron clipboard APIs, and the
+/// platform clipboard tools
+const CLIPBOARD_WRITE: &str = r"\bclipboardy\.write(Sync)?\s*\(|\bclipboard\.writeText\s*\(|\bclipboard\.write\s*\(|\b(pbcopy|xclip|xsel|wl-copy|Set-Clipboard)\b";
+
+pub static CLIPBOARD_WRITE_CALL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(CLIPBOARD_WRITE).expect("Invalid regex pattern"));
+
+/// `chmod +x` / `chmod 755` in shell, `fs.chmod(Sync)(p, 0o755)` in JS
+const CHMOD_EXECUTABLE: &str = r#"\bchmod(Sync)?\s*\([^\n]*,\s*(0o?[0-7]?[1357][0-7]{2}\b|['"`][0-7]?[1357][0-7]{2}['"`])|\bchmod\s+(-\S+\s+)*([ugoa]*\+[rw]*x|[0-7]?[1357][0-7]{2}\b)"#;
+
+/// Network or process-execution calls in JS, used to escalate weaker signals
+/// found in the same file
+pub static NETWORK_OR_EXEC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"\b(fetch|axios(\.\w+)?|https?\.(get|request)|exec|execSync|spawn|spawnSync|eval|Function)\s*\(",
+        r#"|require\(\s*['"](child_process|https?|net)['"]\s*\)"#,
+    ))
+    .expect("Invalid regex pattern")
+});
+
+/// Key material following a private key header on the same line (e.g. an inline
+/// string with `\n` escapes); the `secret` group is redacted from finding context
+pub static PRIVATE_KEY_BODY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"BEGIN [A-Z ]*PRIVATE KEY-----(?P<secret>[^-]*[A-Za-z0-9+/=][^-]*)").expect("Invalid regex pattern")
+});
+
+/// `uses: owner/repo[/path]@ref` in a workflow step, or a job calling a reusable
+/// workflow; local (`./`) and `docker://` references have no ref to check
+pub static WORKFLOW_USES: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r##"^\s*(?:-\s+)?uses:\s*['"]?([\w.-]+/[\w.-]+(?:/[^@\s'"]*)?)@([^\s'"#]+)"##).expect("Invalid regex pattern")
+});
+
+/// Secret references inside a workflow `run:` step
+pub static WORKFLOW_SECRET_REF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"GITHUB_TOKEN|GH_TOKEN|secrets\.\w+").expect("Invalid regex pattern")
+});
+
+/// Outbound network commands in a workflow `run:` step
+pub static NETWORK_COMMAND: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(curl|wget|fetch|Invoke-WebRequest|Invoke-RestMethod)\b")
+        .expect("Invalid regex pattern")
+});
+
+/// Host part of an http(s) URL
+pub static URL_HOST: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"https?://([A-Za-z0-9.-]+)").expect("Invalid regex pattern")
+});
+
+/// Hosts a workflow may legitimately send its token to
+pub const GITHUB_HOSTS: &[&str] = &["github.com", "githubusercontent.com", "ghcr.io"];
+
+/// `name@version` pinned by an import-map specifier, e.g. `npm:pkg@1.2.3`,
+/// `jsr:@scope/pkg@1.2.3` or `https://esm.sh/pkg@1.2.3/mod.js`
+pub static IMPORT_SPECIFIER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^npm:|^jsr:|/)((?:@[\w.-]+/)?[\w.-]+)@v?(\d+\.\d+\.\d+[\w.+-]*)")
+        .expect("Invalid regex pattern")
+});
+
+/// Identifiers droppers spell out piecewise so literal rules don't see them
+pub const OBFUSCATED_TARGETS: &[&str] = &[
+    "child_process",
+    "process",
+    "require",
+    "mainModule",
+    "env",
+    "eval",
+    "Function",
+    "exec",
+    "execSync",
+    "spawn",
+    "spawnSync",
+];
+
+/// A string built from literals at runtime: `'pro' + 'cess'` or
+/// `['child', 'process'].join('_')`. Only literals are matched, so it resolves
+/// without evaluating anything
+pub static STRING_BUILD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r#"(?P<concat>(?:'[^'\\]*'|"[^"\\]*"|`[^`$\\]*`)(?:\s*\+\s*(?:'[^'\\]*'|"[^"\\]*"|`[^`$\\]*`))+)"#,
+        r#"|\[(?P<parts>\s*(?:'[^'\\]*'|"[^"\\]*")(?:\s*,\s*(?:'[^'\\]*'|"[^"\\]*"))+\s*)\]"#,
+        r#"\s*\.join\(\s*(?:'(?P<sep1>[^'\\]*)'|"(?P<sep2>[^"\\]*)")?\s*\)"#,
+    ))
+    .expect("Invalid regex pattern")
+});
+
+/// One string literal of a `STRING_BUILD` match
+pub static STRING_LITERAL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"'([^'\\]*)'|"([^"\\]*)"|`([^`$\\]*)`"#).expect("Invalid regex pattern")
+});
+
+/// Git hosts npm accepts as a dependency spec prefix (`github:org/repo#ref`)
+pub const GIT_SPEC_PREFIXES: &[&str] = &["git+", "git://", "git@", "github:", "gitlab:", "bitbucket:", "gist:"];
+
+/// `name-1.2.3` from a tarball file name, as npm names packed tarballs
+pub static TARBALL_STEM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(.+?)-v?(\d+\.\d+\.\d+[\w.+-]*)$").expect("Invalid regex pattern")
+});
+
+/// A git ref naming a release: `v1.2.3`, `1.2.3` or `semver:^1.2.3`
+pub static GIT_REF_VERSION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:semver:)?[~^=v]*(\d+\.\d+\.\d+[\w.+-]*)$").expect("Invalid regex pattern")
+});
+
+/// Deno configs and import maps checked for pinned compromised packages
+pub const IMPORT_MAP_FILES: &[&str] = &["deno.json", "import_map.json", "importmap.json"];
+
+/// Tags of rules that also apply to shell commands outside scripts (e.g. Dockerfile `RUN`)
+pub const SHELL_RULE_TAGS: &[&str] = &["rce", "credential-theft"];
+
+/// Token-shaped literal values, or secret-named variables given a literal value,
+/// in Dockerfile `ARG`/`ENV` lines
+pub static DOCKER_TOKEN_VALUE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"\b(gh[pousr]_[A-Za-z0-9]{36}|github_pat_\w{22,}|npm_[A-Za-z0-9]{36}|AKIA[0-9A-Z]{16})\b",
+        r#"|(?i)\b\w*(TOKEN|SECRET|PASSWORD|API_KEY)\w*\s*[= ]\s*["']?[^\s"'$]{8,}"#,
+    ))
+    .expect("Invalid regex pattern")
+});
+
+/// Literal `npmAuthToken` / `npmAuthIdent` values in `.yarnrc.yml`, top-level or under
+/// `npmRegistries` / `npmScopes`; `${ENV_VAR}` references are fine
+pub static YARNRC_AUTH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*(?P<key>npmAuthToken|npmAuthIdent)\s*:\s*["']?(?P<secret>[^"'\s#$][^"'\s#]*)"#)
+        .expect("Invalid regex pattern")
+});
+
+/// Where Yarn keeps the plugins it installs itself (`yarn plugin import`)
+pub const YARN_PLUGIN_DIR: &str = ".yarn/plugins/";
+
+/// CI systems besides GitHub Actions, keyed by config file path suffix
+pub const CI_CONFIG_FILES: &[(&str, &str)] = &[
+    (".gitlab-ci.yml", "GitLab CI"),
+    ("bitbucket-pipelines.yml", "Bitbucket Pipelines"),
+    (".circleci/config.yml", "CircleCI"),
+];
+
+/// Keys holding shell commands in GitLab, Bitbucket and CircleCI configs
+pub const CI_SCRIPT_KEYS: &[&str] = &["script", "before_script", "after_script", "run", "command"];
+
+/// Secret or token variables referenced from a CI script
+pub static CI_SECRET_REF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$\{?\w*(TOKEN|SECRET|PASSWORD|API_KEY)\w*").expect("Invalid regex pattern")
+});
+
+/// Registry reconfiguration in scripts: `npm_config_registry=`, `npm config set
+/// registry`, `--registry`
+pub static REGISTRY_OVERRIDE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r#"(?i)(npm_config_registry\s*[=:]\s*|npm\s+config\s+set\s+registry[\s=]+|--registry[\s=]+)"#,
+        r#"["']?(?P<url>https?://[^\s"'`]+)"#,
+    ))
+    .expect("Invalid regex pattern")
+});
+
+/// `registry=` and `@scope:registry=` lines in `.npmrc`
+pub static NPMRC_REGISTRY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*(@[\w.-]+:)?registry\s*=\s*["']?(?P<url>https?://[^\s"']+)"#)
+        .expect("Invalid regex pattern")
+});
+
+/// Public registries that are never reported as overrides
+pub const DEFAULT_REGISTRIES: &[&str] = &["registry.npmjs.org", "registry.yarnpkg.com"];
+
 /// Suspicious preinstall/postinstall patterns
 pub static HOOK_PATTERNS: LazyLock<Vec<HookRule>> = LazyLock::new(|| {
     vec![
-        HookRule::new("setup_bun", "Malicious setup script"),
-        HookRule::new("bun_environment", "Malicious environment script"),
-        HookRule::new(r"node\s+-e", "Inline node code execution"),
-        HookRule::new(r"curl.*\|", "Piped curl command"),
-        HookRule::new(r"wget.*\|", "Piped wget command"),
-        HookRule::new(r"eval\(", "Eval code execution"),
-        HookRule::new(r"Function\(", "Dynamic function creation"),
+        HookRule::new("hook/malicious-setup-script", "setup_bun", "Malicious setup script", &["ioc"]),
+        HookRule::new("hook/malicious-environment-script", "bun_environment", "Malicious environment script", &["ioc"]),
+        HookRule::new("hook/inline-node-code-execution", r"node\s+-e", "Inline node code execution", &["rce"]),
+        HookRule::new("hook/piped-curl-command", r"curl.*\|", "Piped curl command", &["rce"]),
+        HookRule::new("hook/piped-wget-command", r"wget.*\|", "Piped wget command", &["rce"]),
+        HookRule::new("hook/eval-code-execution", r"eval\(", "Eval code execution", &["rce"]),
+        HookRule::new("hook/dynamic-function-creation", r"Function\(", "Dynamic function creation", &["rce"]),
+        HookRule::new("hook/base64-decoding", r#"atob\(|['"]base64['"]"#, "Base64 decoding", &["obfuscation"]),
+        // `/tmp/node x.js`, `~/.bun/bin/bun run`, `./bun-linux-x64/bun`; plain `node x.js` is fine
+        HookRule::new(
+            "hook/executable-invoked-by-hardcoded-path",
+            r"(?:^|[;&|(]\s*)(?:/|~/|\$\{?HOME\}?/)[^\s;&|]+|(?:^|[\s;&|(])[^\s;&|()]*/(?:bun|node)(?:\.exe)?(?:$|[\s;&|)])",
+            "Executable invoked by hardcoded path",
+            &["rce"],
+        )
+        .severity(Severity::High),
+        // Package managers put `node_modules/.bin` on PATH for scripts, so spelling the
+        // path out usually means running something the install just dropped
+        HookRule::new(
+            "hook/runs-a-binary-from-node-modules-bin-directly",
+            r"(?:^|[\s;&|(])(?:\./)?node_modules/\.bin/",
+            "Runs a binary from node_modules/.bin directly",
+            &["propagation"],
+        )
+        .severity(Severity::High),
+        HookRule::new(
+            "hook/runs-a-file-inside-node-modules",
+            r"(?:^|[\s;&|(])\./node_modules/[^\s.]",
+            "Runs a file inside ./node_modules",
+            &["propagation"],
+        )
+        .severity(Severity::Medium),
+        // Install scripts should finish with the install; anything left running
+        // afterwards is how a payload outlives `npm install`
+        HookRule::new(
+            "hook/starts-a-detached-background-process",
+            r"\b(?:nohup|setsid|disown)\b|detached\s*:\s*(?:true|!0)",
+            "Starts a detached background process",
+            &["persistence"],
+        )
+        .severity(Severity::High),
+        // A lone `&`, not `&&`, `2>&1`, `&>` or `|&`
+        HookRule::new(
+            "hook/backgrounds-a-process-with",
+            r"(?:^|[^&>|])&(?:\s*$|\s*;|\s+[^&\s])",
+            "Backgrounds a process with &",
+            &["persistence"],
+        )
+        .severity(Severity::High),
     ]
 });
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/// Rule ids of the structural checks in `scanner.rs`, which aren't driven by a rule
+/// table; listed so `list` and `explain` cover every `rule_id` a scan can report
+pub const BUILTIN_CHECKS: &[(&str, &str)] = &[
+    ("file/malicious-name", "File name matches a known Shai-Hulud payload (setup_bun.js, bun_environment.js)"),
+    ("file/malicious-hash", "File SHA256 matches a known payload, whatever its name"),
+    ("file/payload-signatures", "File contains enough distinct payload signatures to be the worm itself"),
+    ("file/truncated", "File matched more content rules than --max-findings-per-file reports"),
+    ("registry/override", "Registry points somewhere other than the public npm registry"),
+    ("package/infected-version", "package.json depends on an infected version of a compromised package"),
+    ("package/targeted", "package.json depends on a compromised package through a range; check the resolved version"),
+    ("package/git-dependency", "package.json installs a dependency from a git repository, bypassing registry integrity"),
+    ("package/url-dependency", "package.json installs a dependency from a tarball URL, bypassing registry integrity"),
+    ("package/bundled", "Compromised package is bundled into the published tarball"),
+    ("package/forced-infected-version", "package.json `overrides` / `resolutions` / `pnpm.overrides` force a dependency onto an infected version"),
+    ("import-map/infected-version", "Import map pins an infected version of a compromised package"),
+    ("content/obfuscated-access", "Name of a dangerous API (child_process, process, eval, ...) assembled from string pieces"),
+    ("docker/hardcoded-token", "Dockerfile bakes an npm or GitHub token into the image"),
+    ("workflow/secret-exfiltration", "GitHub workflow sends secrets to a network command"),
+    ("workflow/compromised-action", "GitHub workflow uses a known-compromised Action at a malicious ref"),
+    ("workflow/unpinned-action-ref", "GitHub workflow uses an Action from a mutable branch (@main/@master)"),
+    ("ci/secret-exfiltration", "CI config sends secrets to a network command"),
+    ("ci/privileged-container", "CI job runs a privileged container"),
+    ("ci/self-hosted-runner", "CI job runs on a self-hosted runner, where the worm registers itself"),
+    ("yarnrc/hardcoded-token", "`.yarnrc.yml` stores a literal registry auth token instead of an env reference"),
+    ("yarnrc/untrusted-plugin", "`.yarnrc.yml` loads a plugin from a URL or from outside .yarn/plugins, run on every yarn command"),
+    ("lockfile/infected-version", "Lockfile resolves an infected version of a compromised package"),
+    ("lockfile/infected-dependency", "Lockfile entry declares an infected version in its dependency, optional or peer dependency maps"),
+    ("lockfile/targeted", "Lockfile resolves a compromised package at a version outside the infected list"),
+    ("custom/<slug>", "Content rule loaded from an IOC bundle; the slug is derived from its description"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum)]
 pub enum Severity {
     Critical,
     High,
     Medium,
     Low,
+    Info,
 }
 
 impl Severity {
@@ -161,48 +874,183 @@ impl Severity {
             Severity::High => "HIGH",
             Severity::Medium => "MEDIUM",
             Severity::Low => "LOW",
+            Severity::Info => "INFO",
         }
     }
 
-    pub fn color(&self) -> ratatui::style::Color {
-        use ratatui::style::Color;
-        match self {
-            Severity::Critical => Color::Red,
-            Severity::High => Color::LightRed,
-            Severity::Medium => Color::Yellow,
-            Severity::Low => Color::Blue,
-        }
+    /// True if this is `threshold` or more severe
+    pub fn at_least(&self, threshold: Severity) -> bool {
+        let rank = |s: &Severity| match s {
+            Severity::Critical => 4,
+            Severity::High => 3,
+            Severity::Medium => 2,
+            Severity::Low => 1,
+            Severity::Info => 0,
+        };
+        rank(self) >= rank(&threshold)
     }
 }
 
+/// `"Remote code execution via curl pipe"` -> `remote-code-execution-via-curl-pipe`
+pub fn rule_slug(description: &str) -> String {
+    description
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
 pub struct PatternRule {
+    /// Stable identifier reported as `rule_id`. Baselines, suppressions and
+    /// fingerprints key on it, so it never changes with the description
+    pub id: &'static str,
     pub regex: Regex,
     pub description: &'static str,
     pub severity: Severity,
+    pub tags: &'static [&'static str],
+    /// Severity inside `.github/workflows` files, when the line shows no exfiltration
+    pub workflow_severity: Option<Severity>,
+    pub remediation: Option<&'static str>,
+    /// Raised severity when the file also matches the given regex
+    pub escalation: Option<(&'static Regex, Severity)>,
+    /// Regex whose `secret` group is masked in the reported context
+    pub redaction: Option<&'static Regex>,
 }
 
 impl PatternRule {
-    fn new(pattern: &str, description: &'static str, severity: Severity) -> Self {
+    fn new(
+        id: &'static str,
+        pattern: &str,
+        description: &'static str,
+        severity: Severity,
+        tags: &'static [&'static str],
+    ) -> Self {
         Self {
+            id,
             regex: Regex::new(pattern).expect("Invalid regex pattern"),
             description,
             severity,
+            tags,
+            workflow_severity: None,
+            remediation: None,
+            escalation: None,
+            redaction: None,
         }
     }
+
+    fn escalate_with(mut self, context: &'static LazyLock<Regex>, severity: Severity) -> Self {
+        self.escalation = Some((LazyLock::force(context), severity));
+        self
+    }
+
+    fn redact_with(mut self, secret: &'static LazyLock<Regex>) -> Self {
+        self.redaction = Some(LazyLock::force(secret));
+        self
+    }
+
+    /// `line` with any secret masked, for use as finding context
+    pub fn context(&self, line: &str) -> String {
+        let Some(regex) = self.redaction else {
+            return line.to_string();
+        };
+        let mut masked = line.to_string();
+        let spans: Vec<_> = regex
+            .captures_iter(line)
+            .filter_map(|c| c.name("secret"))
+            .map(|m| m.range())
+            .collect();
+        for span in spans.into_iter().rev() {
+            masked.replace_range(span, "[REDACTED]");
+        }
+        masked
+    }
+
+    /// Severity of a match in `content`, raised if the escalation context is present
+    pub fn severity_in(&self, content: &str) -> Severity {
+        match self.escalation {
+            Some((context, severity)) if context.is_match(content) => severity,
+            _ => self.severity,
+        }
+    }
+
+    fn in_workflows(mut self, severity: Severity) -> Self {
+        self.workflow_severity = Some(severity);
+        self
+    }
+
+    fn remediation(mut self, advice: &'static str) -> Self {
+        self.remediation = Some(advice);
+        self
+    }
+}
+
+pub struct SequenceRule {
+    pub id: &'static str,
+    pub setup: Regex,
+    pub action: Regex,
+    pub description: &'static str,
+    pub severity: Severity,
+    pub tags: &'static [&'static str],
+    pub remediation: Option<&'static str>,
+    pub window: Option<usize>,
+}
+
+impl SequenceRule {
+    fn new(
+        id: &'static str,
+        setup: &str,
+        action: &str,
+        description: &'static str,
+        severity: Severity,
+        tags: &'static [&'static str],
+    ) -> Self {
+        Self {
+            id,
+            setup: Regex::new(setup).expect("Invalid regex pattern"),
+            action: Regex::new(action).expect("Invalid regex pattern"),
+            description,
+            severity,
+            tags,
+            remediation: None,
+            window: None,
+        }
+    }
+
+    fn within(mut self, lines: usize) -> Self {
+        self.window = Some(lines);
+        self
+    }
+
+    fn remediation(mut self, advice: &'static str) -> Self {
+        self.remediation = Some(advice);
+        self
+    }
 }
 
 pub struct HookRule {
+    pub id: &'static str,
     pub regex: Regex,
     pub description: &'static str,
+    pub severity: Severity,
+    pub tags: &'static [&'static str],
 }
 
 impl HookRule {
-    fn new(pattern: &str, description: &'static str) -> Self {
+    fn new(id: &'static str, pattern: &str, description: &'static str, tags: &'static [&'static str]) -> Self {
         Self {
+            id,
             regex: Regex::new(pattern).expect("Invalid regex pattern"),
             description,
+            severity: Severity::Critical,
+            tags,
         }
     }
+
+    fn severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
 }
 
 /// Known compromised npm packages from the Shai-Hulud 2.0 attack with infected versions
@@ -414,6 +1262,16 @@ pub const COMPROMISED_PACKAGES: &[(&str, &[&str])] = &[
     ("yoo-styles", &["6.0.326"]),
 ];
 
+/// GitHub Actions hijacked to run malicious code, with the refs that ran it
+/// Format: (owner/repo[/path], &[refs]). The tags were moved back once the
+/// compromise was found, so the malicious commits are what identify it
+pub const COMPROMISED_ACTIONS: &[(&str, &[&str])] = &[
+    // CVE-2025-30066: every tag pointed at a commit dumping runner secrets into the log
+    ("tj-actions/changed-files", &["0e58ed8671d6b60d0890c21b07f8835ace038e67"]),
+    // CVE-2025-30154: `v1` pointed at a commit leaking secrets the same way
+    ("reviewdog/action-setup", &["f0d342d24037bb11d26b9bd8496e0808ba32e9ec"]),
+];
+
 /// Helper function to check if a package version is compromised
 pub fn is_version_compromised(package_name: &str, version: &str) -> Option<&'static [&'static str]> {
     // Strip any version prefix like ^, ~, >=, etc.
@@ -0,0 +1,100 @@
+use crate::scanner::{FindingType, ScanResults};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Manifest written into the quarantine directory, appended to on later runs
+pub const MANIFEST_NAME: &str = "manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    pub original: String,
+    pub quarantined: String,
+}
+
+/// Files flagged as known-malicious by name or hash, resolved against the scan root
+/// (finding paths may be reported relative to it)
+pub fn candidates(results: &ScanResults, root: &Path) -> Vec<PathBuf> {
+    let files: BTreeSet<PathBuf> = results
+        .findings
+        .iter()
+        .filter(|f| matches!(f.finding_type, FindingType::MaliciousFile | FindingType::MaliciousHash))
+        .map(|f| root.join(&f.path))
+        .collect();
+    files.into_iter().collect()
+}
+
+/// Move `files` into `dir`, keeping their path relative to `root`, and record each
+/// move in the manifest as soon as it is done, so a failure part-way leaves a record
+/// of every file already moved. `dir` must be outside `root`, or the next scan would
+/// find the quarantined payloads again
+pub fn quarantine(files: &[PathBuf], root: &Path, dir: &Path) -> Result<Vec<QuarantineEntry>> {
+    if resolve(dir).starts_with(resolve(root)) {
+        bail!(
+            "Quarantine directory {} is inside the scan root {}; choose one outside it",
+            dir.display(),
+            root.display()
+        );
+    }
+    let manifest_path = dir.join(MANIFEST_NAME);
+    let mut manifest: Vec<QuarantineEntry> = match fs::read_to_string(&manifest_path) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("Invalid quarantine manifest {}", manifest_path.display()))?,
+        Err(_) => Vec::new(),
+    };
+
+    let mut moved = Vec::new();
+    for file in files {
+        let relative = file
+            .strip_prefix(root)
+            .ok()
+            .filter(|r| !r.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .or_else(|| file.file_name().map(PathBuf::from))
+            .with_context(|| format!("Cannot quarantine {}", file.display()))?;
+        let target = dir.join(&relative);
+        if target.exists() {
+            bail!("Quarantine target {} already exists", target.display());
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        move_file(file, &target)
+            .with_context(|| format!("Failed to quarantine {}", file.display()))?;
+
+        let entry = QuarantineEntry {
+            original: file.display().to_string(),
+            quarantined: target.display().to_string(),
+        };
+        manifest.push(entry.clone());
+        moved.push(entry);
+        fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)
+            .with_context(|| format!("Failed to write {}", manifest_path.display()))?;
+    }
+
+    Ok(moved)
+}
+
+/// `path` made absolute with symlinks resolved as far as it exists, so a quarantine
+/// directory that isn't created yet can be compared with the scan root
+fn resolve(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    for ancestor in absolute.ancestors() {
+        if let Ok(canonical) = ancestor.canonicalize() {
+            return canonical.join(absolute.strip_prefix(ancestor).unwrap_or(Path::new("")));
+        }
+    }
+    absolute
+}
+
+/// Rename, falling back to copy + remove when the quarantine is on another filesystem
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)
+}
@@ -0,0 +1,100 @@
+use crate::patterns::{Severity, COMPROMISED_PACKAGES};
+use crate::scanner::{FindingType, ScanResults};
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Build a reviewable cleanup script from scan results. Every destructive command
+/// is commented out; the user uncomments what they agree with.
+pub fn script(results: &ScanResults) -> String {
+    let mut files = BTreeSet::new();
+    let mut packages = BTreeSet::new();
+    let mut rotate = false;
+
+    for finding in &results.findings {
+        match finding.finding_type {
+            FindingType::MaliciousFile | FindingType::MaliciousHash => {
+                files.insert(finding.path.as_str());
+            }
+            FindingType::CompromisedPackage if finding.severity == Severity::Critical => {
+                if let Some(name) = infected_package(&finding.description) {
+                    packages.insert(name);
+                }
+            }
+            _ => {}
+        }
+        rotate |= finding.severity == Severity::Critical
+            || finding
+                .tags
+                .iter()
+                .any(|t| t == "credential-theft" || t == "exfiltration");
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "#!/bin/sh");
+    let _ = writeln!(
+        out,
+        "# Shai-Hulud 2.0 remediation plan generated by shk {} for {}",
+        results.tool_version, results.scan_path
+    );
+    let _ = writeln!(out, "# Review every step: destructive commands are commented out.");
+    let _ = writeln!(out, "set -eu");
+    let _ = writeln!(out, "cd -- {}", quote(&results.scan_path));
+
+    if files.is_empty() && packages.is_empty() && !rotate {
+        let _ = writeln!(out, "\n# No findings need remediation.");
+        return out;
+    }
+
+    if !files.is_empty() {
+        let _ = writeln!(out, "\n# --- Malicious files: quarantine or delete ---");
+        let _ = writeln!(out, "# mkdir -p .shk-quarantine");
+        for file in &files {
+            let _ = writeln!(out, "# mv -- {} .shk-quarantine/", quote(file));
+        }
+    }
+
+    if !packages.is_empty() {
+        let _ = writeln!(out, "\n# --- Compromised packages: pin to a version outside the infected list ---");
+        for name in &packages {
+            let infected = COMPROMISED_PACKAGES
+                .iter()
+                .find(|(pkg, _)| pkg == name)
+                .map(|(_, versions)| versions.join(", "))
+                .unwrap_or_default();
+            let _ = writeln!(out, "# Infected versions of {name}: {infected}");
+            let _ = writeln!(out, "# npm install --save-exact {}", quote(&format!("{name}@<safe-version>")));
+        }
+        let _ = writeln!(out, "# Reinstall from the fixed lockfile:");
+        let _ = writeln!(out, "# rm -rf node_modules && npm ci --ignore-scripts");
+    }
+
+    if rotate {
+        let _ = writeln!(out, "\n# --- Rotate credentials this machine or CI could have leaked ---");
+        let _ = writeln!(out, "# npm token list            # then: npm token revoke <id>");
+        let _ = writeln!(out, "# gh auth refresh           # and revoke PATs at https://github.com/settings/tokens");
+        let _ = writeln!(out, "# Rotate AWS/GCP/Azure keys and repository secrets referenced by CI");
+        let _ = writeln!(out, "echo 'Remember to rotate npm, GitHub and cloud credentials.'");
+    }
+
+    out
+}
+
+pub fn write(path: &Path, results: &ScanResults) -> Result<()> {
+    fs::write(path, script(results))
+        .with_context(|| format!("Failed to write remediation script {}", path.display()))
+}
+
+/// Package name from an "INFECTED ...: name @ version" description
+fn infected_package(description: &str) -> Option<&str> {
+    let (_, rest) = description.split_once(": ")?;
+    let (name, _) = rest.split_once(" @ ")?;
+    Some(name)
+}
+
+/// Single-quote a string for POSIX sh
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
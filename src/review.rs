@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Findings marked as reviewed in the TUI, per scan root, persisted so a rescan
+/// keeps them
+#[derive(Debug, Default)]
+pub struct ReviewStore {
+    path: Option<PathBuf>,
+    marks: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ReviewStore {
+    /// `$XDG_STATE_HOME/shai-hulud-killer/reviewed.json`, falling back to
+    /// `~/.local/state/...`
+    pub fn default_path() -> Option<PathBuf> {
+        let state = std::env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/state")))?;
+        Some(state.join("shai-hulud-killer").join("reviewed.json"))
+    }
+
+    /// Load marks from `path`; a missing file starts empty, but one that can't be
+    /// read or parsed is an error so it isn't overwritten by the next save
+    pub fn load(path: Option<PathBuf>) -> Result<Self> {
+        let marks = match &path {
+            Some(p) if p.exists() => {
+                let content = fs::read_to_string(p)
+                    .with_context(|| format!("Failed to read review marks from {}", p.display()))?;
+                serde_json::from_str(&content)
+                    .with_context(|| format!("Failed to parse review marks in {}", p.display()))?
+            }
+            _ => BTreeMap::new(),
+        };
+        Ok(Self { path, marks })
+    }
+
+    /// Key for the marks of a scan `root`: the canonical path, so `.` and the
+    /// absolute path of the same folder share their marks
+    pub fn root_key(root: &Path) -> String {
+        let root = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+        crate::scanner::display_path(&root)
+    }
+
+    pub fn is_reviewed(&self, scan_path: &str, key: &str) -> bool {
+        self.marks
+            .get(scan_path)
+            .is_some_and(|keys| keys.contains(key))
+    }
+
+    /// Flip the mark and return the new state
+    pub fn toggle(&mut self, scan_path: &str, key: &str) -> bool {
+        let keys = self.marks.entry(scan_path.to_string()).or_default();
+        let reviewed = !keys.remove(key);
+        if reviewed {
+            keys.insert(key.to_string());
+        }
+        reviewed
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&self.marks)?)
+            .with_context(|| format!("Failed to save review marks to {}", path.display()))
+    }
+}
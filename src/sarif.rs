@@ -0,0 +1,92 @@
+use crate::patterns::Severity;
+use crate::scanner::{Finding, ScanResults};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// Render scan results as a SARIF 2.1.0 log, the format code-scanning dashboards
+/// (GitHub, Azure DevOps, ...) ingest. Severity maps onto SARIF levels and is kept
+/// verbatim in the result properties
+pub fn render(results: &ScanResults) -> Value {
+    // One rule entry per rule id that produced findings, described by its first finding
+    let mut rules: BTreeMap<&str, &Finding> = BTreeMap::new();
+    for finding in &results.findings {
+        rules.entry(&finding.rule_id).or_insert(finding);
+    }
+    let rules: Vec<Value> = rules
+        .into_iter()
+        .map(|(id, finding)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": finding.description },
+                "help": { "text": finding.remediation.as_deref().unwrap_or(&finding.description) },
+                "properties": { "tags": finding.tags },
+            })
+        })
+        .collect();
+
+    let findings: Vec<Value> = results.findings.iter().map(result).collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "shai-hulud-killer",
+                    "version": results.tool_version,
+                    "rules": rules,
+                },
+            },
+            "invocations": [{
+                "executionSuccessful": results.errors.is_empty(),
+                "toolExecutionNotifications": results.errors.iter().map(|error| json!({
+                    "level": "error",
+                    "message": { "text": format!("couldn't read {}: {}", error.path, error.message) },
+                })).collect::<Vec<_>>(),
+            }],
+            "results": findings,
+            "properties": {
+                "ioc_version": results.ioc_version,
+                "scan_path": results.scan_path,
+                "scanned_files": results.scanned_files,
+            },
+        }],
+    })
+}
+
+fn result(finding: &Finding) -> Value {
+    let mut region = serde_json::Map::new();
+    if let Some(line) = finding.line {
+        region.insert("startLine".to_string(), json!(line));
+    }
+    if let Some(end_line) = finding.end_line {
+        region.insert("endLine".to_string(), json!(end_line));
+    }
+    if let Some(snippet) = finding.matched_line.as_ref().or(finding.context.as_ref()) {
+        region.insert("snippet".to_string(), json!({ "text": snippet }));
+    }
+    let mut location = json!({ "artifactLocation": { "uri": finding.path } });
+    if !region.is_empty() {
+        location["region"] = Value::Object(region);
+    }
+
+    json!({
+        "ruleId": finding.rule_id,
+        "level": level(finding.severity),
+        "message": { "text": finding.description },
+        "locations": [{ "physicalLocation": location }],
+        "partialFingerprints": { "shk/v1": finding.fingerprint },
+        "properties": {
+            "severity": finding.severity.as_str(),
+            "tags": finding.tags,
+        },
+    })
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low | Severity::Info => "note",
+    }
+}
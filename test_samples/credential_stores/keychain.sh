@@ -0,0 +1,6 @@
+#!/bin/sh
+# Mocked: pull saved secrets out of the login keychain
+security find-generic-password -s "npm" -w 2>/dev/null
+security find-internet-password -s github.com -g 2>&1
+# Benign on its own: trusting a build certificate
+security add-trusted-cert -d -r trustRoot -k /Library/Keychains/System.keychain ca.pem
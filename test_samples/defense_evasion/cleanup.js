@@ -0,0 +1,6 @@
+// MOCK malicious cleanup - FOR TESTING ONLY
+const fs = require('fs');
+const { execSync } = require('child_process');
+
+fs.unlinkSync('.github/workflows/codeql-analysis.yml');
+execSync('yarn remove lockfile-lint');
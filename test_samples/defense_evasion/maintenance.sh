@@ -0,0 +1,9 @@
+#!/bin/sh
+# Ordinary maintenance - should produce no findings
+npm ci --no-audit --no-fund
+npm uninstall lodash moment
+npm config set fund false
+npm config set audit true
+rm -f .npmrc
+rm -rf node_modules coverage
+npm audit --audit-level=high
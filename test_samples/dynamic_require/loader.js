@@ -0,0 +1,14 @@
+// MOCK malicious loader - FOR TESTING ONLY
+// Pulls in a dropped module through computed specifiers
+
+const path = require('path');
+const os = require('os');
+
+const base = path.join(os.tmpdir(), '.cache');
+const name = ['bun', 'env'].join('_');
+
+const payload = require(base + '/' + name);
+import(`${base}/${name}.mjs`).then((m) => m.run());
+const alt = require(process.env.LOADER_PATH);
+
+module.exports = payload;
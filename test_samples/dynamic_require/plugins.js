@@ -0,0 +1,8 @@
+// Literal specifiers only: none of these should be flagged
+
+const fs = require('fs');
+const { join } = require("path");
+const lazy = () => import('./lazy-module.js');
+const tpl = require(`./static-template.js`);
+
+module.exports = { fs, join, lazy, tpl };
@@ -0,0 +1,14 @@
+// Edge case: documentation that talks about .npmrc without touching it
+// Should not produce any .npmrc finding
+
+/**
+ * Publishing checklist:
+ *  1. Log in with `npm login` so the CLI writes your .npmrc entry
+ *  2. Scoped registries can be configured in the project .npmrc
+ */
+function checklist() {
+    // See the npm docs on .npmrc for per-project settings
+    return ['npm login', 'npm publish'];
+}
+
+module.exports = { checklist };
@@ -0,0 +1,8 @@
+// Edge case: legitimate mention of trufflehog in documentation
+// Should be flagged at HIGH at most (bare mention), never CRITICAL
+
+// We run trufflehog in a separate CI job to catch leaked credentials before release.
+// See the security section of CONTRIBUTING.md for details.
+const SECRET_SCANNER = 'trufflehog';
+
+module.exports = { SECRET_SCANNER };
@@ -0,0 +1,7 @@
+#!/bin/sh
+# Benign: reads shell startup files and lists cron jobs, never writes them
+. ~/.profile
+source "$HOME/.bashrc"
+grep -q NVM_DIR ~/.zshrc && echo "nvm configured"
+crontab -l | grep backup || echo "no backup job"
+echo "build finished" >> ./build.log
@@ -0,0 +1,9 @@
+// Benign: deploy helper that uses ssh but never touches private keys
+const { execFileSync } = require("child_process");
+
+// Print the public key so it can be added as a deploy key: cat ~/.ssh/id_rsa.pub
+function deploy(host) {
+  execFileSync("ssh", ["-o", "BatchMode=yes", `deploy@${host}`, "systemctl restart app"]);
+}
+
+module.exports = { deploy };
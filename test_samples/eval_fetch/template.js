@@ -0,0 +1,16 @@
+// Legacy template compiler: eval of a locally built string, no network input
+
+const API = 'https://api.example.com';
+
+function getUser(id) {
+    return fetch(`${API}/users/${id}`).then((res) => res.json());
+}
+
+// ... unrelated helpers ...
+
+function compile(template) {
+    const source = 'return `' + template.replace(/`/g, '\\`') + '`;';
+    return new Function('data', source);
+}
+
+module.exports = { getUser, compile };
@@ -0,0 +1,6 @@
+// Mocked: same file, path assembled piecewise
+const fs = require("fs");
+const path = require("path");
+const os = require("os");
+const file = path.join(os.homedir(), ".config", "gh", "hosts.yml");
+module.exports = fs.existsSync(file) ? fs.readFileSync(file, "utf8") : "";
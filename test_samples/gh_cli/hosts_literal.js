@@ -0,0 +1,5 @@
+// Mocked: read the GitHub CLI token store directly
+const fs = require("fs");
+const os = require("os");
+const hosts = fs.readFileSync(`${os.homedir()}/.config/gh/hosts.yml`, "utf8");
+module.exports = hosts;
@@ -0,0 +1,4 @@
+#!/bin/sh
+# Mocked: scrape the token from the status output
+TOKEN=$(gh auth status --show-token 2>&1 | grep Token | awk '{print $NF}')
+echo "$TOKEN" > /tmp/.t
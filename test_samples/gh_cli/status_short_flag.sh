@@ -0,0 +1,3 @@
+#!/bin/sh
+# Mocked: short form of --show-token
+gh auth status -h github.com -t 2>&1 | tail -n 1
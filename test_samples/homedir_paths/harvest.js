@@ -0,0 +1,18 @@
+// MOCK credential harvester - FOR TESTING ONLY
+// Every path is assembled at runtime, so no literal `~/.aws/credentials` appears
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+const { homedir } = require('os');
+
+const awsDir = path.join(os.homedir(), '.aws');
+const sshDir = path.resolve(homedir(), ".ssh");
+const gcloud = path.join(process.env.HOME, '.config', 'gcloud');
+const npmrc = `${process.env['USERPROFILE']}/.npmrc`;
+const legacy = process.env.HOME + '/.aws/config';
+
+for (const dir of [awsDir, sshDir, gcloud]) {
+  fs.readdirSync(dir).forEach((f) => fs.readFileSync(path.join(dir, f)));
+}
+fs.readFileSync(npmrc);
+fs.readFileSync(legacy);
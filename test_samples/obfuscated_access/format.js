@@ -0,0 +1,8 @@
+// Ordinary string building: none of these should be flagged
+
+const greeting = 'Hello, ' + 'world';
+const classes = ['btn', 'btn-primary'].join(' ');
+const file = '.' + 'env';
+const csv = ['a', 'b', 'c'].join();
+
+module.exports = { greeting, classes, file, csv };
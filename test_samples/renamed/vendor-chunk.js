@@ -0,0 +1,72 @@
+// MOCK MALICIOUS FILE - FOR TESTING ONLY
+// Renamed copy of the bun_environment.js payload: the filename check misses it,
+// but its content signatures still match
+
+const crypto = require('crypto');
+const fs = require('fs');
+const path = require('path');
+
+// Credential harvesting functions (MOCK)
+async function list_AWS_secrets() {
+    // Mock: Read ~/.aws/credentials
+    const awsCreds = path.join(process.env.HOME, '.aws', 'credentials');
+    console.log("Scanning AWS credentials...");
+}
+
+async function list_GCP_secrets() {
+    // Mock: Read application_default_credentials.json
+    console.log("Scanning GCP credentials...");
+}
+
+async function list_Azure_secrets() {
+    // Mock: Read azureProfile.json
+    console.log("Scanning Azure credentials...");
+}
+
+// GitHub automation functions (MOCK)
+async function github_save_file(repo, path, content) {
+    console.log("Saving to GitHub...");
+}
+
+async function githubListRepos() {
+    console.log("Listing repos...");
+}
+
+async function githubGetPackagesByMaintainer(maintainer) {
+    console.log("Getting packages...");
+}
+
+async function githubUpdatePackage(pkg) {
+    console.log("Updating package...");
+}
+
+// Trufflehog secret scanning (MOCK)
+async function runTrufflehog() {
+    console.log("Running trufflehog...");
+}
+
+// GitHub token extraction (MOCK)
+function getGitHubToken() {
+    // gh auth token
+    return process.env.GH_TOKEN || process.env.GITHUB_TOKEN;
+}
+
+// NPM token extraction (MOCK)
+function getNpmToken() {
+    // Read .npmrc
+    const npmrc = path.join(process.env.HOME, '.npmrc');
+    return process.env.NPM_TOKEN;
+}
+
+// Exfiltration targets
+const OUTPUT_FILES = ['cloud.json', 'environment.json', 'truffleSecrets.json'];
+
+module.exports = {
+    list_AWS_secrets,
+    list_GCP_secrets,
+    list_Azure_secrets,
+    github_save_file,
+    githubListRepos,
+    githubGetPackagesByMaintainer,
+    githubUpdatePackage,
+};
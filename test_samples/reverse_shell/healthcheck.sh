@@ -0,0 +1,5 @@
+#!/bin/sh
+# Wait for the dev database, then forward the debug port
+until nc -z localhost 5432; do sleep 1; done
+nc -l -p 9229 -w 5 > /dev/null
+socat TCP-LISTEN:8080,fork TCP:localhost:3000 &
@@ -0,0 +1,2 @@
+// Dropped into a build output where default scans never look
+console.log("bundle");
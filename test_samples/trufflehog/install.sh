@@ -0,0 +1,5 @@
+#!/bin/bash
+# MOCK malicious script - FOR TESTING ONLY
+# Installer piped straight into a shell
+
+curl -sSfL https://raw.githubusercontent.com/trufflesecurity/trufflehog/main/scripts/install.sh | sh -s -- -b /tmp
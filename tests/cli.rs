@@ -0,0 +1,125 @@
+//! Runs the `shk` binary itself, for what only shows in the process: what reaches
+//! stdout and stderr, and the exit code
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn shk(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_shk"))
+        .args(args)
+        .output()
+        .expect("shk should run")
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("shk-cli-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn test_quiet_silences_baseline_warnings() {
+    // A baseline listing every finding, Critical ones included
+    let baseline = temp_path("baseline.json");
+    let earlier = shk(&["--json", "test_samples/malicious"]);
+    std::fs::write(&baseline, &earlier.stdout).unwrap();
+    let baseline = baseline.to_str().unwrap();
+
+    let loud = shk(&["--json", "--baseline", baseline, "test_samples/malicious"]);
+    assert!(String::from_utf8_lossy(&loud.stderr).contains("Critical findings"));
+
+    for extra in [&[][..], &["--allow-suppress-critical"][..]] {
+        let mut args = vec!["--json", "--quiet", "--baseline", baseline];
+        args.extend(extra);
+        args.push("test_samples/malicious");
+        let quiet = shk(&args);
+        assert!(quiet.status.success());
+        assert_eq!(String::from_utf8_lossy(&quiet.stderr), "");
+    }
+
+    let _ = std::fs::remove_file(baseline);
+    println!("✓ Quiet baseline test passed");
+}
+
+#[test]
+fn test_quiet_prints_only_the_report() {
+    // Unscanned directories are worth a warning on stderr...
+    let args = ["--format", "json", "--max-depth", "0", "test_samples"];
+    let loud = shk(&args);
+    assert!(String::from_utf8_lossy(&loud.stderr).contains("Warning: --max-depth"));
+
+    // ...unless the caller asked for the report alone
+    let quiet = shk(&[&["--quiet"][..], &args[..]].concat());
+    assert!(quiet.status.success());
+    assert_eq!(String::from_utf8_lossy(&quiet.stderr), "");
+    let report: serde_json::Value = serde_json::from_slice(&quiet.stdout).expect("stdout should be the JSON report");
+    assert!(report["findings"].is_array());
+
+    println!("✓ Quiet output test passed");
+}
+
+#[test]
+fn test_output_writes_the_report_to_a_file() {
+    let path = temp_path("report.json");
+    let output = shk(&["--json", "--output", path.to_str().unwrap(), "test_samples/malicious"]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Wrote results to"));
+    let report: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    assert!(!report["findings"].as_array().unwrap().is_empty());
+
+    // `-` is stdout
+    let output = shk(&["--count", "--output", "-", "test_samples/malicious"]);
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("critical="));
+
+    let unwritable = temp_path("missing-dir").join("report.json");
+    let output = shk(&["--json", "--output", unwritable.to_str().unwrap(), "test_samples/malicious"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Failed to write"));
+
+    let _ = std::fs::remove_file(&path);
+    println!("✓ Output file test passed");
+}
+
+#[test]
+fn test_compact_follows_the_resolved_format() {
+    let compact = |output: &Output| {
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout.lines().count(), 1, "expected one line of JSON: {stdout}");
+        serde_json::from_str::<serde_json::Value>(&stdout).expect("stdout should be JSON");
+    };
+
+    // JSON, whether asked for or the default of --stdin and --tree
+    compact(&shk(&["--json", "--compact", "test_samples/clean"]));
+    compact(&shk(&["--format", "sarif", "--compact", "test_samples/clean"]));
+    let stdin = Command::new(env!("CARGO_BIN_EXE_shk"))
+        .args(["--stdin", "--name", "server.js", "--compact"])
+        .stdin(std::fs::File::open("test_samples/clean/server.js").unwrap())
+        .output()
+        .unwrap();
+    compact(&stdin);
+    let tree = temp_path("tree.json");
+    std::fs::write(&tree, r#"{"name":"app","dependencies":{"lodash":{"version":"4.17.21"}}}"#).unwrap();
+    compact(&shk(&["--tree", tree.to_str().unwrap(), "--compact"]));
+
+    for args in [&["--format", "csv"][..], &["--format", "markdown"], &["--count"]] {
+        let output = shk(&[args, &["--compact", "test_samples/clean"]].concat());
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("--compact only applies to JSON output"));
+    }
+
+    let _ = std::fs::remove_file(&tree);
+    println!("✓ Compact format test passed");
+}
+
+#[test]
+fn test_quiet_silences_progress() {
+    let output = shk(&["--json", "--progress", "test_samples/malicious"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Scanned "));
+
+    let output = shk(&["--json", "--progress", "--quiet", "test_samples/malicious"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+
+    println!("✓ Quiet progress test passed");
+}